@@ -0,0 +1,141 @@
+use serde::Deserialize;
+use std::{collections::HashSet, fmt};
+
+use crate::annotations;
+
+/// What an HTTP client is allowed to do, going by the token it connected with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Can only retrieve data.
+    Viewer,
+    /// Can also create, update, activate and run workflows.
+    Editor,
+    /// Can do anything, including deleting workflows, executions and tags.
+    Admin,
+}
+
+impl Role {
+    /// The least a client needs to be allowed to call a tool.
+    pub fn required_for(tool_name: &str) -> Self {
+        match tool_name {
+            "delete_execution_by_id"
+            | "delete_workflow_by_id"
+            | "delete_workflows_bulk"
+            | "delete_tag_by_id"
+            | "prune_executions" => Self::Admin,
+            _ if annotations::hints(tool_name).read_only => Self::Viewer,
+            _ => Self::Editor,
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Viewer => f.write_str("viewer"),
+            Self::Editor => f.write_str("editor"),
+            Self::Admin => f.write_str("admin"),
+        }
+    }
+}
+
+/// Which tools a session's clients can see and call.
+///
+/// Tools that aren't allowed are left out of `tools/list`, and calling one anyway is rejected.
+#[derive(Debug, Clone)]
+pub struct ToolAccess {
+    role: Role,
+    read_only: bool,
+    /// When set, only these tools are available.
+    allow: Option<HashSet<String>>,
+    deny: HashSet<String>,
+}
+
+impl Default for ToolAccess {
+    fn default() -> Self {
+        Self {
+            role: Role::Admin,
+            read_only: false,
+            allow: None,
+            deny: HashSet::new(),
+        }
+    }
+}
+
+impl ToolAccess {
+    pub fn role(mut self, role: Role) -> Self {
+        self.role = role;
+        self
+    }
+
+    /// Only allows tools that don't change anything in n8n, going by their read-only hint.
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    pub fn allow(mut self, tools: impl IntoIterator<Item = String>) -> Self {
+        self.allow = Some(tools.into_iter().collect());
+        self
+    }
+
+    pub fn deny(mut self, tools: impl IntoIterator<Item = String>) -> Self {
+        self.deny.extend(tools);
+        self
+    }
+
+    /// Whether only retrieval tools are available, either because of read-only mode or the client's role.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only || self.role == Role::Viewer
+    }
+
+    pub fn allows(&self, tool_name: &str) -> bool {
+        self.denial(tool_name).is_none()
+    }
+
+    /// Why a tool can't be called, if it can't, for the error returned when a client tries to anyway.
+    pub fn denial(&self, tool_name: &str) -> Option<String> {
+        if self.read_only && !annotations::hints(tool_name).read_only {
+            return Some(format!(
+                "The {tool_name} tool isn't available, as the server is in read-only mode"
+            ));
+        }
+
+        let allowed = self
+            .allow
+            .as_ref()
+            .is_none_or(|allow| allow.contains(tool_name));
+        if !allowed || self.deny.contains(tool_name) {
+            return Some(format!(
+                "The {tool_name} tool has been disabled on this server"
+            ));
+        }
+
+        let required = Role::required_for(tool_name);
+        if self.role < required {
+            return Some(format!(
+                "The {tool_name} tool needs the {required} role, but this client has the {} role",
+                self.role
+            ));
+        }
+
+        None
+    }
+
+    /// The tools named in the allow and deny lists that don't exist, which are most likely typos.
+    pub fn unknown_tools<'a>(&'a self, known: &HashSet<String>) -> Vec<&'a str> {
+        let mut unknown: Vec<&str> = self
+            .allow
+            .iter()
+            .flatten()
+            .chain(&self.deny)
+            .filter(|name| !known.contains(*name))
+            .map(String::as_str)
+            .collect();
+        unknown.sort();
+        unknown.dedup();
+
+        unknown
+    }
+}
@@ -0,0 +1,128 @@
+use rmcp::model::Tool;
+
+/// The MCP tool annotation hints for a tool.
+///
+/// rmcp 0.1.5's `Tool` has no `annotations` field, so the hints are appended to each tool's description
+/// instead. They can be moved onto the tool itself once rmcp supports them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToolHints {
+    pub read_only: bool,
+    pub destructive: bool,
+    pub idempotent: bool,
+}
+
+const READ_ONLY: ToolHints = ToolHints {
+    read_only: true,
+    destructive: false,
+    idempotent: true,
+};
+
+const fn writes(destructive: bool, idempotent: bool) -> ToolHints {
+    ToolHints {
+        read_only: false,
+        destructive,
+        idempotent,
+    }
+}
+
+/// Looks up the hints for a tool. Tools that aren't listed get the MCP defaults, which assume the worst.
+pub fn hints(tool_name: &str) -> ToolHints {
+    match tool_name {
+        "retrieve_all_executions"
+        | "retrieve_executions_in_range"
+        | "list_running_executions"
+        | "retrieve_execution_by_id"
+        | "analyze_execution_failure"
+        | "compare_executions"
+        | "latest_executions_overview"
+        | "execution_metrics"
+        | "execution_durations"
+        | "search_executions"
+        | "retrieve_workflows"
+        | "retrieve_workflow_by_id"
+        | "get_workflow_static_data"
+        | "get_workflow_tags_by_workflow_id"
+        | "get_license_info"
+        | "retrieve_tags"
+        | "retrieve_tag_by_id"
+        | "validate_workflow_json"
+        | "fill_node_defaults"
+        | "search_workflows"
+        | "workflow_dependencies"
+        | "list_workflow_versions"
+        | "lint_workflow"
+        | "scaffold_workflow"
+        | "render_workflow_diagram"
+        | "get_workflow_docs"
+        | "workflow_stats"
+        | "compare_instances"
+        | "find_unused_credentials"
+        | "find_disabled_nodes"
+        | "find_workflows_without_error_workflow"
+        | "check_policies"
+        | "find_duplicate_workflows" => READ_ONLY,
+
+        "create_workflow" | "create_tag" | "add_node_to_workflow" => writes(false, false),
+
+        // Only writes to the server's backup directory, replacing earlier backups
+        "export_workflows" => writes(false, true),
+
+        // Only writes new files to the server's backup directory
+        "export_executions" => writes(false, false),
+
+        "activate_workflow_by_id"
+        | "deactivate_workflow_by_id"
+        | "update_workflow_fields"
+        | "rename_workflow"
+        | "set_active_bulk"
+        | "update_workflow_settings"
+        | "set_error_workflow"
+        | "archive_workflow"
+        | "unarchive_workflow"
+        | "set_node_disabled"
+        | "update_node_parameters"
+        | "tag_workflow_by_names"
+        | "tag_workflows_bulk"
+        | "update_tag_by_id" => writes(false, true),
+
+        "delete_execution_by_id"
+        | "prune_executions"
+        | "delete_workflow_by_id"
+        | "delete_workflows_bulk"
+        | "delete_tag_by_id"
+        | "update_workflow_by_id"
+        | "set_workflow_static_data"
+        | "set_pinned_data"
+        | "clear_pinned_data"
+        | "remove_node_from_workflow"
+        | "convert_trigger"
+        | "rewire_connection"
+        | "rollback_workflow"
+        | "sync_workflow_to_instance"
+        | "update_workflow_tags_by_workflow_id"
+        | "import_workflows" => writes(true, true),
+
+        // Running a workflow (run_workflow, run_workflow_test and rerun_execution) can do anything the workflow does
+        _ => writes(true, false),
+    }
+}
+
+/// Appends a tool's hints to its description, e.g. `Hints: readOnlyHint, idempotentHint`.
+pub fn annotate(mut tool: Tool) -> Tool {
+    let hints = hints(&tool.name);
+
+    let names: Vec<&str> = [
+        (hints.read_only, "readOnlyHint"),
+        (hints.destructive, "destructiveHint"),
+        (hints.idempotent, "idempotentHint"),
+    ]
+    .into_iter()
+    .filter_map(|(set, name)| set.then_some(name))
+    .collect();
+
+    if !names.is_empty() {
+        tool.description = format!("{}\n\nHints: {}", tool.description, names.join(", ")).into();
+    }
+
+    tool
+}
@@ -0,0 +1,101 @@
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+use crate::{error::ServerError, id::WorkflowId, workflow::Workflow};
+
+/// The file a workflow is backed up to. It's named by ID alone, so renaming a workflow doesn't leave a second backup
+/// of it behind.
+pub fn file_name(workflow_id: &WorkflowId) -> String {
+    format!("{workflow_id}.json")
+}
+
+/// Writes a workflow to `dir`, replacing any earlier backup of it.
+pub async fn write_workflow(
+    dir: &Path,
+    workflow_id: &WorkflowId,
+    workflow: &Value,
+) -> Result<PathBuf, ServerError> {
+    let path = dir.join(file_name(workflow_id));
+    write_json(&path, workflow).await?;
+
+    Ok(path)
+}
+
+/// Writes `value` to `path` as pretty-printed JSON.
+pub async fn write_json(path: &Path, value: &Value) -> Result<(), ServerError> {
+    write_file(path, &serde_json::to_vec_pretty(value)?).await
+}
+
+/// Writes `contents` to `path`.
+///
+/// The file is written next to `path` and then renamed over it, so a write that's interrupted never leaves a
+/// half-written file behind.
+pub async fn write_file(path: &Path, contents: &[u8]) -> Result<(), ServerError> {
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".partial");
+    let partial = PathBuf::from(partial);
+
+    tokio::fs::write(&partial, contents)
+        .await
+        .map_err(|err| ServerError::file(&partial, err))?;
+    tokio::fs::rename(&partial, path)
+        .await
+        .map_err(|err| ServerError::file(path, err))?;
+
+    Ok(())
+}
+
+/// Reads every workflow backup in `dir`, in file name order, along with the name of the file each came from. A file
+/// that can't be read or isn't a workflow doesn't stop the rest from being read.
+pub async fn read_workflows(
+    dir: &Path,
+) -> Result<Vec<(String, Result<Workflow, ServerError>)>, ServerError> {
+    let mut entries = tokio::fs::read_dir(dir)
+        .await
+        .map_err(|err| ServerError::file(dir, err))?;
+
+    let mut files = Vec::new();
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|err| ServerError::file(dir, err))?
+    {
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        let is_file = entry
+            .file_type()
+            .await
+            .is_ok_and(|file_type| file_type.is_file());
+        if is_file && file_name.ends_with(".json") {
+            files.push(file_name);
+        }
+    }
+    files.sort();
+
+    let mut workflows = Vec::new();
+    for file in files {
+        let workflow = read_workflow(&dir.join(&file)).await;
+        workflows.push((file, workflow));
+    }
+
+    Ok(workflows)
+}
+
+pub async fn read_workflow(path: &Path) -> Result<Workflow, ServerError> {
+    let contents = tokio::fs::read(path)
+        .await
+        .map_err(|err| ServerError::file(path, err))?;
+
+    Ok(serde_json::from_slice(&contents)?)
+}
+
+/// Backups are only ever read from the backup directory itself, so a file name that could point anywhere else is
+/// rejected.
+pub fn validate_file_name(file_name: &str) -> Result<(), ServerError> {
+    if Path::new(file_name).file_name() != Some(file_name.as_ref()) || file_name.starts_with('.') {
+        return Err(ServerError::validation(format!(
+            "'{file_name}' isn't the name of a file in the backup directory"
+        )));
+    }
+
+    Ok(())
+}
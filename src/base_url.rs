@@ -0,0 +1,71 @@
+use std::fmt;
+
+const API_PREFIX: &str = "/api/v1";
+
+/// The root URL of an n8n instance, normalized so paths can be joined onto it safely.
+///
+/// `N8N_BASE_URL` is accepted with or without a trailing slash, with or without `/api/v1` on the end, and
+/// with a subpath for instances that aren't served from the root of their domain
+/// (e.g. `https://example.com/n8n/api/v1/` becomes `https://example.com/n8n`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BaseUrl(String);
+
+impl BaseUrl {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let url = reqwest::Url::parse(raw.trim())
+            .map_err(|err| format!("{raw} isn't a valid URL: {err}"))?;
+
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!("{raw} must be an http:// or https:// URL"));
+        }
+        if url.query().is_some() || url.fragment().is_some() {
+            return Err(format!("{raw} can't have a query string or fragment"));
+        }
+
+        let mut root = url.as_str().trim_end_matches('/');
+        if let Some(stripped) = root.strip_suffix(API_PREFIX) {
+            root = stripped.trim_end_matches('/');
+        }
+
+        Ok(Self(root.to_string()))
+    }
+
+    /// Whether this is an n8n Cloud instance, which are all served from `{name}.app.n8n.cloud`.
+    pub fn is_n8n_cloud(&self) -> bool {
+        reqwest::Url::parse(&self.0)
+            .ok()
+            .and_then(|url| url.host_str().map(|host| host.ends_with(".n8n.cloud")))
+            .unwrap_or(false)
+    }
+
+    /// Joins a path onto the instance's root, regardless of whether it starts with a slash.
+    pub fn join(&self, path: &str) -> String {
+        format!("{}/{}", self.0, path.trim_start_matches('/'))
+    }
+
+    /// A route of n8n's public API, e.g. `api("workflows")` for `{root}/api/v1/workflows`.
+    pub fn api(&self, path: &str) -> String {
+        self.join(&format!("{API_PREFIX}/{}", path.trim_start_matches('/')))
+    }
+
+    /// A route of the internal API the n8n editor uses, which isn't covered by the API key.
+    pub fn rest(&self, path: &str) -> String {
+        self.join(&format!("rest/{}", path.trim_start_matches('/')))
+    }
+
+    /// The production URL of a webhook trigger.
+    pub fn webhook(&self, path: &str) -> String {
+        self.join(&format!("webhook/{}", path.trim_start_matches('/')))
+    }
+
+    /// The URL a webhook trigger listens on while its workflow is being tested in the editor.
+    pub fn test_webhook(&self, path: &str) -> String {
+        self.join(&format!("webhook-test/{}", path.trim_start_matches('/')))
+    }
+}
+
+impl fmt::Display for BaseUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
@@ -0,0 +1,70 @@
+use clap::{Args, Parser, Subcommand};
+use std::{net::SocketAddr, path::PathBuf};
+
+use crate::config::{ServerConfig, Transport};
+
+#[derive(Parser)]
+#[command(version, about = "An MCP server for n8n")]
+pub struct Cli {
+    /// A TOML or YAML config file. Environment variables and flags override anything set in it
+    #[arg(long, global = true, env = "N8N_MCP_CONFIG")]
+    pub config: Option<PathBuf>,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Running without a subcommand is the same as running `serve`
+    #[command(flatten)]
+    pub serve: ServeArgs,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Run the MCP server (the default)
+    Serve(ServeArgs),
+    /// Check the configuration, and that the n8n instance can be reached with the configured API key
+    Check,
+    /// List the tools the server provides
+    ListTools {
+        /// Print the full tool definitions, including their input schemas, as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print the server's version
+    Version,
+}
+
+#[derive(Args)]
+pub struct ServeArgs {
+    /// How MCP clients connect to the server [default: http]
+    #[arg(long, value_enum)]
+    pub transport: Option<Transport>,
+    /// The address to listen on when using the HTTP transport [default: 127.0.0.1:8000]
+    #[arg(long)]
+    pub bind: Option<SocketAddr>,
+    /// The path of the socket to listen on when using the Unix transport
+    #[arg(long)]
+    pub socket: Option<PathBuf>,
+    /// A PEM-encoded certificate chain to serve HTTPS with, instead of plain HTTP
+    #[arg(long, requires = "tls_key")]
+    pub tls_cert: Option<PathBuf>,
+    /// The PEM-encoded private key for the certificate passed to --tls-cert
+    #[arg(long, requires = "tls_cert")]
+    pub tls_key: Option<PathBuf>,
+    /// Let each HTTP client connect with its own n8n API key (X-N8N-API-KEY header) and base URL (X-N8N-BASE-URL header)
+    #[arg(long)]
+    pub multi_tenant: bool,
+}
+
+impl ServeArgs {
+    pub fn overrides(self) -> ServerConfig {
+        ServerConfig {
+            transport: self.transport,
+            bind: self.bind,
+            socket: self.socket,
+            tls_cert: self.tls_cert,
+            tls_key: self.tls_key,
+            bearer_token: None,
+            clients: Vec::new(),
+            multi_tenant: self.multi_tenant.then_some(true),
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use anyhow::Context;
+use axum::http::{HeaderMap, HeaderValue, header::AUTHORIZATION};
+use base64::prelude::*;
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    fmt,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use crate::base_url::BaseUrl;
+
+/// What the server needs to talk to an n8n instance.
+#[derive(Clone)]
+pub struct Connection {
+    pub api_key: String,
+    pub base_url: BaseUrl,
+    pub deployment: DeploymentKind,
+    /// Where webhooks are served, when that isn't the instance's own URL.
+    pub webhook_base_url: Option<BaseUrl>,
+    pub n8n_user: Option<String>,
+    pub n8n_password: Option<String>,
+    pub timeout: Option<Duration>,
+}
+
+/// Whether an instance is hosted by n8n or by whoever runs it, which changes what can go wrong when connecting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum DeploymentKind {
+    Cloud,
+    SelfHosted,
+}
+
+impl DeploymentKind {
+    pub fn detect(base_url: &BaseUrl) -> Self {
+        if base_url.is_n8n_cloud() {
+            Self::Cloud
+        } else {
+            Self::SelfHosted
+        }
+    }
+}
+
+impl fmt::Display for DeploymentKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cloud => f.write_str("n8n Cloud"),
+            Self::SelfHosted => f.write_str("self-hosted"),
+        }
+    }
+}
+
+/// The other n8n instances a server can reach, by the name they're configured under.
+pub type Instances = Arc<BTreeMap<String, N8nClient>>;
+
+/// An HTTP client for an n8n instance, shared by every session using that instance.
+///
+/// The connection behind it can be replaced at runtime (e.g. after an API key is rotated), and every session picks up
+/// the new one from its next request without having to reconnect.
+#[derive(Clone)]
+pub struct N8nClient(Arc<RwLock<Inner>>);
+
+struct Inner {
+    client: reqwest::Client,
+    base_url: BaseUrl,
+    deployment: DeploymentKind,
+    webhook_base_url: BaseUrl,
+}
+
+impl N8nClient {
+    /// Fails if the API key or basic auth credentials can't be sent as a header, e.g. because they contain a newline.
+    pub fn new(connection: Connection) -> anyhow::Result<Self> {
+        Ok(Self(Arc::new(RwLock::new(Inner::new(connection)?))))
+    }
+
+    /// Switches every session over to a new connection. Requests already in flight finish on the old one.
+    pub fn replace(&self, connection: Connection) -> anyhow::Result<()> {
+        let inner = Inner::new(connection)?;
+        *self.0.write().unwrap() = inner;
+
+        Ok(())
+    }
+
+    pub fn http(&self) -> reqwest::Client {
+        self.0.read().unwrap().client.clone()
+    }
+
+    pub fn base_url(&self) -> BaseUrl {
+        self.0.read().unwrap().base_url.clone()
+    }
+
+    pub fn deployment(&self) -> DeploymentKind {
+        self.0.read().unwrap().deployment
+    }
+
+    /// The root that webhook paths are joined onto, which is the instance's own URL unless configured otherwise.
+    pub fn webhook_base_url(&self) -> BaseUrl {
+        self.0.read().unwrap().webhook_base_url.clone()
+    }
+}
+
+impl Inner {
+    fn new(connection: Connection) -> anyhow::Result<Self> {
+        let Connection {
+            api_key,
+            base_url,
+            deployment,
+            webhook_base_url,
+            n8n_user,
+            n8n_password,
+            timeout,
+        } = connection;
+
+        let mut headers = HeaderMap::new();
+        let api_key = HeaderValue::from_str(&api_key)
+            .context("The n8n API key contains characters that can't be sent in a header")?;
+        headers.insert("X-N8N-API-KEY", api_key);
+
+        // Self-hosted instances are often put behind basic auth, which covers webhooks as well as the API
+        if let Some(user) = n8n_user {
+            let credentials = format!("{user}:{}", n8n_password.unwrap_or_default());
+            let mut authorization =
+                HeaderValue::from_str(&format!("Basic {}", BASE64_STANDARD.encode(credentials)))
+                    .context("The n8n basic auth user can't be sent in a header")?;
+            authorization.set_sensitive(true);
+            headers.insert(AUTHORIZATION, authorization);
+        }
+
+        let mut client = reqwest::Client::builder().default_headers(headers);
+        if let Some(timeout) = timeout {
+            client = client.timeout(timeout);
+        }
+
+        Ok(Self {
+            client: client.build()?,
+            webhook_base_url: webhook_base_url.unwrap_or_else(|| base_url.clone()),
+            base_url,
+            deployment,
+        })
+    }
+}
@@ -0,0 +1,207 @@
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+
+use crate::{
+    id::WorkflowId,
+    workflow::{Node, Workflow},
+};
+
+/// Settings that hold the ID of another workflow, which is different on every instance even when it's the same
+/// workflow.
+const INSTANCE_SPECIFIC_SETTINGS: &[&str] = &["errorWorkflow"];
+
+/// How the workflows on two instances differ, matched up by name.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Comparison {
+    pub identical: usize,
+    pub only_in_source: Vec<Summary>,
+    pub only_in_target: Vec<Summary>,
+    pub differing: Vec<Differing>,
+    /// Names used by more than one workflow on either instance, which can't be matched up and so aren't compared.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ambiguous: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Summary {
+    pub id: Option<WorkflowId>,
+    pub name: String,
+    pub active: Value,
+}
+
+/// A workflow on both instances whose definitions differ.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Differing {
+    pub name: String,
+    pub source_id: Option<WorkflowId>,
+    pub target_id: Option<WorkflowId>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub nodes_only_in_source: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub nodes_only_in_target: Vec<String>,
+    /// Nodes on both, with the fields that differ, e.g. `parameters` or `typeVersion`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub changed_nodes: BTreeMap<String, Vec<String>>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub connections_differ: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub changed_settings: Vec<String>,
+}
+
+impl Differing {
+    fn is_empty(&self) -> bool {
+        self.nodes_only_in_source.is_empty()
+            && self.nodes_only_in_target.is_empty()
+            && self.changed_nodes.is_empty()
+            && !self.connections_differ
+            && self.changed_settings.is_empty()
+    }
+}
+
+/// Matches up the workflows on two instances by name and compares each pair.
+///
+/// IDs and node positions are different on every instance, so they're left out of the comparison, and credentials
+/// are compared by name rather than ID.
+pub fn compare(source: &[Workflow], target: &[Workflow]) -> Comparison {
+    let source_by_name = by_name(source);
+    let target_by_name = by_name(target);
+
+    let mut comparison = Comparison::default();
+    for (name, sources) in &source_by_name {
+        match (
+            sources.as_slice(),
+            target_by_name.get(name).map(Vec::as_slice),
+        ) {
+            ([source], Some([target])) => {
+                let differing = differences(source, target);
+                if differing.is_empty() {
+                    comparison.identical += 1;
+                } else {
+                    comparison.differing.push(differing);
+                }
+            }
+            (sources, None) => comparison
+                .only_in_source
+                .extend(sources.iter().map(|workflow| summary(workflow))),
+            _ => comparison.ambiguous.push(name.to_string()),
+        }
+    }
+    for (name, targets) in &target_by_name {
+        if source_by_name.contains_key(name) {
+            continue;
+        }
+        comparison
+            .only_in_target
+            .extend(targets.iter().map(|workflow| summary(workflow)));
+    }
+
+    comparison
+}
+
+/// The workflows with each name, ignoring any whitespace around it.
+fn by_name(workflows: &[Workflow]) -> BTreeMap<&str, Vec<&Workflow>> {
+    let mut by_name: BTreeMap<&str, Vec<&Workflow>> = BTreeMap::new();
+    for workflow in workflows {
+        by_name
+            .entry(workflow.name.trim())
+            .or_default()
+            .push(workflow);
+    }
+
+    by_name
+}
+
+fn summary(workflow: &Workflow) -> Summary {
+    Summary {
+        id: workflow.id.clone(),
+        name: workflow.name.clone(),
+        active: workflow.extra.get("active").cloned().unwrap_or(Value::Null),
+    }
+}
+
+fn differences(source: &Workflow, target: &Workflow) -> Differing {
+    let source_nodes: BTreeMap<&str, &Node> = source
+        .nodes
+        .iter()
+        .map(|node| (node.name.as_str(), node))
+        .collect();
+    let target_nodes: BTreeMap<&str, &Node> = target
+        .nodes
+        .iter()
+        .map(|node| (node.name.as_str(), node))
+        .collect();
+
+    let mut changed_nodes = BTreeMap::new();
+    for (name, source_node) in &source_nodes {
+        if let Some(target_node) = target_nodes.get(name) {
+            let fields = changed_fields(&definition(source_node), &definition(target_node));
+            if !fields.is_empty() {
+                changed_nodes.insert(name.to_string(), fields);
+            }
+        }
+    }
+
+    Differing {
+        name: source.name.clone(),
+        source_id: source.id.clone(),
+        target_id: target.id.clone(),
+        nodes_only_in_source: only_in(&source_nodes, &target_nodes),
+        nodes_only_in_target: only_in(&target_nodes, &source_nodes),
+        changed_nodes,
+        connections_differ: json!(source.connections) != json!(target.connections),
+        changed_settings: changed_fields(&settings(source), &settings(target)),
+    }
+}
+
+fn only_in(nodes: &BTreeMap<&str, &Node>, other: &BTreeMap<&str, &Node>) -> Vec<String> {
+    nodes
+        .keys()
+        .filter(|name| !other.contains_key(*name))
+        .map(|name| name.to_string())
+        .collect()
+}
+
+/// What a node does, without the fields that are particular to the instance it's on. Credentials are kept by name.
+fn definition(node: &Node) -> Map<String, Value> {
+    let mut definition = match json!(node) {
+        Value::Object(definition) => definition,
+        _ => Map::new(),
+    };
+    definition.remove("id");
+    definition.remove("position");
+
+    if let Some(Value::Object(credentials)) = definition.get_mut("credentials") {
+        for credential in credentials.values_mut() {
+            *credential = credential["name"].clone();
+        }
+    }
+
+    definition
+}
+
+fn settings(workflow: &Workflow) -> Map<String, Value> {
+    let mut settings = workflow.settings.clone();
+    for key in INSTANCE_SPECIFIC_SETTINGS {
+        settings.remove(*key);
+    }
+
+    settings
+}
+
+/// The keys whose values differ between two objects, including keys only one of them has.
+fn changed_fields(source: &Map<String, Value>, target: &Map<String, Value>) -> Vec<String> {
+    let mut fields: Vec<String> = source
+        .keys()
+        .chain(target.keys())
+        .filter(|key| source.get(*key) != target.get(*key))
+        .cloned()
+        .collect();
+    fields.sort();
+    fields.dedup();
+
+    fields
+}
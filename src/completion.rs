@@ -0,0 +1,92 @@
+use rmcp::model::{CompletionInfo, Reference};
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::resources::ResourceUri;
+
+const CACHE_TTL: Duration = Duration::from_secs(60);
+// The MCP spec caps completion results at 100 values
+const MAX_VALUES: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompletionKind {
+    Workflow,
+    Execution,
+    Tag,
+}
+
+impl CompletionKind {
+    /// Works out which kind of ID an argument takes, from either a prompt argument's name or a resource template.
+    pub fn for_argument(reference: &Reference, argument: &str) -> Option<Self> {
+        match reference {
+            Reference::Prompt(_) => match argument {
+                "workflow_id" => Some(Self::Workflow),
+                "execution_id" => Some(Self::Execution),
+                "tag_id" => Some(Self::Tag),
+                _ => None,
+            },
+            Reference::Resource(resource) => {
+                if argument != "id" {
+                    return None;
+                }
+
+                match ResourceUri::parse(&resource.uri)? {
+                    ResourceUri::Workflow(_) => Some(Self::Workflow),
+                    ResourceUri::Execution(_) => Some(Self::Execution),
+                    ResourceUri::Tag(_) => Some(Self::Tag),
+                }
+            }
+        }
+    }
+}
+
+/// An ID that can be suggested, along with a human-readable label (e.g. the workflow name) to match against.
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub id: String,
+    pub label: String,
+}
+
+#[derive(Default)]
+pub struct CompletionCache {
+    entries: Mutex<HashMap<CompletionKind, (Instant, Vec<Candidate>)>>,
+}
+
+impl CompletionCache {
+    pub fn get(&self, kind: CompletionKind) -> Option<Vec<Candidate>> {
+        let entries = self.entries.lock().unwrap();
+        let (fetched_at, candidates) = entries.get(&kind)?;
+
+        (fetched_at.elapsed() < CACHE_TTL).then(|| candidates.clone())
+    }
+
+    pub fn insert(&self, kind: CompletionKind, candidates: Vec<Candidate>) {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(kind, (Instant::now(), candidates));
+    }
+}
+
+/// Suggests the IDs that start with the typed value, or whose label contains it.
+pub fn complete(candidates: &[Candidate], value: &str) -> CompletionInfo {
+    let value = value.to_lowercase();
+
+    let matches: Vec<String> = candidates
+        .iter()
+        .filter(|candidate| {
+            candidate.id.to_lowercase().starts_with(&value)
+                || candidate.label.to_lowercase().contains(&value)
+        })
+        .map(|candidate| candidate.id.clone())
+        .collect();
+
+    CompletionInfo {
+        total: Some(matches.len() as u32),
+        has_more: Some(matches.len() > MAX_VALUES),
+        values: matches.into_iter().take(MAX_VALUES).collect(),
+    }
+}
@@ -0,0 +1,470 @@
+use anyhow::{Context, bail};
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap, env, net::SocketAddr, path::Path, path::PathBuf, sync::Arc,
+    time::Duration,
+};
+
+use crate::{
+    access::{Role, ToolAccess},
+    base_url::BaseUrl,
+    client::{Connection, DeploymentKind, Instances, N8nClient},
+    id::WorkflowId,
+    policy::{Policies, Policy},
+    server::Server,
+};
+
+pub const BIND_ADDRESS: &str = "127.0.0.1:8000";
+/// The service the API key is stored under in the OS keyring.
+pub const KEYRING_SERVICE: &str = "n8n-mcp-server";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Transport {
+    /// Talk to a single client over stdin/stdout, e.g. as a desktop MCP plugin
+    Stdio,
+    /// Serve any number of clients over HTTP with server-sent events
+    Http,
+    /// Serve HTTP with server-sent events on a Unix domain socket, for clients on the same host
+    Unix,
+}
+
+/// The server's configuration, layered from a TOML or YAML file, then environment variables, then CLI flags.
+///
+/// Every setting is optional at each layer, so a later layer only overrides what it actually sets.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub n8n: N8nConfig,
+    pub server: ServerConfig,
+    pub tools: ToolsConfig,
+    pub features: Features,
+    /// Other n8n instances, by name, for the tools that compare or copy workflows between instances. Each is set up
+    /// the same way as the `[n8n]` section, but only from the config file.
+    pub instances: BTreeMap<String, N8nConfig>,
+    /// Rules workflows have to follow, checked by `check_policies` and, when enforced, on every create, update and tag change.
+    pub policies: Vec<PolicyConfig>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct N8nConfig {
+    pub base_url: Option<String>,
+    /// Detected from the base URL when not set.
+    pub deployment_kind: Option<DeploymentKind>,
+    /// Where webhooks are served, if not from the base URL (e.g. n8n's own `WEBHOOK_URL` is set, or webhooks are
+    /// handled by separate webhook processors).
+    pub webhook_url: Option<String>,
+    pub api_key: Option<String>,
+    /// A file containing the API key, e.g. a Docker or Kubernetes secret.
+    pub api_key_file: Option<PathBuf>,
+    /// The account the API key is stored under in the OS keyring, with the service `n8n-mcp-server`.
+    pub api_key_keyring: Option<String>,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    /// How long to wait for n8n to respond to a request before giving up.
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub transport: Option<Transport>,
+    pub bind: Option<SocketAddr>,
+    pub socket: Option<PathBuf>,
+    pub tls_cert: Option<PathBuf>,
+    pub tls_key: Option<PathBuf>,
+    pub bearer_token: Option<String>,
+    /// Tokens for individual HTTP clients, each with the role that decides which tools it can use.
+    pub clients: Vec<ClientToken>,
+    pub multi_tenant: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClientToken {
+    /// Only used to identify the client in logs.
+    pub name: Option<String>,
+    pub token: String,
+    pub role: Role,
+}
+
+/// One policy, with one or more rules a workflow has to follow.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyConfig {
+    /// Identifies the policy in reports, e.g. `team-prefix`.
+    pub name: String,
+    /// Added to every violation, e.g. to say why the policy exists or who to ask.
+    pub description: Option<String>,
+    /// Stop workflows that break the policy from being created or updated, or having their tags changed, rather than
+    /// only reporting them.
+    #[serde(default)]
+    pub enforce: bool,
+    /// A regex workflow names have to match, e.g. `^(Billing|Ops) - `.
+    pub name_pattern: Option<String>,
+    /// A tag every workflow has to have.
+    pub required_tag: Option<String>,
+    /// Every workflow has to have a tag starting with this, e.g. `owner:`.
+    pub required_tag_prefix: Option<String>,
+    /// Node types workflows can't use, e.g. `n8n-nodes-base.executeCommand`.
+    #[serde(default)]
+    pub forbidden_node_types: Vec<String>,
+    /// Every workflow (other than error workflows themselves) has to have an error workflow set.
+    #[serde(default)]
+    pub require_error_workflow: bool,
+}
+
+/// Restrictions on which tools clients can use.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ToolsConfig {
+    /// Hide every tool that changes anything in n8n (creating, updating, deleting, activating or running).
+    pub read_only: bool,
+    /// When set, only the tools listed are available.
+    pub allow: Option<Vec<String>>,
+    /// Tools that aren't available, e.g. `delete_workflow_by_id`.
+    pub deny: Vec<String>,
+}
+
+/// Optional behaviour that can be switched on or off.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Features {
+    /// Let clients subscribe to executions, which polls n8n in the background while anyone is subscribed.
+    pub resource_subscriptions: bool,
+    /// Send a log message to the client for every request made to n8n.
+    pub request_logging: bool,
+    /// Where `export_workflows` writes backups to, and `import_workflows` restores them from, with executions exported
+    /// by `export_executions` in its `executions` folder. None of these tools can be used without it, or in
+    /// multi-tenant mode.
+    pub backup_dir: Option<PathBuf>,
+    /// Where the previous version of a workflow is saved before it's updated or deleted, so it can be looked back on
+    /// with `list_workflow_versions`. Nothing is saved without it, or in multi-tenant mode.
+    pub snapshot_dir: Option<PathBuf>,
+    /// Watch for executions that fail, and send connected clients a log message about each one.
+    pub failure_watch: FailureWatch,
+}
+
+/// Which failed executions clients are told about, and how often n8n is checked for them.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FailureWatch {
+    pub enabled: bool,
+    pub interval_secs: u64,
+    /// Only watch these workflows (along with any that have one of `tags`). Every workflow is watched if neither is
+    /// set.
+    pub workflows: Vec<WorkflowId>,
+    /// Only watch workflows with one of these tags (along with any in `workflows`).
+    pub tags: Vec<String>,
+}
+
+impl Default for FailureWatch {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: 60,
+            workflows: Vec::new(),
+            tags: Vec::new(),
+        }
+    }
+}
+
+impl Default for Features {
+    fn default() -> Self {
+        Self {
+            resource_subscriptions: true,
+            request_logging: true,
+            backup_dir: None,
+            snapshot_dir: None,
+            failure_watch: FailureWatch::default(),
+        }
+    }
+}
+
+impl N8nConfig {
+    fn set_api_key_source(
+        &mut self,
+        api_key: Option<String>,
+        api_key_file: Option<PathBuf>,
+        api_key_keyring: Option<String>,
+    ) {
+        self.api_key = api_key;
+        self.api_key_file = api_key_file;
+        self.api_key_keyring = api_key_keyring;
+    }
+
+    pub fn has_api_key(&self) -> bool {
+        self.api_key.is_some() || self.api_key_file.is_some() || self.api_key_keyring.is_some()
+    }
+
+    /// Reads the API key from wherever it's configured to come from. Only one source should be set, but if the
+    /// config file sets more than one the plain value wins, then the file, then the keyring.
+    pub fn api_key(&self) -> anyhow::Result<Option<String>> {
+        if let Some(api_key) = &self.api_key {
+            return Ok(Some(api_key.clone()));
+        }
+
+        if let Some(path) = &self.api_key_file {
+            let api_key = std::fs::read_to_string(path).with_context(|| {
+                format!("Could not read the n8n API key from {}", path.display())
+            })?;
+            let api_key = api_key.trim();
+            if api_key.is_empty() {
+                bail!("The n8n API key file {} is empty", path.display());
+            }
+            return Ok(Some(api_key.to_string()));
+        }
+
+        if let Some(account) = &self.api_key_keyring {
+            let api_key = keyring::Entry::new(KEYRING_SERVICE, account)
+                .and_then(|entry| entry.get_password())
+                .with_context(|| {
+                    format!(
+                        "Could not read the n8n API key for {account} from the OS keyring (service {KEYRING_SERVICE})"
+                    )
+                })?;
+            // Keyring tools often store the trailing newline of a pasted key too
+            let api_key = api_key.trim();
+            if api_key.is_empty() {
+                bail!("The n8n API key for {account} in the OS keyring is empty");
+            }
+            return Ok(Some(api_key.to_string()));
+        }
+
+        Ok(None)
+    }
+
+    fn connection(&self, base_url: BaseUrl, api_key: String) -> anyhow::Result<Connection> {
+        let webhook_base_url = self
+            .webhook_url
+            .as_deref()
+            .map(BaseUrl::parse)
+            .transpose()
+            .map_err(|err| anyhow::anyhow!("Invalid n8n webhook URL: {err}"))?;
+
+        Ok(Connection {
+            api_key,
+            deployment: self
+                .deployment_kind
+                .unwrap_or_else(|| DeploymentKind::detect(&base_url)),
+            webhook_base_url,
+            base_url,
+            n8n_user: self.user.clone(),
+            n8n_password: self.password.clone(),
+            timeout: self.timeout_secs.map(Duration::from_secs),
+        })
+    }
+}
+
+impl Config {
+    /// Loads the config file, if there is one, and applies any environment variable overrides on top.
+    pub fn load(path: Option<&Path>) -> anyhow::Result<Self> {
+        let mut config = match path {
+            Some(path) => Self::from_file(path)?,
+            None => Self::default(),
+        };
+
+        config.apply_env()?;
+
+        Ok(config)
+    }
+
+    fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Could not read config file {}", path.display()))?;
+
+        let config = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => toml::from_str(&contents).map_err(anyhow::Error::from),
+            Some("yaml" | "yml") => serde_yaml::from_str(&contents).map_err(anyhow::Error::from),
+            _ => bail!(
+                "Config file {} must end in .toml, .yaml or .yml",
+                path.display()
+            ),
+        };
+
+        config.with_context(|| format!("Could not parse config file {}", path.display()))
+    }
+
+    fn apply_env(&mut self) -> anyhow::Result<()> {
+        let var = |key: &str| env::var(key).ok().filter(|value| !value.is_empty());
+
+        if let Some(base_url) = var("N8N_BASE_URL") {
+            self.n8n.base_url = Some(base_url);
+        }
+        if let Some(kind) = var("N8N_DEPLOYMENT_KIND") {
+            self.n8n.deployment_kind =
+                Some(DeploymentKind::from_str(&kind, true).map_err(|_| {
+                    anyhow::anyhow!("N8N_DEPLOYMENT_KIND must be cloud or self-hosted")
+                })?);
+        }
+        if let Some(webhook_url) = var("N8N_WEBHOOK_URL") {
+            self.n8n.webhook_url = Some(webhook_url);
+        }
+        // Wherever the environment says the API key is overrides wherever the config file says it is
+        if let Some(api_key) = var("N8N_API_KEY") {
+            self.n8n.set_api_key_source(Some(api_key), None, None);
+        }
+        if let Some(path) = var("N8N_API_KEY_FILE") {
+            self.n8n.set_api_key_source(None, Some(path.into()), None);
+        }
+        if let Some(account) = var("N8N_API_KEY_KEYRING") {
+            self.n8n.set_api_key_source(None, None, Some(account));
+        }
+        if let Some(user) = var("N8N_USER") {
+            self.n8n.user = Some(user);
+        }
+        if let Some(password) = var("N8N_PASSWORD") {
+            self.n8n.password = Some(password);
+        }
+        if let Some(timeout) = var("N8N_TIMEOUT_SECS") {
+            self.n8n.timeout_secs = Some(
+                timeout
+                    .parse()
+                    .context("N8N_TIMEOUT_SECS must be a whole number of seconds")?,
+            );
+        }
+        if let Some(read_only) = var("READ_ONLY") {
+            self.tools.read_only = read_only
+                .parse()
+                .context("READ_ONLY must be true or false")?;
+        }
+        let tools = |list: String| -> Vec<String> {
+            list.split(',')
+                .map(str::trim)
+                .filter(|tool| !tool.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        if let Some(allow) = var("MCP_ALLOWED_TOOLS") {
+            self.tools.allow = Some(tools(allow));
+        }
+        if let Some(deny) = var("MCP_DENIED_TOOLS") {
+            self.tools.deny = tools(deny);
+        }
+        if let Some(bearer_token) = var("MCP_BEARER_TOKEN") {
+            self.server.bearer_token = Some(bearer_token);
+        }
+        if let Some(backup_dir) = var("MCP_BACKUP_DIR") {
+            self.features.backup_dir = Some(backup_dir.into());
+        }
+        if let Some(snapshot_dir) = var("MCP_SNAPSHOT_DIR") {
+            self.features.snapshot_dir = Some(snapshot_dir.into());
+        }
+
+        Ok(())
+    }
+
+    /// Applies the CLI flags, which take precedence over everything else.
+    pub fn apply_overrides(&mut self, overrides: ServerConfig) {
+        let server = &mut self.server;
+
+        server.transport = overrides.transport.or(server.transport);
+        server.bind = overrides.bind.or(server.bind);
+        server.socket = overrides.socket.or(server.socket.take());
+        server.tls_cert = overrides.tls_cert.or(server.tls_cert.take());
+        server.tls_key = overrides.tls_key.or(server.tls_key.take());
+        server.bearer_token = overrides.bearer_token.or(server.bearer_token.take());
+        server.multi_tenant = overrides.multi_tenant.or(server.multi_tenant);
+    }
+
+    pub fn base_url(&self) -> anyhow::Result<Option<BaseUrl>> {
+        self.n8n
+            .base_url
+            .as_deref()
+            .map(BaseUrl::parse)
+            .transpose()
+            .map_err(|err| anyhow::anyhow!("Invalid n8n base URL: {err}"))
+    }
+
+    /// The n8n instance every session talks to, outside of multi-tenant mode.
+    pub fn connection(&self) -> anyhow::Result<Connection> {
+        let Some(base_url) = self.base_url()? else {
+            bail!(
+                "No n8n base URL configured - set N8N_BASE_URL, or base_url in the [n8n] section of the config file"
+            );
+        };
+        let Some(api_key) = self.n8n.api_key()? else {
+            bail!(
+                "No n8n API key configured - set N8N_API_KEY, N8N_API_KEY_FILE or N8N_API_KEY_KEYRING, or api_key, api_key_file or api_key_keyring in the [n8n] section of the config file"
+            );
+        };
+
+        self.n8n.connection(base_url, api_key)
+    }
+
+    /// A client for each of the other configured instances, checking that each has a base URL and API key.
+    pub fn instances(&self) -> anyhow::Result<Instances> {
+        let mut instances = BTreeMap::new();
+        for (name, instance) in &self.instances {
+            let Some(base_url) = &instance.base_url else {
+                bail!(
+                    "No base URL configured for the n8n instance {name} - set base_url in the [instances.{name}] section of the config file"
+                );
+            };
+            let base_url = BaseUrl::parse(base_url).map_err(|err| {
+                anyhow::anyhow!("Invalid base URL for the n8n instance {name}: {err}")
+            })?;
+            let Some(api_key) = instance.api_key()? else {
+                bail!(
+                    "No API key configured for the n8n instance {name} - set api_key, api_key_file or api_key_keyring in the [instances.{name}] section of the config file"
+                );
+            };
+
+            let connection = instance
+                .connection(base_url, api_key)
+                .with_context(|| format!("Invalid settings for the n8n instance {name}"))?;
+            instances.insert(name.clone(), N8nClient::new(connection)?);
+        }
+
+        Ok(Arc::new(instances))
+    }
+
+    /// Compiles the configured policies, checking that each has a rule and that its name pattern is a valid regex.
+    pub fn policies(&self) -> anyhow::Result<Policies> {
+        self.policies.iter().map(Policy::compile).collect()
+    }
+
+    /// Which tools clients can use, after checking that every tool named in the allow and deny lists exists.
+    pub fn tool_access(&self) -> anyhow::Result<ToolAccess> {
+        let mut access = ToolAccess::default()
+            .read_only(self.tools.read_only)
+            .deny(self.tools.deny.iter().cloned());
+        if let Some(allow) = &self.tools.allow {
+            access = access.allow(allow.iter().cloned());
+        }
+
+        let known = Server::tools()
+            .into_iter()
+            .map(|tool| tool.name.to_string())
+            .collect();
+        let unknown = access.unknown_tools(&known);
+        if !unknown.is_empty() {
+            bail!(
+                "Unknown tool(s) in the tool allow or deny list: {} - run `list-tools` to see every tool",
+                unknown.join(", ")
+            );
+        }
+
+        Ok(access)
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.n8n.timeout_secs.map(Duration::from_secs)
+    }
+
+    pub fn transport(&self) -> Transport {
+        self.server.transport.unwrap_or(Transport::Http)
+    }
+
+    pub fn bind(&self) -> SocketAddr {
+        self.server
+            .bind
+            .unwrap_or_else(|| BIND_ADDRESS.parse().unwrap())
+    }
+
+    pub fn multi_tenant(&self) -> bool {
+        self.server.multi_tenant.unwrap_or(false)
+    }
+}
@@ -0,0 +1,160 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use crate::{
+    id::WorkflowId,
+    workflow::{Node, Workflow},
+};
+
+/// Node types that run another workflow: Execute Workflow (called Execute Sub-workflow in newer versions of n8n) and
+/// the AI agent tool that calls a workflow.
+const EXECUTE_WORKFLOW_TYPES: &[&str] = &[
+    "n8n-nodes-base.executeWorkflow",
+    "@n8n/n8n-nodes-langchain.toolWorkflow",
+];
+
+/// One workflow running another through one of its nodes.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Call {
+    pub caller: WorkflowId,
+    pub callee: WorkflowId,
+    pub node: String,
+    /// Disabled nodes don't run, but still count, since enabling the node brings the call back.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
+}
+
+/// A node that runs a workflow which can't be worked out without running it, e.g. because its ID is an expression.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnresolvedCall {
+    pub caller: WorkflowId,
+    pub node: String,
+    pub reason: String,
+}
+
+/// Which workflows call which, across a set of workflows.
+#[derive(Debug, Default)]
+pub struct Graph {
+    pub calls: Vec<Call>,
+    pub unresolved: Vec<UnresolvedCall>,
+}
+
+impl Graph {
+    pub fn build(workflows: &[Workflow]) -> Self {
+        let mut graph = Self::default();
+
+        for workflow in workflows {
+            let Some(caller) = &workflow.id else {
+                continue;
+            };
+
+            for node in &workflow.nodes {
+                match callee(node) {
+                    None => {}
+                    Some(Ok(callee)) => graph.calls.push(Call {
+                        caller: caller.clone(),
+                        callee,
+                        node: node.name.clone(),
+                        disabled: node.disabled.unwrap_or(false),
+                    }),
+                    Some(Err(reason)) => graph.unresolved.push(UnresolvedCall {
+                        caller: caller.clone(),
+                        node: node.name.clone(),
+                        reason,
+                    }),
+                }
+            }
+        }
+
+        graph
+    }
+
+    pub fn calls_from<'a>(&'a self, workflow_id: &'a WorkflowId) -> impl Iterator<Item = &'a Call> {
+        self.calls
+            .iter()
+            .filter(move |call| &call.caller == workflow_id)
+    }
+
+    pub fn calls_to<'a>(&'a self, workflow_id: &'a WorkflowId) -> impl Iterator<Item = &'a Call> {
+        self.calls
+            .iter()
+            .filter(move |call| &call.callee == workflow_id)
+    }
+
+    /// Every workflow that calls `workflow_id`, directly or through other workflows, and so could break if it's
+    /// changed or deleted. Doesn't include `workflow_id` itself, even if it's part of a cycle.
+    pub fn all_callers<'a>(&'a self, workflow_id: &'a WorkflowId) -> BTreeSet<&'a WorkflowId> {
+        let mut callers = BTreeSet::new();
+        let mut queue = VecDeque::from([workflow_id]);
+
+        while let Some(callee) = queue.pop_front() {
+            for call in self.calls_to(callee) {
+                if &call.caller != workflow_id && callers.insert(&call.caller) {
+                    queue.push_back(&call.caller);
+                }
+            }
+        }
+
+        callers
+    }
+
+    /// Workflows that are called but aren't among those the graph was built from, which usually means they've been
+    /// deleted.
+    pub fn missing<'a>(
+        &'a self,
+        workflows: &HashMap<&WorkflowId, &Workflow>,
+    ) -> BTreeSet<&'a WorkflowId> {
+        self.calls
+            .iter()
+            .map(|call| &call.callee)
+            .filter(|callee| !workflows.contains_key(callee))
+            .collect()
+    }
+}
+
+/// The workflow a node runs, if it's a node that runs one. Nodes that pick the workflow with an expression, or load it
+/// from a file, URL or parameter rather than by ID, give the reason it can't be known instead.
+fn callee(node: &Node) -> Option<Result<WorkflowId, String>> {
+    if !EXECUTE_WORKFLOW_TYPES.contains(&node.kind.as_str()) {
+        return None;
+    }
+
+    let parameters = node.parameters.clone().unwrap_or_default();
+
+    // Older nodes can run a workflow from elsewhere than the database, and default to the database
+    let source = parameters
+        .get("source")
+        .and_then(Value::as_str)
+        .unwrap_or("database");
+    if source != "database" {
+        return Some(Err(format!(
+            "Runs a workflow given by its '{source}' source rather than by ID"
+        )));
+    }
+
+    // The ID is either a string, or (since typeVersion 1.1) a resource locator like `{"__rl": true, "value": "..."}`
+    let workflow_id = match parameters.get("workflowId") {
+        Some(Value::Object(locator)) => locator.get("value").cloned(),
+        other => other.cloned(),
+    };
+    let workflow_id = match workflow_id {
+        Some(Value::String(id)) => id,
+        Some(Value::Number(id)) => id.to_string(),
+        _ => return Some(Err("No workflow is selected".to_string())),
+    };
+
+    if workflow_id.starts_with('=') {
+        return Some(Err(format!(
+            "The workflow ID is an expression: {}",
+            workflow_id.trim_start_matches('=')
+        )));
+    }
+    if workflow_id.trim().is_empty() {
+        return Some(Err("No workflow is selected".to_string()));
+    }
+
+    Some(Ok(workflow_id.trim().into()))
+}
@@ -0,0 +1,204 @@
+use rmcp::schemars::{self, JsonSchema};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::HashMap, fmt::Write};
+
+use crate::workflow::{Node, Workflow};
+
+/// The text formats `render_workflow_diagram` can draw a workflow in.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    /// A Mermaid flowchart, which many chat clients and Markdown viewers render.
+    #[default]
+    Mermaid,
+    /// Graphviz DOT.
+    Dot,
+}
+
+impl Format {
+    /// The language of a fenced code block holding the diagram.
+    pub fn language(self) -> &'static str {
+        match self {
+            Self::Mermaid => "mermaid",
+            Self::Dot => "dot",
+        }
+    }
+}
+
+/// How a node is drawn, going by what it does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Shape {
+    Trigger,
+    Branch,
+    Step,
+}
+
+/// One connection to draw, between the indexes of two nodes.
+struct Edge {
+    from: usize,
+    to: usize,
+    label: Option<String>,
+    /// Connections other than `main`, i.e. AI sub-nodes, are drawn dotted.
+    sub_node: bool,
+}
+
+/// Draws a workflow's nodes and connections left to right. Sticky notes are left out, and disabled nodes are drawn
+/// dashed.
+pub fn render(workflow: &Workflow, format: Format) -> String {
+    let nodes: Vec<&Node> = workflow
+        .nodes
+        .iter()
+        .filter(|node| !node.is_sticky_note())
+        .collect();
+    let index: HashMap<&str, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| (node.name.as_str(), i))
+        .collect();
+
+    let mut edges = Vec::new();
+    for (source, outputs) in &workflow.connections {
+        let Some(&from) = index.get(source.as_str()) else {
+            continue;
+        };
+        for (connection_type, outputs) in outputs {
+            for (output, targets) in outputs.iter().enumerate() {
+                for connection in targets.iter().flatten() {
+                    let Some(&to) = index.get(connection.node.as_str()) else {
+                        continue;
+                    };
+                    let sub_node = connection_type != "main";
+                    edges.push(Edge {
+                        from,
+                        to,
+                        label: if sub_node {
+                            Some(connection_type.trim_start_matches("ai_").to_string())
+                        } else {
+                            output_label(nodes[from], output, outputs.len())
+                        },
+                        sub_node,
+                    });
+                }
+            }
+        }
+    }
+
+    match format {
+        Format::Mermaid => mermaid(&nodes, &edges),
+        Format::Dot => dot(&nodes, &edges),
+    }
+}
+
+fn mermaid(nodes: &[&Node], edges: &[Edge]) -> String {
+    let mut diagram = String::from("flowchart LR\n");
+
+    for (i, node) in nodes.iter().enumerate() {
+        let label = node.name.replace('"', "#quot;");
+        let _ = match shape(node) {
+            Shape::Trigger => writeln!(diagram, "    n{i}([\"{label}\"])"),
+            Shape::Branch => writeln!(diagram, "    n{i}{{\"{label}\"}}"),
+            Shape::Step => writeln!(diagram, "    n{i}[\"{label}\"]"),
+        };
+    }
+    for edge in edges {
+        let arrow = if edge.sub_node { "-.->" } else { "-->" };
+        let label = edge
+            .label
+            .as_ref()
+            .map(|label| format!("|{}|", label.replace('"', "#quot;")))
+            .unwrap_or_default();
+        let _ = writeln!(diagram, "    n{} {arrow}{label} n{}", edge.from, edge.to);
+    }
+
+    let disabled: Vec<String> = nodes
+        .iter()
+        .enumerate()
+        .filter(|(_, node)| node.disabled == Some(true))
+        .map(|(i, _)| format!("n{i}"))
+        .collect();
+    if !disabled.is_empty() {
+        diagram.push_str("    classDef disabled stroke-dasharray: 5 5,opacity:0.6\n");
+        let _ = writeln!(diagram, "    class {} disabled", disabled.join(","));
+    }
+
+    diagram
+}
+
+fn dot(nodes: &[&Node], edges: &[Edge]) -> String {
+    let mut diagram = String::from("digraph workflow {\n    rankdir=LR;\n    node [shape=box];\n");
+
+    for (i, node) in nodes.iter().enumerate() {
+        let mut attributes = vec![format!("label=\"{}\"", escape_dot(&node.name))];
+        match shape(node) {
+            Shape::Trigger => attributes.push("shape=oval".to_string()),
+            Shape::Branch => attributes.push("shape=diamond".to_string()),
+            Shape::Step => {}
+        }
+        if node.disabled == Some(true) {
+            attributes.push("style=dashed".to_string());
+        }
+        let _ = writeln!(diagram, "    n{i} [{}];", attributes.join(", "));
+    }
+    for edge in edges {
+        let mut attributes = Vec::new();
+        if let Some(label) = &edge.label {
+            attributes.push(format!("label=\"{}\"", escape_dot(label)));
+        }
+        if edge.sub_node {
+            attributes.push("style=dotted".to_string());
+        }
+        let attributes = if attributes.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", attributes.join(", "))
+        };
+        let _ = writeln!(diagram, "    n{} -> n{}{attributes};", edge.from, edge.to);
+    }
+
+    diagram.push_str("}\n");
+    diagram
+}
+
+fn shape(node: &Node) -> Shape {
+    if node.is_trigger() {
+        Shape::Trigger
+    } else if matches!(
+        node.kind.as_str(),
+        "n8n-nodes-base.if" | "n8n-nodes-base.switch" | "n8n-nodes-base.filter"
+    ) {
+        Shape::Branch
+    } else {
+        Shape::Step
+    }
+}
+
+/// What to label a connection out of one of a node's outputs with. Nodes with one output don't need a label.
+fn output_label(node: &Node, output: usize, outputs: usize) -> Option<String> {
+    // Nodes set to continue on error get an extra output for errors, after their usual ones. Switch and Filter nodes
+    // have as many usual outputs as they're set up with, so their error output can't be told apart.
+    let usual_outputs = match node.kind.as_str() {
+        "n8n-nodes-base.if" | "n8n-nodes-base.splitInBatches" => Some(2),
+        "n8n-nodes-base.switch" | "n8n-nodes-base.filter" => None,
+        _ => Some(1),
+    };
+    let continues_on_error =
+        node.extra.get("onError").and_then(Value::as_str) == Some("continueErrorOutput");
+    if continues_on_error && usual_outputs == Some(output) {
+        return Some("error".to_string());
+    }
+
+    match (node.kind.as_str(), output) {
+        ("n8n-nodes-base.if", 0) => Some("true".to_string()),
+        ("n8n-nodes-base.if", 1) => Some("false".to_string()),
+        ("n8n-nodes-base.filter", 1) => Some("discarded".to_string()),
+        ("n8n-nodes-base.splitInBatches", 0) => Some("done".to_string()),
+        ("n8n-nodes-base.splitInBatches", 1) => Some("loop".to_string()),
+        _ if outputs > 1 => Some(format!("output {output}")),
+        _ => None,
+    }
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
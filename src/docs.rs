@@ -0,0 +1,136 @@
+use serde_json::Value;
+use std::fmt::Write;
+
+use crate::workflow::{Node, Workflow};
+
+/// The size n8n gives a sticky note that hasn't been resized.
+const STICKY_NOTE_SIZE: [f64; 2] = [240.0, 160.0];
+
+/// Collects the documentation written into a workflow - its description, its sticky notes and the notes on its nodes
+/// - as Markdown.
+///
+/// Sticky notes are in reading order (left to right across the canvas), each with the nodes it's drawn over, since
+/// that's usually what it's describing. Node notes follow, in the order the nodes are in the workflow.
+pub fn render(workflow: &Workflow) -> String {
+    let mut markdown = format!("# {}\n", workflow.name);
+
+    if let Some(description) = text(workflow.extra.get("description")) {
+        let _ = write!(markdown, "\n{description}\n");
+    }
+
+    let mut sticky_notes: Vec<&Node> = workflow
+        .nodes
+        .iter()
+        .filter(|node| node.is_sticky_note())
+        .filter(|node| content(node).is_some())
+        .collect();
+    sticky_notes.sort_by(|a, b| {
+        let [ax, ay] = a.position.unwrap_or_default();
+        let [bx, by] = b.position.unwrap_or_default();
+        ax.total_cmp(&bx).then(ay.total_cmp(&by))
+    });
+
+    let noted: Vec<(&Node, &str)> = workflow
+        .nodes
+        .iter()
+        .filter(|node| !node.is_sticky_note())
+        .filter_map(|node| Some((node, text(node.extra.get("notes"))?)))
+        .collect();
+
+    if sticky_notes.is_empty() && noted.is_empty() {
+        markdown.push_str("\n_This workflow has no sticky notes or node notes._\n");
+        return markdown;
+    }
+
+    if !sticky_notes.is_empty() {
+        markdown.push_str("\n## Sticky notes\n");
+        for note in sticky_notes {
+            let _ = write!(markdown, "\n### {}\n", note.name);
+            let covered = covered_nodes(workflow, note);
+            if !covered.is_empty() {
+                let _ = write!(markdown, "\n_Covers: {}_\n", covered.join(", "));
+            }
+            let _ = write!(
+                markdown,
+                "\n{}\n",
+                demote_headings(content(note).unwrap_or_default(), 3)
+            );
+        }
+    }
+
+    if !noted.is_empty() {
+        markdown.push_str("\n## Node notes\n");
+        for (node, notes) in noted {
+            let _ = write!(
+                markdown,
+                "\n### {} (`{}`)\n\n{notes}\n",
+                node.name, node.kind
+            );
+        }
+    }
+
+    markdown
+}
+
+/// A string that isn't just whitespace, trimmed.
+fn text(value: Option<&Value>) -> Option<&str> {
+    value
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+}
+
+fn content(note: &Node) -> Option<&str> {
+    text(note.parameters.as_ref()?.get("content"))
+}
+
+/// Pushes the Markdown headings in a note down by `levels`, so they sit under the note's own heading. Headings go no
+/// lower than Markdown's sixth level, and lines in code blocks are left alone.
+fn demote_headings(text: &str, levels: usize) -> String {
+    let mut in_code_block = false;
+
+    text.lines()
+        .map(|line| {
+            if line.trim_start().starts_with("```") {
+                in_code_block = !in_code_block;
+            }
+            let level = line.chars().take_while(|c| *c == '#').count();
+            let is_heading = level > 0 && line[level..].starts_with(' ');
+            if in_code_block || !is_heading {
+                return line.to_string();
+            }
+            format!("{}{}", "#".repeat((level + levels).min(6)), &line[level..])
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The nodes whose position is inside the sticky note on the canvas.
+fn covered_nodes<'a>(workflow: &'a Workflow, note: &Node) -> Vec<&'a str> {
+    let Some([x, y]) = note.position else {
+        return Vec::new();
+    };
+    let size = |key: &str, default: f64| {
+        note.parameters
+            .as_ref()
+            .and_then(|parameters| parameters.get(key))
+            .and_then(Value::as_f64)
+            .unwrap_or(default)
+    };
+    let (width, height) = (
+        size("width", STICKY_NOTE_SIZE[0]),
+        size("height", STICKY_NOTE_SIZE[1]),
+    );
+
+    workflow
+        .nodes
+        .iter()
+        .filter(|node| !node.is_sticky_note())
+        .filter(|node| {
+            node.position.is_some_and(|[node_x, node_y]| {
+                (x..=x + width).contains(&node_x) && (y..=y + height).contains(&node_y)
+            })
+        })
+        .map(|node| node.name.as_str())
+        .collect()
+}
@@ -0,0 +1,155 @@
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap},
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use crate::{id::WorkflowId, workflow::Workflow};
+
+/// Workflows that are copies of each other, going by their structure.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Group {
+    /// Identifies the shared structure, so the same group can be recognised across calls.
+    pub fingerprint: String,
+    /// Whether the node settings match too, rather than just the nodes and how they're connected.
+    pub identical: bool,
+    pub node_count: usize,
+    pub workflows: Vec<Member>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Member {
+    pub id: Option<WorkflowId>,
+    pub name: String,
+    pub active: Value,
+    pub updated_at: Value,
+}
+
+/// Groups workflows whose nodes and connections have the same shape: the same node types, wired together the same
+/// way. Node names, IDs and positions, and sticky notes, are left out, since those are what usually change when a
+/// workflow is copied. Workflows with no nodes aren't grouped.
+///
+/// With `identical_only`, workflows are only grouped when their node settings match too. Groups are largest first,
+/// and only groups of more than one workflow are returned.
+pub fn groups(workflows: &[Workflow], identical_only: bool) -> Vec<Group> {
+    let mut by_structure: BTreeMap<(String, Vec<String>), Vec<&Workflow>> = BTreeMap::new();
+    for workflow in workflows {
+        if let Some(structure) = structure(workflow) {
+            let definitions = if identical_only {
+                node_definitions(workflow)
+            } else {
+                Vec::new()
+            };
+            by_structure
+                .entry((structure, definitions))
+                .or_default()
+                .push(workflow);
+        }
+    }
+
+    let mut groups: Vec<Group> = by_structure
+        .into_iter()
+        .filter(|(_, workflows)| workflows.len() > 1)
+        .map(|((structure, _), workflows)| {
+            let first = node_definitions(workflows[0]);
+            Group {
+                fingerprint: fingerprint(&structure),
+                identical: workflows
+                    .iter()
+                    .all(|workflow| node_definitions(workflow) == first),
+                node_count: first.len(),
+                workflows: workflows.into_iter().map(member).collect(),
+            }
+        })
+        .collect();
+    groups.sort_by_key(|group| Reverse(group.workflows.len()));
+
+    groups
+}
+
+/// The node types in a workflow and the connections between them, by type rather than name, in a canonical order.
+fn structure(workflow: &Workflow) -> Option<String> {
+    let types: HashMap<&str, &str> = workflow
+        .nodes
+        .iter()
+        .filter(|node| !node.is_sticky_note())
+        .map(|node| (node.name.as_str(), node.kind.as_str()))
+        .collect();
+    if types.is_empty() {
+        return None;
+    }
+
+    let mut nodes: Vec<&str> = types.values().copied().collect();
+    nodes.sort_unstable();
+
+    let mut connections = Vec::new();
+    for (source, outputs) in &workflow.connections {
+        let Some(source_type) = types.get(source.as_str()) else {
+            continue;
+        };
+        for (kind, outputs) in outputs {
+            for (output, targets) in outputs.iter().enumerate() {
+                for target in targets.iter().flatten() {
+                    let target_type = types.get(target.node.as_str()).copied().unwrap_or("?");
+                    connections.push(format!(
+                        "{source_type}[{kind}:{output}]->{target_type}[{}:{}]",
+                        target.kind, target.index
+                    ));
+                }
+            }
+        }
+    }
+    connections.sort_unstable();
+
+    Some(json!([nodes, connections]).to_string())
+}
+
+/// Everything about a workflow's nodes except what's particular to the copy, in a canonical order. Credentials are
+/// kept by name.
+fn node_definitions(workflow: &Workflow) -> Vec<String> {
+    let mut definitions: Vec<String> = workflow
+        .nodes
+        .iter()
+        .filter(|node| !node.is_sticky_note())
+        .map(|node| {
+            let mut definition = json!(node);
+            if let Value::Object(definition) = &mut definition {
+                for key in ["id", "name", "position", "webhookId"] {
+                    definition.remove(key);
+                }
+                if let Some(Value::Object(credentials)) = definition.get_mut("credentials") {
+                    for credential in credentials.values_mut() {
+                        *credential = credential["name"].clone();
+                    }
+                }
+            }
+            definition.to_string()
+        })
+        .collect();
+    definitions.sort_unstable();
+
+    definitions
+}
+
+fn fingerprint(structure: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    structure.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+fn member(workflow: &Workflow) -> Member {
+    Member {
+        id: workflow.id.clone(),
+        name: workflow.name.clone(),
+        active: workflow.extra.get("active").cloned().unwrap_or(Value::Null),
+        updated_at: workflow
+            .extra
+            .get("updatedAt")
+            .cloned()
+            .unwrap_or(Value::Null),
+    }
+}
@@ -0,0 +1,64 @@
+use std::fmt;
+
+use crate::{
+    base_url::BaseUrl,
+    id::{ExecutionId, TagId, WorkflowId},
+};
+
+/// Every n8n route the server calls, so each path is only spelled out in one place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endpoint<'a> {
+    Workflows,
+    Workflow(&'a WorkflowId),
+    ActivateWorkflow(&'a WorkflowId),
+    DeactivateWorkflow(&'a WorkflowId),
+    WorkflowTags(&'a WorkflowId),
+    Executions,
+    Execution(&'a ExecutionId),
+    Tags,
+    Tag(&'a TagId),
+    /// Only listed by the public API of recent n8n versions.
+    Credentials,
+    /// The instance's settings, from the internal API the n8n editor uses.
+    Settings,
+    /// The instance's license, from the internal API the n8n editor uses.
+    License,
+    /// The production URL of a webhook trigger, by the path set on its node.
+    Webhook(&'a str),
+    /// The test URL of a webhook trigger, only listened on after "Listen for test event" is clicked in the editor.
+    TestWebhook(&'a str),
+}
+
+impl Endpoint<'_> {
+    /// The full URL of the route on an instance. Webhooks should be given the instance's webhook URL rather than its
+    /// base URL.
+    pub fn url(&self, base_url: &BaseUrl) -> String {
+        match self {
+            Self::Settings | Self::License => base_url.rest(&self.to_string()),
+            Self::Webhook(path) => base_url.webhook(path),
+            Self::TestWebhook(path) => base_url.test_webhook(path),
+            _ => base_url.api(&self.to_string()),
+        }
+    }
+}
+
+/// The route's path, relative to the API (or webhook) root it belongs to.
+impl fmt::Display for Endpoint<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Workflows => f.write_str("workflows"),
+            Self::Workflow(id) => write!(f, "workflows/{id}"),
+            Self::ActivateWorkflow(id) => write!(f, "workflows/{id}/activate"),
+            Self::DeactivateWorkflow(id) => write!(f, "workflows/{id}/deactivate"),
+            Self::WorkflowTags(id) => write!(f, "workflows/{id}/tags"),
+            Self::Executions => f.write_str("executions"),
+            Self::Execution(id) => write!(f, "executions/{id}"),
+            Self::Tags => f.write_str("tags"),
+            Self::Tag(id) => write!(f, "tags/{id}"),
+            Self::Credentials => f.write_str("credentials"),
+            Self::Settings => f.write_str("settings"),
+            Self::License => f.write_str("license"),
+            Self::Webhook(path) | Self::TestWebhook(path) => f.write_str(path),
+        }
+    }
+}
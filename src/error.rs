@@ -0,0 +1,114 @@
+use rmcp::{
+    Error as McpError,
+    model::{Content, IntoContents},
+};
+use std::path::PathBuf;
+
+/// Everything that can go wrong while a tool is talking to n8n.
+///
+/// Tools return it as their error type, and rmcp turns it into an error result (`isError: true`) for the client, so
+/// the agent can see what went wrong and try something else.
+#[derive(Debug, thiserror::Error)]
+pub enum ServerError {
+    /// The request couldn't be sent, or the body couldn't be read, e.g. because the connection dropped.
+    #[error("{0}")]
+    Request(#[from] reqwest::Error),
+    /// n8n responded, but not with a 2xx status.
+    #[error("n8n responded with {status}{}", details(.message, .code, .hint))]
+    Status {
+        status: reqwest::StatusCode,
+        /// n8n's own explanation, from the `message` field of its error body, or the start of the body itself if
+        /// it isn't JSON.
+        message: Option<String>,
+        code: Option<serde_json::Value>,
+        hint: Option<String>,
+    },
+    /// The body isn't JSON, which usually means something in front of n8n answered instead of n8n itself.
+    #[error("n8n responded with {status} and a body that isn't JSON: {body}")]
+    NotJson {
+        status: reqwest::StatusCode,
+        body: String,
+    },
+    /// JSON that doesn't have the shape it should, either from n8n or on its way out to the client.
+    #[error("Unexpected JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    /// The tool was called with arguments that can't work, so n8n was never asked.
+    #[error("{0}")]
+    Validation(String),
+    /// A failure that's been looked into, described as JSON along with what to do about it.
+    #[error("{0}")]
+    Detailed(serde_json::Value),
+    /// A file on the server couldn't be read or written, e.g. a workflow backup.
+    #[error("{}: {source}", path.display())]
+    File {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+fn details(
+    message: &Option<String>,
+    code: &Option<serde_json::Value>,
+    hint: &Option<String>,
+) -> String {
+    let mut details = String::new();
+    if let Some(message) = message {
+        details.push_str(&format!(": {message}"));
+    }
+    if let Some(code) = code {
+        details.push_str(&format!(" (code {code})"));
+    }
+    if let Some(hint) = hint {
+        details.push_str(&format!(". {hint}"));
+    }
+
+    details
+}
+
+impl ServerError {
+    pub fn validation(message: impl Into<String>) -> Self {
+        Self::Validation(message.into())
+    }
+
+    pub fn file(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        Self::File {
+            path: path.into(),
+            source,
+        }
+    }
+
+    /// The HTTP status n8n responded with, if it responded at all.
+    pub fn status(&self) -> Option<reqwest::StatusCode> {
+        match self {
+            Self::Request(err) => err.status(),
+            Self::Status { status, .. } | Self::NotJson { status, .. } => Some(*status),
+            Self::Json(_) | Self::Validation(_) | Self::Detailed(_) | Self::File { .. } => None,
+        }
+    }
+}
+
+/// What the client sees when a tool fails.
+impl IntoContents for ServerError {
+    fn into_contents(self) -> Vec<Content> {
+        let text = match self {
+            Self::Validation(message) => message,
+            Self::Detailed(details) => serde_json::to_string_pretty(&details).unwrap(),
+            err => format!("Workflow error: {err}"),
+        };
+
+        vec![Content::text(text)]
+    }
+}
+
+/// For everything other than tools, e.g. reading resources, where errors are protocol errors rather than results.
+impl From<ServerError> for McpError {
+    fn from(err: ServerError) -> Self {
+        match (&err, err.status()) {
+            (ServerError::Validation(_), _) => McpError::invalid_params(err.to_string(), None),
+            (_, Some(reqwest::StatusCode::NOT_FOUND)) => {
+                McpError::resource_not_found(format!("Workflow error: {err}"), None)
+            }
+            _ => McpError::internal_error(format!("Workflow error: {err}"), None),
+        }
+    }
+}
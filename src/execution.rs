@@ -0,0 +1,654 @@
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::{
+    id::{ExecutionId, WorkflowId},
+    page::Paginated,
+};
+
+/// A single run of a workflow, as returned by the API.
+///
+/// `data` holds the input and output of every node, which easily runs to megabytes, so tools return a
+/// [`summary`](Execution::summary) instead unless they're asked for it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Execution {
+    pub id: ExecutionId,
+    #[serde(default)]
+    pub workflow_id: Option<WorkflowId>,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub mode: Option<String>,
+    #[serde(default)]
+    pub started_at: Option<String>,
+    #[serde(default)]
+    pub stopped_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// The parts of an execution needed to tell what happened, without its data.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionSummary {
+    pub id: ExecutionId,
+    pub workflow_id: Option<WorkflowId>,
+    pub status: Option<String>,
+    pub mode: Option<String>,
+    pub started_at: Option<String>,
+    pub stopped_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ExecutionError>,
+}
+
+/// What one node did in an execution, with a sample of the items it output rather than all of them.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeDigest {
+    /// How many times the node ran, e.g. once per batch in a loop.
+    pub runs: usize,
+    /// The items it output, over every run and output.
+    pub items: usize,
+    /// Roughly how big its data is, as JSON.
+    pub size_bytes: usize,
+    /// Every top-level field of the items it output.
+    pub fields: BTreeSet<String>,
+    /// The first items it output.
+    pub sample: Vec<Value>,
+    pub execution_time_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// How a workflow's executions went, counted by status.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Metrics {
+    pub total: usize,
+    pub success: usize,
+    /// Including executions that crashed.
+    pub error: usize,
+    pub waiting: usize,
+    /// Including executions that are queued.
+    pub running: usize,
+    pub canceled: usize,
+    /// The share of finished executions that failed, from 0 to 1. `None` if none have finished.
+    pub failure_rate: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_failure_at: Option<String>,
+}
+
+impl Metrics {
+    /// Counts an execution. Executions are expected newest first, as the API returns them.
+    pub fn add(&mut self, execution: &Execution) {
+        self.total += 1;
+        match execution.status.as_deref() {
+            Some("success") => self.success += 1,
+            Some("error" | "crashed") => {
+                self.error += 1;
+                if self.last_failure_at.is_none() {
+                    self.last_failure_at = execution.started_at.clone();
+                }
+            }
+            Some("waiting") => self.waiting += 1,
+            Some("running" | "new") => self.running += 1,
+            Some("canceled") => self.canceled += 1,
+            _ => {}
+        }
+
+        let finished = self.success + self.error;
+        self.failure_rate =
+            (finished > 0).then(|| (self.error as f64 / finished as f64 * 1000.0).round() / 1000.0);
+    }
+}
+
+/// How long a workflow's finished executions took, in milliseconds.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Durations {
+    pub count: usize,
+    pub min_ms: i64,
+    pub median_ms: i64,
+    pub p95_ms: i64,
+    pub max_ms: i64,
+}
+
+impl Durations {
+    /// `None` if there aren't any durations.
+    pub fn of(mut durations: Vec<i64>) -> Option<Self> {
+        durations.sort_unstable();
+        // The nearest-rank percentile: the smallest duration at least that share of them are no longer than
+        let percentile = |share: f64| {
+            let rank = (share * durations.len() as f64).ceil() as usize;
+            durations[rank.clamp(1, durations.len()) - 1]
+        };
+
+        Some(Self {
+            count: durations.len(),
+            min_ms: *durations.first()?,
+            median_ms: percentile(0.5),
+            p95_ms: percentile(0.95),
+            max_ms: *durations.last()?,
+        })
+    }
+}
+
+/// How a node's run differed between two executions. Each pair is `[first, second]`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeComparison {
+    pub node: String,
+    /// `success`, `error` or `not run`.
+    pub status: [&'static str; 2],
+    pub runs: [usize; 2],
+    pub items: [usize; 2],
+    pub execution_time_ms: [u64; 2],
+    /// Fields the node's items have in the second execution but not the first.
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    pub fields_added: BTreeSet<String>,
+    /// Fields the node's items have in the first execution but not the second.
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    pub fields_removed: BTreeSet<String>,
+    /// The fields of the node's first item whose values differ.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub changed_values: BTreeMap<String, [Value; 2]>,
+    #[serde(skip_serializing_if = "is_none_pair")]
+    pub errors: [Option<String>; 2],
+}
+
+impl NodeComparison {
+    /// Whether the node did anything differently, going by everything but how long it took.
+    pub fn differs(&self) -> bool {
+        self.status[0] != self.status[1]
+            || self.runs[0] != self.runs[1]
+            || self.items[0] != self.items[1]
+            || !self.fields_added.is_empty()
+            || !self.fields_removed.is_empty()
+            || !self.changed_values.is_empty()
+            || self.errors[0] != self.errors[1]
+    }
+}
+
+fn is_none_pair(pair: &[Option<String>; 2]) -> bool {
+    pair.iter().all(Option::is_none)
+}
+
+/// How many lines of an error's stack trace a diagnosis keeps. The top of it is where the error was thrown.
+const MAX_STACK_LINES: usize = 8;
+
+/// Where and why an execution failed, and what the failing node was given.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnosis {
+    pub node: Option<String>,
+    pub node_type: Option<String>,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_code: Option<String>,
+    /// Which of the input items the node failed on, when n8n says.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub item_index: Option<u64>,
+    /// The top of the stack trace.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stack: Vec<String>,
+    /// The node whose output went into the failing node.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_from: Option<String>,
+    pub input_item_count: usize,
+    /// The items the failing node was given: just the one it failed on if that's known, otherwise the first few.
+    pub input_items: Vec<Value>,
+    pub hint: &'static str,
+}
+
+/// Why an execution failed. Only known when the execution was fetched with its data.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecutionError {
+    pub message: String,
+    /// The name of the node that failed, if the error came from a node.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node: Option<String>,
+}
+
+impl Execution {
+    pub fn error(&self) -> Option<ExecutionError> {
+        let error = self.data.as_ref()?.pointer("/resultData/error")?;
+
+        Some(ExecutionError {
+            message: error["message"]
+                .as_str()
+                .unwrap_or("Unknown error")
+                .to_string(),
+            node: error["node"]["name"].as_str().map(ToString::to_string),
+        })
+    }
+
+    /// Whether the execution has stopped running, one way or another. Waiting executions (e.g. at a Wait node) haven't.
+    pub fn is_finished(&self) -> bool {
+        !matches!(
+            self.status.as_deref(),
+            Some("new" | "running" | "waiting") | None
+        )
+    }
+
+    /// The name of the node that ran last, which is where a successful execution's result comes out.
+    pub fn last_node(&self) -> Option<&str> {
+        self.data
+            .as_ref()?
+            .pointer("/resultData/lastNodeExecuted")?
+            .as_str()
+    }
+
+    /// The items a node output from its first output the last time it ran, as their JSON. Only known when the
+    /// execution was fetched with its data.
+    pub fn node_output(&self, node: &str) -> Option<Vec<Value>> {
+        let runs = self
+            .data
+            .as_ref()?
+            .pointer("/resultData/runData")?
+            .get(node)?;
+        let items = runs
+            .as_array()?
+            .last()?
+            .pointer("/data/main/0")?
+            .as_array()?;
+
+        Some(items.iter().map(|item| item["json"].clone()).collect())
+    }
+
+    /// Every run of a node in the execution, with the items it was given and output. Only known when the execution
+    /// was fetched with its data.
+    pub fn node_runs(&self, node: &str) -> Option<&Value> {
+        self.data
+            .as_ref()?
+            .pointer("/resultData/runData")?
+            .get(node)
+    }
+
+    /// The node that started the execution and the first item it output, e.g. a webhook call's headers, query and
+    /// body. The trigger is the node whose first run wasn't given anything by another node.
+    pub fn trigger_item(&self) -> Option<(&str, &Value)> {
+        self.nodes_run().into_iter().find_map(|node| {
+            let run = self.node_runs(node)?.get(0)?;
+            let has_source = run["source"]
+                .as_array()
+                .is_some_and(|sources| sources.iter().any(|source| !source.is_null()));
+            if has_source {
+                return None;
+            }
+            Some((node, run.pointer("/data/main/0/0/json")?))
+        })
+    }
+
+    /// The nodes that ran in the execution, in no particular order.
+    pub fn nodes_run(&self) -> Vec<&str> {
+        match self
+            .data
+            .as_ref()
+            .and_then(|data| data.pointer("/resultData/runData"))
+        {
+            Some(Value::Object(run_data)) => run_data.keys().map(String::as_str).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// A digest of what each node that ran did, with up to `max_items` of the items it output.
+    pub fn node_digests(&self, max_items: usize) -> BTreeMap<String, NodeDigest> {
+        self.nodes_run()
+            .into_iter()
+            .filter_map(|node| {
+                let runs = self.node_runs(node)?.as_array()?;
+                let items: Vec<&Value> = runs
+                    .iter()
+                    .filter_map(|run| run.pointer("/data/main")?.as_array())
+                    .flatten()
+                    .filter_map(Value::as_array)
+                    .flatten()
+                    .map(|item| &item["json"])
+                    .collect();
+
+                let digest = NodeDigest {
+                    runs: runs.len(),
+                    items: items.len(),
+                    size_bytes: serde_json::to_string(runs).map_or(0, |json| json.len()),
+                    fields: items
+                        .iter()
+                        .filter_map(|item| item.as_object())
+                        .flat_map(|item| item.keys().cloned())
+                        .collect(),
+                    sample: items
+                        .iter()
+                        .take(max_items)
+                        .map(|item| (*item).clone())
+                        .collect(),
+                    execution_time_ms: runs
+                        .iter()
+                        .filter_map(|run| run["executionTime"].as_u64())
+                        .sum(),
+                    error: runs
+                        .iter()
+                        .find_map(|run| run.pointer("/error/message")?.as_str())
+                        .map(ToString::to_string),
+                };
+
+                Some((node.to_string(), digest))
+            })
+            .collect()
+    }
+
+    /// How long the execution took, if it's finished.
+    pub fn duration_ms(&self) -> Option<i64> {
+        let timestamp = |timestamp: Option<&str>| DateTime::parse_from_rfc3339(timestamp?).ok();
+        let started_at = timestamp(self.started_at.as_deref())?;
+        let stopped_at = timestamp(self.stopped_at.as_deref())?;
+
+        Some((stopped_at - started_at).num_milliseconds())
+    }
+
+    /// When a node first started running, in milliseconds since the epoch.
+    fn node_start_time(&self, node: &str) -> Option<u64> {
+        self.node_runs(node)?.get(0)?["startTime"].as_u64()
+    }
+
+    /// Works out which node made the execution fail, with what error and on which input. `None` if it didn't fail.
+    pub fn diagnose(&self, max_items: usize) -> Option<Diagnosis> {
+        let data = self.data.as_ref()?;
+        let top_level = data.pointer("/resultData/error");
+
+        // A node's own error is kept on its run, and is usually more specific than the execution's
+        let failed_run = self.nodes_run().into_iter().find_map(|node| {
+            let run = self.node_runs(node)?.as_array()?.last()?;
+            run.get("error").map(|error| (node, run, error))
+        });
+        let node = top_level
+            .and_then(|error| error.pointer("/node/name")?.as_str())
+            .or(failed_run.map(|(node, _, _)| node))
+            .or(self.last_node());
+        let error = failed_run
+            .filter(|(failed, _, _)| Some(*failed) == node)
+            .map(|(_, _, error)| error)
+            .or(top_level)?;
+
+        let text = |key: &str| {
+            error
+                .get(key)
+                .or_else(|| top_level?.get(key))
+                .and_then(|value| match value {
+                    Value::String(text) => Some(text.clone()),
+                    Value::Number(number) => Some(number.to_string()),
+                    _ => None,
+                })
+        };
+        let item_index = error
+            .pointer("/context/itemIndex")
+            .or_else(|| top_level?.pointer("/context/itemIndex"))
+            .and_then(Value::as_u64);
+
+        // Where the failing node's input came from, from the run's `source`
+        let run = node
+            .and_then(|node| self.node_runs(node)?.as_array()?.last())
+            .cloned()
+            .unwrap_or_default();
+        let source = run.pointer("/source/0");
+        let input_from = source
+            .and_then(|source| source["previousNode"].as_str())
+            .map(ToString::to_string);
+        let input: Vec<Value> = input_from
+            .as_deref()
+            .and_then(|previous| {
+                let source = source?;
+                let runs = self.node_runs(previous)?.as_array()?;
+                let run = runs.get(source["previousNodeRun"].as_u64().unwrap_or(0) as usize)?;
+                let output = source["previousNodeOutput"].as_u64().unwrap_or(0);
+                run.pointer(&format!("/data/main/{output}"))?
+                    .as_array()
+                    .cloned()
+            })
+            .unwrap_or_default()
+            .into_iter()
+            .map(|item| item["json"].clone())
+            .collect();
+
+        let input_items = match item_index.and_then(|index| input.get(index as usize)) {
+            Some(item) => vec![item.clone()],
+            None => input.iter().take(max_items).cloned().collect(),
+        };
+
+        let message = text("message").unwrap_or_else(|| "Unknown error".to_string());
+        // Some nodes only mention the status code in the message, e.g. "Request failed with status code 401"
+        let http_code = text("httpCode").or_else(|| {
+            let (_, code) = message.split_once("status code ")?;
+            let code = code.get(..3)?;
+            code.chars()
+                .all(|c| c.is_ascii_digit())
+                .then(|| code.to_string())
+        });
+        let node_type = error
+            .pointer("/node/type")
+            .or_else(|| {
+                self.extra
+                    .get("workflowData")?
+                    .get("nodes")?
+                    .as_array()?
+                    .iter()
+                    .find(|candidate| candidate["name"].as_str() == node)?
+                    .get("type")
+            })
+            .and_then(Value::as_str)
+            .map(ToString::to_string);
+
+        Some(Diagnosis {
+            node: node.map(ToString::to_string),
+            node_type,
+            hint: failure_hint(&message, http_code.as_deref()),
+            description: text("description"),
+            http_code,
+            item_index,
+            stack: text("stack")
+                .map(|stack| {
+                    stack
+                        .lines()
+                        .take(MAX_STACK_LINES)
+                        .map(|line| line.trim().to_string())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            input_from,
+            input_item_count: input.len(),
+            input_items,
+            message,
+        })
+    }
+
+    pub fn summary(&self) -> ExecutionSummary {
+        ExecutionSummary {
+            id: self.id.clone(),
+            workflow_id: self.workflow_id.clone(),
+            status: self.status.clone(),
+            mode: self.mode.clone(),
+            started_at: self.started_at.clone(),
+            stopped_at: self.stopped_at.clone(),
+            error: self.error(),
+        }
+    }
+}
+
+/// Compares what each node did in two executions, e.g. of the same workflow on different days. Nodes are in the order
+/// they first ran in, with those that only ran in the second execution last.
+pub fn compare(first: &Execution, second: &Execution) -> Vec<NodeComparison> {
+    let digests = [first.node_digests(1), second.node_digests(1)];
+
+    let mut nodes: Vec<&str> = digests[0]
+        .keys()
+        .chain(digests[1].keys())
+        .map(String::as_str)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    nodes.sort_by_key(|node| {
+        (
+            first.node_start_time(node).is_none(),
+            first.node_start_time(node).or(second.node_start_time(node)),
+        )
+    });
+
+    nodes
+        .into_iter()
+        .map(|node| {
+            let [first, second] = [&digests[0], &digests[1]].map(|digests| digests.get(node));
+            let status = |digest: Option<&NodeDigest>| match digest {
+                None => "not run",
+                Some(digest) if digest.error.is_some() => "error",
+                Some(_) => "success",
+            };
+            let fields = |digest: Option<&NodeDigest>| {
+                digest
+                    .map(|digest| digest.fields.clone())
+                    .unwrap_or_default()
+            };
+            let first_item = |digest: Option<&NodeDigest>| {
+                digest
+                    .and_then(|digest| digest.sample.first()?.as_object().cloned())
+                    .unwrap_or_default()
+            };
+
+            let (first_fields, second_fields) = (fields(first), fields(second));
+            let (first_item, second_item) = (first_item(first), first_item(second));
+            let changed_values = first_item
+                .iter()
+                .filter_map(|(field, value)| {
+                    let other = second_item.get(field)?;
+                    (value != other).then(|| (field.clone(), [value.clone(), other.clone()]))
+                })
+                .collect();
+
+            NodeComparison {
+                node: node.to_string(),
+                status: [status(first), status(second)],
+                runs: [first, second].map(|digest| digest.map_or(0, |digest| digest.runs)),
+                items: [first, second].map(|digest| digest.map_or(0, |digest| digest.items)),
+                execution_time_ms: [first, second]
+                    .map(|digest| digest.map_or(0, |digest| digest.execution_time_ms)),
+                fields_added: &second_fields - &first_fields,
+                fields_removed: &first_fields - &second_fields,
+                changed_values,
+                errors: [first, second]
+                    .map(|digest| digest.and_then(|digest| digest.error.clone())),
+            }
+        })
+        .collect()
+}
+
+/// The columns of [`to_csv`].
+const CSV_COLUMNS: [&str; 10] = [
+    "id",
+    "workflowId",
+    "workflowName",
+    "status",
+    "mode",
+    "startedAt",
+    "stoppedAt",
+    "durationMs",
+    "errorNode",
+    "errorMessage",
+];
+
+/// A table of executions, one row each, as CSV. Workflow names are looked up in `workflow_names`, and left empty for
+/// workflows that aren't in it.
+pub fn to_csv(executions: &[Execution], workflow_names: &HashMap<WorkflowId, String>) -> String {
+    let mut csv = CSV_COLUMNS.join(",");
+    csv.push('\n');
+
+    for execution in executions {
+        let error = execution.error();
+        let fields = [
+            Some(execution.id.to_string()),
+            execution.workflow_id.as_ref().map(ToString::to_string),
+            execution
+                .workflow_id
+                .as_ref()
+                .and_then(|workflow_id| workflow_names.get(workflow_id).cloned()),
+            execution.status.clone(),
+            execution.mode.clone(),
+            execution.started_at.clone(),
+            execution.stopped_at.clone(),
+            execution.duration_ms().map(|duration| duration.to_string()),
+            error.as_ref().and_then(|error| error.node.clone()),
+            error.map(|error| error.message),
+        ];
+        let row: Vec<String> = fields
+            .iter()
+            .map(|field| csv_field(field.as_deref().unwrap_or_default()))
+            .collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+/// Quotes a CSV field if it has to be, doubling any quotes in it.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Suggests where to look for the cause of a node's error, going by its message and HTTP status.
+fn failure_hint(message: &str, http_code: Option<&str>) -> &'static str {
+    let message = message.to_lowercase();
+
+    match http_code {
+        Some("401" | "403") => {
+            return "The service rejected the node's credentials. Check the credentials assigned to the node, and that they haven't expired or lost access";
+        }
+        Some("404") => {
+            return "The service couldn't find what the node asked for. Check the URL or ID the node uses, which may come from the input items";
+        }
+        Some("429") => {
+            return "The service is rate limiting the node. Add a Wait node or batching before it, or turn on Retry On Fail";
+        }
+        Some(code) if code.starts_with('5') => {
+            return "The service the node called had an error of its own. Turn on Retry On Fail if it's intermittent";
+        }
+        _ => {}
+    }
+
+    if message.contains("credential") {
+        "The node's credentials are missing or invalid. Check the credentials assigned to it"
+    } else if message.contains("timeout") || message.contains("timed out") {
+        "The node took too long. Check the service is reachable, or raise the node's or workflow's timeout"
+    } else if message.contains("cannot read properties of undefined")
+        || message.contains("is not defined")
+        || message.contains("invalid syntax")
+    {
+        "An expression or piece of code in the node refers to something that isn't there. Compare it with the fields of the input items"
+    } else if message.contains("econnrefused") || message.contains("enotfound") {
+        "The node couldn't connect to the service. Check its URL, and that n8n can reach it"
+    } else {
+        "Check the node's parameters against the input items it failed on"
+    }
+}
+
+/// Replaces each execution in a page of them with its summary, unless `include_data` is set.
+pub fn summarize_page(
+    page: Paginated<Value>,
+    include_data: bool,
+) -> Result<Paginated<Value>, serde_json::Error> {
+    if include_data {
+        return Ok(page);
+    }
+
+    page.try_map(|execution| {
+        let execution: Execution = serde_json::from_value(execution)?;
+        serde_json::to_value(execution.summary())
+    })
+}
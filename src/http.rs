@@ -0,0 +1,440 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, Request, State},
+    http::{HeaderMap, StatusCode, header},
+    middleware::{self, Next},
+    response::{
+        IntoResponse, Response,
+        sse::{Event, Sse},
+    },
+    routing::{get, post},
+};
+use axum_server::{Handle, tls_rustls::RustlsConfig};
+use futures::{SinkExt, Stream, StreamExt};
+use rmcp::{ServiceExt, model::ClientJsonRpcMessage};
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    fmt,
+    net::SocketAddr,
+    os::unix::fs::FileTypeExt,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::{RwLock, mpsc};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::{
+    sync::{CancellationToken, PollSender},
+    task::TaskTracker,
+};
+
+use crate::{
+    access::{Role, ToolAccess},
+    base_url::BaseUrl,
+    client::{Connection, DeploymentKind, Instances, N8nClient},
+    config::{ClientToken, Features},
+    policy::Policies,
+    server::Server,
+    shutdown,
+};
+
+const SSE_PATH: &str = "/sse";
+const POST_PATH: &str = "/message";
+const HEALTH_PATH: &str = "/healthz";
+const API_KEY_HEADER: &str = "X-N8N-API-KEY";
+const BASE_URL_HEADER: &str = "X-N8N-BASE-URL";
+
+type Sessions = Arc<RwLock<HashMap<String, mpsc::Sender<ClientJsonRpcMessage>>>>;
+
+/// The MCP server's HTTP transport: an SSE stream per session at `/sse`, with client messages posted to `/message`.
+///
+/// rmcp's own `SseServer` builds its router internally, which leaves no way of adding authentication to it.
+#[derive(Clone)]
+struct App {
+    sessions: Sessions,
+    /// Every token a client can authenticate with. When empty, clients don't need to authenticate at all.
+    tokens: Arc<[ClientToken]>,
+    tenancy: Arc<Tenancy>,
+    access: ToolAccess,
+    features: Features,
+    policies: Policies,
+    in_flight: TaskTracker,
+    ct: CancellationToken,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MessageQuery {
+    session_id: String,
+}
+
+/// Where the HTTP transport accepts connections.
+pub enum Listen {
+    Tcp(SocketAddr),
+    /// A Unix domain socket at the given path, for clients on the same host.
+    Unix(PathBuf),
+}
+
+impl fmt::Display for Listen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(addr) => write!(f, "{addr}"),
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+pub struct HttpConfig {
+    pub listen: Listen,
+    /// When set, every request must have an `Authorization: Bearer <token>` header with this token (or one of the
+    /// client tokens). Clients using it have the admin role.
+    pub bearer_token: Option<String>,
+    pub clients: Vec<ClientToken>,
+    /// When set, connections are served over HTTPS rather than plain HTTP.
+    pub tls: Option<TlsConfig>,
+    pub tenancy: Tenancy,
+    pub access: ToolAccess,
+    pub features: Features,
+    /// Every session checks workflows against the same policies, whichever instance it talks to.
+    pub policies: Policies,
+}
+
+/// Which n8n instance each session talks to.
+pub enum Tenancy {
+    /// Every session shares the same instance and API key, and can use the other configured instances too.
+    Single {
+        n8n: N8nClient,
+        instances: Instances,
+    },
+    /// Each client supplies its own n8n API key (and optionally base URL) when it connects.
+    Multi {
+        default_base_url: Option<BaseUrl>,
+        timeout: Option<Duration>,
+    },
+}
+
+/// PEM-encoded certificate chain and private key files for serving HTTPS.
+pub struct TlsConfig {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
+/// Starts serving MCP over HTTP, until the cancellation token is cancelled.
+///
+/// Once it is, no new connections or tool calls are accepted, and every session is closed once the tool calls
+/// already running have finished.
+pub async fn serve(config: HttpConfig, ct: CancellationToken) -> std::io::Result<()> {
+    let mut tokens = config.clients;
+    if let Some(token) = config.bearer_token {
+        tokens.push(ClientToken {
+            name: None,
+            token,
+            role: Role::Admin,
+        });
+    }
+
+    if tokens.is_empty() {
+        tracing::warn!(
+            "No bearer token is set (MCP_BEARER_TOKEN), so anyone who can reach {} can use the n8n API key",
+            config.listen
+        );
+    }
+
+    let app = App {
+        sessions: Arc::default(),
+        tokens: tokens.into(),
+        tenancy: Arc::new(config.tenancy),
+        access: config.access,
+        features: config.features,
+        policies: config.policies,
+        in_flight: TaskTracker::new(),
+        ct: CancellationToken::new(),
+    };
+
+    let in_flight = app.in_flight.clone();
+    let sessions_ct = app.ct.clone();
+    let drained = ct.clone();
+    tokio::spawn(async move {
+        drained.cancelled().await;
+        shutdown::drain(&in_flight).await;
+        sessions_ct.cancel();
+    });
+
+    let router = Router::new()
+        .route(SSE_PATH, get(sse_handler))
+        .route(POST_PATH, post(message_handler))
+        .layer(middleware::from_fn_with_state(app.clone(), authenticate))
+        // Health checks come from load balancers and orchestrators, which won't have the bearer token
+        .route(HEALTH_PATH, get(health_handler))
+        .with_state(app);
+
+    let shutdown = async move { ct.cancelled().await };
+
+    match (config.listen, config.tls) {
+        (Listen::Tcp(addr), None) => {
+            let listener = tokio::net::TcpListener::bind(addr).await?;
+            tracing::info!("Listening on http://{addr}");
+
+            axum::serve(listener, router)
+                .with_graceful_shutdown(shutdown)
+                .await
+        }
+        (Listen::Tcp(addr), Some(tls)) => {
+            let rustls = RustlsConfig::from_pem_file(&tls.cert, &tls.key).await?;
+
+            let handle = Handle::new();
+            let graceful = handle.clone();
+            tokio::spawn(async move {
+                shutdown.await;
+                graceful.graceful_shutdown(None);
+            });
+
+            tracing::info!("Listening on https://{addr}");
+
+            axum_server::bind_rustls(addr, rustls)
+                .handle(handle)
+                .serve(router.into_make_service())
+                .await
+        }
+        (Listen::Unix(path), None) => {
+            remove_stale_socket(&path)?;
+            let listener = tokio::net::UnixListener::bind(&path)?;
+            tracing::info!("Listening on unix:{}", path.display());
+
+            let res = axum::serve(listener, router)
+                .with_graceful_shutdown(shutdown)
+                .await;
+
+            std::fs::remove_file(&path)?;
+            res
+        }
+        (Listen::Unix(_), Some(_)) => {
+            Err(std::io::Error::other("TLS isn't supported on Unix sockets"))
+        }
+    }
+}
+
+/// Removes a socket left behind by a previous run, which would otherwise stop us binding to its path.
+fn remove_stale_socket(path: &Path) -> std::io::Result<()> {
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => std::fs::remove_file(path),
+        Ok(_) => Err(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            format!("{} already exists and isn't a socket", path.display()),
+        )),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Checks the request's bearer token, and passes the client it belongs to on to the handler.
+async fn authenticate(State(app): State<App>, mut request: Request, next: Next) -> Response {
+    if app.tokens.is_empty() {
+        return next.run(request).await;
+    }
+
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    // Every token is compared, so the time taken doesn't give away which one (if any) matched
+    let client = token.and_then(|token| {
+        app.tokens.iter().fold(None, |found, client| {
+            let matches = constant_time_eq(token.as_bytes(), client.token.as_bytes());
+            found.or(matches.then(|| client.clone()))
+        })
+    });
+
+    match client {
+        Some(client) => {
+            request.extensions_mut().insert(client);
+            next.run(request).await
+        }
+        None => (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+        )
+            .into_response(),
+    }
+}
+
+/// Compares two tokens without returning early, so the comparison's timing doesn't leak how much of a guess was right.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// Works out which n8n instance a new session should talk to.
+///
+/// In multi-tenant mode the client must send its own `X-N8N-API-KEY` header, and can send `X-N8N-BASE-URL`
+/// to use a different instance than the configured one.
+fn session_server(
+    app: &App,
+    headers: &HeaderMap,
+    role: Role,
+) -> Result<Server, (StatusCode, &'static str)> {
+    let (n8n, instances, features) = match app.tenancy.as_ref() {
+        Tenancy::Single { n8n, instances } => {
+            (n8n.clone(), instances.clone(), app.features.clone())
+        }
+        Tenancy::Multi {
+            default_base_url,
+            timeout,
+        } => {
+            let header = |name: &str| {
+                headers
+                    .get(name)
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::trim)
+                    .filter(|value| !value.is_empty())
+            };
+
+            let api_key = header(API_KEY_HEADER).ok_or((
+                StatusCode::BAD_REQUEST,
+                "The X-N8N-API-KEY header is required",
+            ))?;
+            let base_url = match header(BASE_URL_HEADER) {
+                Some(base_url) => BaseUrl::parse(base_url).map_err(|_| {
+                    (
+                        StatusCode::BAD_REQUEST,
+                        "The X-N8N-BASE-URL header must be an http:// or https:// URL",
+                    )
+                })?,
+                None => default_base_url.clone().ok_or((
+                    StatusCode::BAD_REQUEST,
+                    "The X-N8N-BASE-URL header is required, as no default n8n instance is configured",
+                ))?,
+            };
+
+            let n8n = N8nClient::new(Connection {
+                api_key: api_key.to_string(),
+                deployment: DeploymentKind::detect(&base_url),
+                webhook_base_url: None,
+                base_url,
+                n8n_user: None,
+                n8n_password: None,
+                timeout: *timeout,
+            })
+            .map_err(|_| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    "The X-N8N-API-KEY header isn't valid",
+                )
+            })?;
+            let mut features = app.features.clone();
+            // Every tenant would share the one snapshot directory, where workflows are only told apart by ID
+            features.snapshot_dir = None;
+            // Or the one backup directory, so one tenant's exports could be read back by another
+            features.backup_dir = None;
+            // The other instances' API keys aren't shared with clients that bring their own
+            (n8n, Instances::default(), features)
+        }
+    };
+
+    Ok(Server::new(n8n, features)
+        .with_access(app.access.clone().role(role))
+        .with_instances(instances)
+        .with_policies(app.policies.clone())
+        .with_in_flight(app.in_flight.clone()))
+}
+
+async fn sse_handler(
+    State(app): State<App>,
+    client: Option<Extension<ClientToken>>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, &'static str)> {
+    // Without any tokens configured, every client is trusted
+    let role = client.as_ref().map_or(Role::Admin, |client| client.role);
+    let server = session_server(&app, &headers, role)?;
+
+    let session_id = format!("{:016x}", rand::random::<u128>());
+    let client_name = client.as_ref().and_then(|client| client.name.as_deref());
+    tracing::info!(session_id, client = client_name, %role, "New SSE connection");
+
+    let (from_client_tx, from_client_rx) = mpsc::channel(64);
+    let (to_client_tx, to_client_rx) = mpsc::channel(64);
+
+    app.sessions
+        .write()
+        .await
+        .insert(session_id.clone(), from_client_tx);
+
+    // The session ends when either the server shuts down or the client goes away
+    let session_ct = app.ct.child_token();
+    let disconnected = session_ct.clone().drop_guard();
+
+    let sessions = app.sessions.clone();
+    let id = session_id.clone();
+    tokio::spawn(async move {
+        let transport = (
+            PollSender::new(to_client_tx).sink_map_err(std::io::Error::other),
+            ReceiverStream::new(from_client_rx),
+        );
+
+        // rmcp doesn't watch the cancellation token until the client has initialized the session
+        let service = tokio::select! {
+            service = server.serve_with_ct(transport, session_ct.clone()) => service,
+            _ = session_ct.cancelled() => Err(std::io::Error::other("the session was closed before it was initialized")),
+        };
+
+        match service {
+            Ok(service) => {
+                if let Err(err) = service.waiting().await {
+                    tracing::error!(session_id = id, "Session ended with an error: {err}");
+                }
+            }
+            Err(err) => tracing::error!(session_id = id, "Could not start session: {err}"),
+        }
+
+        sessions.write().await.remove(&id);
+        tracing::info!(session_id = id, "Session closed");
+    });
+
+    let endpoint = Event::default()
+        .event("endpoint")
+        .data(format!("{POST_PATH}?sessionId={session_id}"));
+
+    let messages = ReceiverStream::new(to_client_rx).filter_map(move |message| {
+        // Held by the stream, so it's dropped (cancelling the session) when the client disconnects
+        let _ = &disconnected;
+
+        let event = match serde_json::to_string(&message) {
+            Ok(message) => Some(Ok(Event::default().event("message").data(message))),
+            Err(err) => {
+                tracing::error!("Could not serialize message: {err}");
+                None
+            }
+        };
+        futures::future::ready(event)
+    });
+
+    Ok(Sse::new(
+        futures::stream::once(futures::future::ok(endpoint)).chain(messages),
+    ))
+}
+
+async fn health_handler(State(app): State<App>) -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "status": "ok",
+        "sessions": app.sessions.read().await.len(),
+    }))
+}
+
+async fn message_handler(
+    State(app): State<App>,
+    Query(MessageQuery { session_id }): Query<MessageQuery>,
+    Json(message): Json<ClientJsonRpcMessage>,
+) -> StatusCode {
+    let Some(tx) = app.sessions.read().await.get(&session_id).cloned() else {
+        return StatusCode::NOT_FOUND;
+    };
+
+    if tx.send(message).await.is_err() {
+        return StatusCode::GONE;
+    }
+
+    StatusCode::ACCEPTED
+}
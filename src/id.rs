@@ -0,0 +1,77 @@
+use rmcp::schemars::{self, JsonSchema};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use std::{fmt, ops::Deref};
+
+/// Declares the ID type of one kind of n8n object, so that e.g. a tag's ID can't be passed where a workflow's is
+/// expected.
+///
+/// n8n sends some IDs (executions', and older instances' workflow IDs) as numbers, so they're read from either, and
+/// always kept as strings.
+macro_rules! id_type {
+    ($(#[$doc:meta])* $name:ident) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, JsonSchema)]
+        #[serde(transparent)]
+        pub struct $name(String);
+
+        impl From<String> for $name {
+            fn from(id: String) -> Self {
+                Self(id)
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(id: &str) -> Self {
+                Self(id.to_string())
+            }
+        }
+
+        impl Deref for $name {
+            type Target = str;
+
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                string_or_number(deserializer).map(Self)
+            }
+        }
+    };
+}
+
+id_type!(
+    /// The ID of a workflow.
+    WorkflowId
+);
+id_type!(
+    /// The ID of an execution. n8n returns these as numbers, unlike every other ID.
+    ExecutionId
+);
+id_type!(
+    /// The ID of a tag.
+    TagId
+);
+id_type!(
+    /// The ID of a project, which workflows and executions can be filtered by.
+    ProjectId
+);
+
+fn string_or_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::String(id) => Ok(id),
+        Value::Number(id) => Ok(id.to_string()),
+        other => Err(serde::de::Error::custom(format!(
+            "expected an ID, found {other}"
+        ))),
+    }
+}
@@ -0,0 +1,250 @@
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::LazyLock,
+};
+
+use crate::{
+    search,
+    workflow::{Node, Workflow},
+};
+
+/// How many nodes can run one after another, with no branches or merges, before it's suggested that some of them are
+/// moved into a sub-workflow.
+const MAX_LINEAR_CHAIN: usize = 12;
+
+/// Node types that call out over HTTP and so are worth retrying when they fail.
+const HTTP_NODE_TYPES: &[&str] = &["n8n-nodes-base.httpRequest", "n8n-nodes-base.graphql"];
+
+/// Parameter names that usually hold a secret, e.g. `apiKey` or `Authorization`.
+static SECRET_NAME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(password|passwd|secret|token|api[_-]?key|authorization|private[_-]?key|access[_-]?key)")
+        .expect("the secret parameter name pattern is valid")
+});
+
+/// Values that look like a secret whatever they're called: bearer tokens, and well-known API key and private key
+/// formats.
+static SECRET_VALUE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"(?i:bearer\s+[a-z0-9._~+/-]{16,})",
+        r"|sk-[A-Za-z0-9_-]{20,}",
+        r"|xox[abprs]-[A-Za-z0-9-]{10,}",
+        r"|AKIA[0-9A-Z]{16}",
+        r"|gh[pousr]_[A-Za-z0-9]{30,}",
+        r"|-----BEGIN [A-Z ]*PRIVATE KEY-----",
+    ))
+    .expect("the secret value pattern is valid")
+});
+
+/// A problem `lint_workflow` found. n8n runs workflows with any of them, but they make the workflow fragile, insecure
+/// or hard to follow.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Issue {
+    /// Which check found it, e.g. `hardcoded-secret`.
+    pub rule: &'static str,
+    pub severity: Severity,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Will go wrong when the workflow runs.
+    Error,
+    /// Is likely to go wrong, or is a risk.
+    Warning,
+    /// Worth knowing, but may well be intended.
+    Info,
+}
+
+/// Runs every check on a workflow, returning what was found, most severe first.
+pub fn lint(workflow: &Workflow) -> Vec<Issue> {
+    let nodes: Vec<&Node> = workflow
+        .nodes
+        .iter()
+        .filter(|node| !node.is_sticky_note())
+        .collect();
+
+    let mut issues = Vec::new();
+    error_handling(workflow, &nodes, &mut issues);
+    hardcoded_secrets(&nodes, &mut issues);
+    webhook_responses(&nodes, &mut issues);
+    linear_chains(workflow, &nodes, &mut issues);
+    http_retries(&nodes, &mut issues);
+
+    issues.sort_by_key(|issue| match issue.severity {
+        Severity::Error => 0,
+        Severity::Warning => 1,
+        Severity::Info => 2,
+    });
+
+    issues
+}
+
+/// Without an error workflow or any node handling its own errors, a failed execution goes unnoticed unless someone
+/// looks for it.
+fn error_handling(workflow: &Workflow, nodes: &[&Node], issues: &mut Vec<Issue>) {
+    let handles_errors = workflow.is_error_workflow()
+        || nodes.iter().any(|node| {
+            node.extra.get("onError").and_then(Value::as_str) == Some("continueErrorOutput")
+        });
+
+    if workflow.error_workflow().is_none() && !handles_errors {
+        issues.push(Issue {
+            rule: "no-error-handling",
+            severity: Severity::Warning,
+            node: None,
+            message: "Nothing handles failures: no error workflow is set, and no node sends its errors down an error output. Set an error workflow (e.g. with set_error_workflow) so failures are noticed".to_string(),
+        });
+    }
+}
+
+/// Secrets belong in credentials, where they're encrypted and not copied along with the workflow. Expressions are
+/// skipped, since they're usually reading a value from elsewhere.
+fn hardcoded_secrets(nodes: &[&Node], issues: &mut Vec<Issue>) {
+    for node in nodes {
+        let mut strings = Vec::new();
+        for (name, value) in node.parameters.iter().flatten() {
+            search::collect_strings(value, format!("parameters.{name}"), &mut strings);
+        }
+
+        for (path, value) in strings {
+            if value.starts_with('=') || value.trim().is_empty() {
+                continue;
+            }
+
+            let name = path.rsplit(['.', '[']).next().unwrap_or_default();
+            let secret_name = SECRET_NAME.is_match(name) && value.trim().len() >= 8;
+            if secret_name || SECRET_VALUE.is_match(value) {
+                issues.push(Issue {
+                    rule: "hardcoded-secret",
+                    severity: Severity::Error,
+                    node: Some(node.name.clone()),
+                    message: format!(
+                        "{path} looks like a hardcoded secret. Store it in a credential instead, so it isn't exposed to everyone who can see or export the workflow"
+                    ),
+                });
+            }
+        }
+    }
+}
+
+/// A webhook set to respond from a Respond to Webhook node hangs until it times out if there isn't one, and a Respond to
+/// Webhook node does nothing unless the webhook is set to use it.
+fn webhook_responses(nodes: &[&Node], issues: &mut Vec<Issue>) {
+    let has_respond_node = nodes
+        .iter()
+        .any(|node| node.kind == "n8n-nodes-base.respondToWebhook");
+
+    for node in nodes
+        .iter()
+        .filter(|node| node.kind == "n8n-nodes-base.webhook")
+    {
+        let response_mode = node
+            .parameters
+            .as_ref()
+            .and_then(|parameters| parameters.get("responseMode"))
+            .and_then(Value::as_str)
+            .unwrap_or("onReceived");
+
+        let (severity, message) = match (response_mode, has_respond_node) {
+            ("responseNode", false) => (
+                Severity::Error,
+                "The webhook is set to respond from a Respond to Webhook node, but there isn't one, so callers will wait until the request times out. Add a Respond to Webhook node",
+            ),
+            ("responseNode", true) => continue,
+            (_, true) => (
+                Severity::Warning,
+                "There's a Respond to Webhook node, but the webhook isn't set to use it (responseMode 'responseNode'), so it will be ignored",
+            ),
+            ("onReceived", false) => (
+                Severity::Info,
+                "The webhook responds as soon as it's called, before the workflow has run. Add a Respond to Webhook node if callers need a result",
+            ),
+            _ => continue,
+        };
+
+        issues.push(Issue {
+            rule: "webhook-response",
+            severity,
+            node: Some(node.name.clone()),
+            message: message.to_string(),
+        });
+    }
+}
+
+/// Long runs of nodes one after another are hard to follow and test, and are often better split into sub-workflows.
+fn linear_chains(workflow: &Workflow, nodes: &[&Node], issues: &mut Vec<Issue>) {
+    let mut outgoing: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut incoming: HashMap<&str, usize> = HashMap::new();
+    for (source, outputs) in &workflow.connections {
+        for connection in outputs
+            .get("main")
+            .into_iter()
+            .flatten()
+            .flatten()
+            .flatten()
+        {
+            outgoing
+                .entry(source.as_str())
+                .or_default()
+                .push(connection.node.as_str());
+            *incoming.entry(connection.node.as_str()).or_default() += 1;
+        }
+    }
+
+    // The node after `name`, when there's nothing else before or beside it
+    let next = |name: &str| match outgoing.get(name).map(Vec::as_slice) {
+        Some([next]) if incoming.get(next) == Some(&1) => Some(*next),
+        _ => None,
+    };
+    let continues_chain: HashSet<&str> = nodes.iter().filter_map(|node| next(&node.name)).collect();
+
+    for start in nodes
+        .iter()
+        .filter(|node| !continues_chain.contains(node.name.as_str()))
+    {
+        let mut chain = vec![start.name.as_str()];
+        while let Some(node) = chain.last().and_then(|name| next(name)) {
+            if chain.contains(&node) {
+                break;
+            }
+            chain.push(node);
+        }
+
+        if chain.len() > MAX_LINEAR_CHAIN {
+            issues.push(Issue {
+                rule: "long-linear-chain",
+                severity: Severity::Info,
+                node: Some(start.name.clone()),
+                message: format!(
+                    "{} nodes run one after another, from '{}' to '{}'. Consider moving some of them into a sub-workflow called with an Execute Workflow node",
+                    chain.len(),
+                    chain[0],
+                    chain[chain.len() - 1]
+                ),
+            });
+        }
+    }
+}
+
+/// Requests to other services fail now and then for reasons that go away on their own, which retrying gets past.
+fn http_retries(nodes: &[&Node], issues: &mut Vec<Issue>) {
+    for node in nodes {
+        let retries = node.extra.get("retryOnFail").and_then(Value::as_bool) == Some(true);
+        if HTTP_NODE_TYPES.contains(&node.kind.as_str()) && !retries && node.disabled != Some(true)
+        {
+            issues.push(Issue {
+                rule: "http-retry",
+                severity: Severity::Warning,
+                node: Some(node.name.clone()),
+                message: "Retry On Fail isn't turned on, so a single failed request fails the execution. Set retryOnFail (and maxTries and waitBetweenTries) on the node".to_string(),
+            });
+        }
+    }
+}
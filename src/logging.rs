@@ -0,0 +1,144 @@
+use rmcp::{
+    model::{LoggingLevel, LoggingMessageNotificationParam},
+    service::{Peer, RoleServer},
+};
+use serde_json::json;
+use std::{
+    env,
+    sync::{Mutex, OnceLock},
+    time::Instant,
+};
+
+fn severity(level: &LoggingLevel) -> u8 {
+    match level {
+        LoggingLevel::Debug => 0,
+        LoggingLevel::Info => 1,
+        LoggingLevel::Notice => 2,
+        LoggingLevel::Warning => 3,
+        LoggingLevel::Error => 4,
+        LoggingLevel::Critical => 5,
+        LoggingLevel::Alert => 6,
+        LoggingLevel::Emergency => 7,
+    }
+}
+
+fn level_from_env(key: &str, default: LoggingLevel) -> LoggingLevel {
+    env::var(key)
+        .ok()
+        .and_then(|level| serde_json::from_value(json!(level.to_lowercase())).ok())
+        .unwrap_or(default)
+}
+
+/// Sends a `notifications/message` entry to the client for every request made to n8n.
+///
+/// Successful requests are logged at `N8N_REQUEST_LOG_LEVEL` (info by default), client errors as warnings
+/// and server errors or failed requests as errors. Anything below the session's level, which starts at
+/// `MCP_LOG_LEVEL` and can be changed by the client with `logging/setLevel`, isn't sent.
+pub struct RequestLogger {
+    peer: OnceLock<Peer<RoleServer>>,
+    level: Mutex<LoggingLevel>,
+    success_level: LoggingLevel,
+    enabled: bool,
+}
+
+impl RequestLogger {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            peer: OnceLock::new(),
+            level: Mutex::new(level_from_env("MCP_LOG_LEVEL", LoggingLevel::Info)),
+            success_level: level_from_env("N8N_REQUEST_LOG_LEVEL", LoggingLevel::Info),
+            enabled,
+        }
+    }
+
+    pub fn set_peer(&self, peer: Peer<RoleServer>) {
+        let _ = self.peer.set(peer);
+    }
+
+    pub fn set_level(&self, level: LoggingLevel) {
+        *self.level.lock().unwrap() = level;
+    }
+
+    pub async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        let (client, request) = request.build_split();
+        let request = request?;
+        let method = request.method().to_string();
+        let path = request.url().path().to_string();
+
+        let started = Instant::now();
+        let res = client.execute(request).await;
+        let duration_ms = started.elapsed().as_millis() as u64;
+
+        let (level, status) = match &res {
+            Ok(res) if res.status().is_server_error() => {
+                (LoggingLevel::Error, json!(res.status().as_u16()))
+            }
+            Ok(res) if res.status().is_client_error() => {
+                (LoggingLevel::Warning, json!(res.status().as_u16()))
+            }
+            Ok(res) => (self.success_level.clone(), json!(res.status().as_u16())),
+            Err(err) => (LoggingLevel::Error, json!(err.to_string())),
+        };
+
+        self.log(
+            level,
+            json!({
+                "method": method,
+                "path": path,
+                "status": status,
+                "durationMs": duration_ms,
+            }),
+        )
+        .await;
+
+        res
+    }
+
+    async fn log(&self, level: LoggingLevel, data: serde_json::Value) {
+        if self.enabled {
+            self.message("n8n", level, data).await;
+        }
+    }
+
+    /// Sends a log message from something other than a request, e.g. the failure watcher, unless it's below the
+    /// session's level. Sent whether or not request logging is turned on.
+    pub async fn message(&self, logger: &str, level: LoggingLevel, data: serde_json::Value) {
+        if severity(&level) < severity(&self.level.lock().unwrap()) {
+            return;
+        }
+
+        let Some(peer) = self.peer.get() else {
+            return;
+        };
+
+        let notification = LoggingMessageNotificationParam {
+            level,
+            logger: Some(logger.to_string()),
+            data,
+        };
+
+        if let Err(err) = peer.notify_logging_message(notification).await {
+            tracing::debug!("Could not send log message: {err}");
+        }
+    }
+}
+
+/// Lets a request be sent through a [`RequestLogger`] in place of calling `send` on it directly.
+pub trait SendLogged {
+    fn send_logged(
+        self,
+        logger: &RequestLogger,
+    ) -> impl Future<Output = Result<reqwest::Response, reqwest::Error>> + Send;
+}
+
+impl SendLogged for reqwest::RequestBuilder {
+    fn send_logged(
+        self,
+        logger: &RequestLogger,
+    ) -> impl Future<Output = Result<reqwest::Response, reqwest::Error>> + Send {
+        logger.send(self)
+    }
+}
@@ -1,29 +1,244 @@
-use rmcp::transport::sse_server::SseServer;
+use clap::Parser;
+use cli::{Cli, Command, ServeArgs};
+use client::N8nClient;
+use config::{Config, Transport};
+use http::{HttpConfig, Listen, Tenancy, TlsConfig};
+use rmcp::{ServiceExt, transport::stdio};
+use std::path::PathBuf;
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
 use tracing_subscriber::{
     layer::SubscriberExt,
     util::SubscriberInitExt,
     {self},
 };
+mod access;
+mod annotations;
+mod backup;
+mod base_url;
+mod cli;
+mod client;
+mod compare;
+mod completion;
+mod config;
+mod dependencies;
+mod diagram;
+mod docs;
+mod duplicate;
+mod endpoints;
+mod error;
+mod execution;
+mod http;
+mod id;
+mod lint;
+mod logging;
+mod output;
+mod page;
+mod policy;
+mod progress;
+mod prompts;
+mod query;
+mod reload;
+mod resources;
+mod response;
+mod scaffold;
+mod search;
 mod server;
+mod shutdown;
+mod snapshot;
+mod stats;
+mod tag;
+mod validate;
+mod watcher;
+mod workflow;
 use server::Server;
 
-const BIND_ADDRESS: &str = "127.0.0.1:8000";
-
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    // Logs go to stderr so they don't get mixed up with the protocol when using stdio
     tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| "debug".to_string().into()),
         )
-        .with(tracing_subscriber::fmt::layer())
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
         .init();
 
-    let ct = SseServer::serve(BIND_ADDRESS.parse()?)
-        .await?
-        .with_service(Server::from_env);
+    match cli.command {
+        Some(Command::Version) => {
+            println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+            Ok(())
+        }
+        Some(Command::ListTools { json }) => {
+            list_tools(json)?;
+            Ok(())
+        }
+        Some(Command::Check) => check(Config::load(cli.config.as_deref())?).await,
+        Some(Command::Serve(args)) => serve(cli.config, args).await,
+        None => serve(cli.config, cli.serve).await,
+    }
+}
+
+fn list_tools(json: bool) -> anyhow::Result<()> {
+    let tools = Server::tools();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&tools)?);
+        return Ok(());
+    }
+
+    for tool in tools {
+        let summary = tool.description.lines().next().unwrap_or_default();
+        println!("{:<40} {summary}", tool.name);
+    }
+
+    Ok(())
+}
+
+async fn check(config: Config) -> anyhow::Result<()> {
+    println!("Transport: {:?}", config.transport());
+
+    let access = config.tool_access()?;
+    let tools = Server::tools();
+    let available = tools
+        .iter()
+        .filter(|tool| access.allows(&tool.name))
+        .count();
+    println!("Tools: {available} of {} available", tools.len());
+
+    let instances = config.instances()?;
+    if !instances.is_empty() {
+        let names: Vec<&str> = instances.keys().map(String::as_str).collect();
+        println!("Other instances: {}", names.join(", "));
+    }
+
+    let policies = config.policies()?;
+    if !policies.is_empty() {
+        let enforced = policies.iter().filter(|policy| policy.enforced()).count();
+        println!("Policies: {} ({enforced} enforced)", policies.len());
+    }
+
+    let failure_watch = &config.features.failure_watch;
+    if failure_watch.enabled {
+        println!(
+            "Watching for failed executions every {}s",
+            failure_watch.interval_secs.max(1)
+        );
+    }
+
+    if config.multi_tenant() && !config.n8n.has_api_key() {
+        match config.base_url()? {
+            Some(base_url) => println!("Default n8n instance: {base_url}"),
+            None => println!("Default n8n instance: none, clients must send X-N8N-BASE-URL"),
+        }
+        println!("Multi-tenant mode with no API key configured, so there's no connection to check");
+        return Ok(());
+    }
+
+    let connection = config.connection()?;
+    let base_url = connection.base_url.clone();
+    println!("Deployment: {}", connection.deployment);
+    if let Some(webhook_base_url) = &connection.webhook_base_url {
+        println!("Webhooks: {webhook_base_url}");
+    }
+    Server::new(N8nClient::new(connection)?, config.features)
+        .check_connection()
+        .await?;
+
+    println!("Connected to n8n at {base_url}");
+    Ok(())
+}
+
+async fn serve(config_path: Option<PathBuf>, args: ServeArgs) -> anyhow::Result<()> {
+    let mut config = Config::load(config_path.as_deref())?;
+    config.apply_overrides(args.overrides());
+
+    match config.transport() {
+        Transport::Stdio => {
+            let n8n = N8nClient::new(config.connection()?)?;
+            let server = Server::new(n8n.clone(), config.features.clone());
+            server.check_connection().await?;
+            tokio::spawn(reload::on_hangup(config_path, n8n));
+
+            let in_flight = TaskTracker::new();
+            let ct = CancellationToken::new();
+            let service = server
+                .with_access(config.tool_access()?)
+                .with_instances(config.instances()?)
+                .with_policies(config.policies()?)
+                .with_in_flight(in_flight.clone())
+                .serve_with_ct(stdio(), ct.clone())
+                .await?;
+            let mut service = tokio::spawn(service.waiting());
+
+            tokio::select! {
+                res = &mut service => {
+                    res??;
+                }
+                res = shutdown::signal_received() => {
+                    res?;
+                    shutdown::drain(&in_flight).await;
+                    ct.cancel();
+                    service.await??;
+                }
+            }
+        }
+        transport @ (Transport::Http | Transport::Unix) => {
+            let listen = match transport {
+                Transport::Unix => Listen::Unix(config.server.socket.clone().ok_or_else(|| {
+                    anyhow::anyhow!("The Unix transport needs a socket path (--socket)")
+                })?),
+                _ => Listen::Tcp(config.bind()),
+            };
+
+            let tls = match (config.server.tls_cert.take(), config.server.tls_key.take()) {
+                (Some(cert), Some(key)) => Some(TlsConfig { cert, key }),
+                (None, None) => None,
+                _ => anyhow::bail!("TLS needs both a certificate and a key"),
+            };
+
+            // In multi-tenant mode the credentials only arrive with each session
+            let tenancy = if config.multi_tenant() {
+                Tenancy::Multi {
+                    default_base_url: config.base_url()?,
+                    timeout: config.timeout(),
+                }
+            } else {
+                let n8n = N8nClient::new(config.connection()?)?;
+                Server::new(n8n.clone(), config.features.clone())
+                    .check_connection()
+                    .await?;
+                tokio::spawn(reload::on_hangup(config_path, n8n.clone()));
+                Tenancy::Single {
+                    n8n,
+                    instances: config.instances()?,
+                }
+            };
+
+            let ct = CancellationToken::new();
+            let http_config = HttpConfig {
+                listen,
+                bearer_token: config.server.bearer_token.take(),
+                clients: std::mem::take(&mut config.server.clients),
+                tls,
+                tenancy,
+                access: config.tool_access()?,
+                policies: config.policies()?,
+                features: config.features,
+            };
+            let mut server = tokio::spawn(http::serve(http_config, ct.clone()));
+
+            tokio::select! {
+                // The server only stops by itself if it couldn't start, e.g. because the address is in use
+                res = &mut server => return Ok(res??),
+                res = shutdown::signal_received() => res?,
+            }
+
+            ct.cancel();
+            server.await??;
+        }
+    }
 
-    tokio::signal::ctrl_c().await?;
-    ct.cancel();
     Ok(())
 }
@@ -0,0 +1,154 @@
+use rmcp::model::{Content, Tool};
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::error::ServerError;
+
+fn nullable(kind: &str) -> Value {
+    json!({ "type": [kind, "null"] })
+}
+
+fn tag_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "id": { "type": "string" },
+            "name": { "type": "string" },
+            "createdAt": { "type": "string", "format": "date-time" },
+            "updatedAt": { "type": "string", "format": "date-time" },
+        },
+        "required": ["id", "name"],
+    })
+}
+
+fn workflow_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "id": { "type": "string" },
+            "name": { "type": "string" },
+            "active": { "type": "boolean" },
+            "createdAt": { "type": "string", "format": "date-time" },
+            "updatedAt": { "type": "string", "format": "date-time" },
+            "nodes": { "type": "array", "items": { "type": "object" } },
+            "connections": { "type": "object" },
+            "settings": { "type": "object" },
+            "staticData": nullable("object"),
+            "tags": { "type": "array", "items": tag_schema() },
+        },
+        "required": ["id", "name", "active", "nodes", "connections"],
+    })
+}
+
+fn execution_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            // n8n returns execution IDs as numbers, unlike every other ID
+            "id": { "type": ["integer", "string"] },
+            "workflowId": { "type": "string" },
+            "finished": { "type": "boolean" },
+            "mode": { "type": "string" },
+            "status": { "type": "string" },
+            "startedAt": { "type": "string", "format": "date-time" },
+            "stoppedAt": nullable("string"),
+            "retryOf": nullable("string"),
+            "retrySuccessId": nullable("string"),
+            "data": { "type": "object" },
+            "error": {
+                "type": "object",
+                "properties": {
+                    "message": { "type": "string" },
+                    "node": { "type": "string" },
+                },
+                "required": ["message"],
+            },
+        },
+        "required": ["id", "workflowId", "startedAt"],
+    })
+}
+
+/// An execution with the digest of each node's data and the data of a single node that `retrieve_execution_by_id`
+/// can add.
+fn execution_with_nodes_schema() -> Value {
+    let mut schema = execution_schema();
+    let properties = &mut schema["properties"];
+    properties["nodes"] = json!({
+        "type": "object",
+        "additionalProperties": {
+            "type": "object",
+            "properties": {
+                "runs": { "type": "integer" },
+                "items": { "type": "integer" },
+                "sizeBytes": { "type": "integer" },
+                "fields": { "type": "array", "items": { "type": "string" } },
+                "sample": { "type": "array", "items": { "type": "object" } },
+                "executionTimeMs": { "type": "integer" },
+                "error": { "type": "string" },
+            },
+            "required": ["runs", "items", "sizeBytes", "fields", "sample", "executionTimeMs"],
+        },
+    });
+    properties["node"] = json!({ "type": "string" });
+    properties["runs"] = json!({ "type": "array", "items": { "type": "object" } });
+
+    schema
+}
+
+fn page_schema(item: Value) -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "data": { "type": "array", "items": item },
+            "nextCursor": {
+                "description": "Pass this as the cursor to get the next page. Null on the last page.",
+                "type": ["string", "null"],
+            },
+        },
+        "required": ["data", "nextCursor"],
+    })
+}
+
+/// The JSON schema of what a retrieval tool returns, if it has one.
+pub fn schema(tool_name: &str) -> Option<Value> {
+    let schema = match tool_name {
+        "retrieve_workflows" => page_schema(workflow_schema()),
+        "retrieve_workflow_by_id" => workflow_schema(),
+        "retrieve_all_executions" => page_schema(execution_schema()),
+        "retrieve_execution_by_id" => execution_with_nodes_schema(),
+        "retrieve_executions_in_range" => json!({
+            "type": "object",
+            "properties": {
+                "data": { "type": "array", "items": execution_schema() },
+                "truncated": { "type": "boolean" },
+            },
+            "required": ["data", "truncated"],
+        }),
+        "retrieve_tags" => page_schema(tag_schema()),
+        "retrieve_tag_by_id" => tag_schema(),
+        _ => return None,
+    };
+
+    Some(schema)
+}
+
+/// Appends a tool's output schema to its description.
+///
+/// rmcp 0.1.5's `Tool` has no `outputSchema` field (nor `CallToolResult` a `structuredContent` one), so this is
+/// the only place it can be advertised for now.
+pub fn describe(mut tool: Tool) -> Tool {
+    if let Some(schema) = schema(&tool.name) {
+        tool.description = format!(
+            "{}\n\nReturns JSON matching this schema: {schema}",
+            tool.description
+        )
+        .into();
+    }
+
+    tool
+}
+
+/// Returns a tool's result as a single JSON document matching its output schema, rather than pretty-printed text.
+pub fn structured<T: Serialize>(value: &T) -> Result<Content, ServerError> {
+    Ok(Content::text(serde_json::to_string(value)?))
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// One page of a list returned by n8n's API, and of the list tools' results.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Paginated<T> {
+    pub data: Vec<T>,
+    /// The cursor for the page after this one, or `None` on the last page. Always serialized, so tool results say
+    /// outright whether there's more to fetch.
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Paginated<T> {
+    pub fn try_map<U, E>(self, f: impl FnMut(T) -> Result<U, E>) -> Result<Paginated<U>, E> {
+        Ok(Paginated {
+            data: self.data.into_iter().map(f).collect::<Result<_, _>>()?,
+            next_cursor: self.next_cursor,
+        })
+    }
+}
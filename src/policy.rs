@@ -0,0 +1,197 @@
+use anyhow::Context;
+use regex::Regex;
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::{config::PolicyConfig, tag::Tag, workflow::Workflow};
+
+/// Every policy workflows are checked against, in the order they're configured.
+pub type Policies = Arc<[Policy]>;
+
+/// A rule workflows have to follow, e.g. that their names start with a team prefix or that they have an owner tag.
+///
+/// Every policy is reported on by `check_policies`. Enforced ones also stop workflows that break them from being
+/// created or updated through the server, and their tag rules stop tags being changed so that they're broken.
+#[derive(Debug)]
+pub struct Policy {
+    name: String,
+    description: Option<String>,
+    enforce: bool,
+    name_pattern: Option<Regex>,
+    required_tag: Option<String>,
+    required_tag_prefix: Option<String>,
+    forbidden_node_types: Vec<String>,
+    require_error_workflow: bool,
+}
+
+/// A way a workflow breaks a policy.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Violation {
+    pub policy: String,
+    /// Which part of the policy is broken, e.g. `name_pattern`.
+    pub rule: &'static str,
+    pub enforced: bool,
+    pub message: String,
+}
+
+impl Policy {
+    pub fn compile(config: &PolicyConfig) -> anyhow::Result<Self> {
+        let name = &config.name;
+        if config.name_pattern.is_none()
+            && config.required_tag.is_none()
+            && config.required_tag_prefix.is_none()
+            && config.forbidden_node_types.is_empty()
+            && !config.require_error_workflow
+        {
+            anyhow::bail!(
+                "The policy {name} has no rules - set at least one of name_pattern, required_tag, required_tag_prefix, forbidden_node_types and require_error_workflow"
+            );
+        }
+
+        let name_pattern = config
+            .name_pattern
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .with_context(|| format!("Invalid name_pattern in the policy {name}"))?;
+
+        Ok(Self {
+            name: name.clone(),
+            description: config.description.clone(),
+            enforce: config.enforce,
+            name_pattern,
+            required_tag: config.required_tag.clone(),
+            required_tag_prefix: config.required_tag_prefix.clone(),
+            forbidden_node_types: config.forbidden_node_types.clone(),
+            require_error_workflow: config.require_error_workflow,
+        })
+    }
+
+    pub fn enforced(&self) -> bool {
+        self.enforce
+    }
+
+    /// Whether the policy has rules about tags, which are kept apart from the rest of a workflow in n8n.
+    pub fn checks_tags(&self) -> bool {
+        self.required_tag.is_some() || self.required_tag_prefix.is_some()
+    }
+
+    pub fn summary(&self) -> serde_json::Value {
+        serde_json::json!({
+            "name": self.name,
+            "description": self.description,
+            "enforced": self.enforce,
+            "namePattern": self.name_pattern.as_ref().map(Regex::as_str),
+            "requiredTag": self.required_tag,
+            "requiredTagPrefix": self.required_tag_prefix,
+            "forbiddenNodeTypes": self.forbidden_node_types,
+            "requireErrorWorkflow": self.require_error_workflow,
+        })
+    }
+
+    /// Checks a workflow against the policy. Tag rules are skipped when the workflow's tags aren't known, e.g. when
+    /// it's about to be created and so can't have any yet.
+    pub fn check(&self, workflow: &Workflow, tags: Option<&[Tag]>) -> Vec<Violation> {
+        let mut violations = Vec::new();
+
+        if let Some(pattern) = &self.name_pattern
+            && !pattern.is_match(&workflow.name)
+        {
+            violations.push(self.violation(
+                "name_pattern",
+                format!(
+                    "The name '{}' doesn't match the pattern '{}'",
+                    workflow.name,
+                    pattern.as_str()
+                ),
+            ));
+        }
+
+        if let Some(tags) = tags {
+            let tag_names: Vec<&str> = tags.iter().map(|tag| tag.name.as_str()).collect();
+            violations.extend(self.check_tags(&tag_names));
+        }
+
+        for node in &workflow.nodes {
+            if self.forbidden_node_types.contains(&node.kind) {
+                violations.push(self.violation(
+                    "forbidden_node_types",
+                    format!(
+                        "'{}' is a {} node, which isn't allowed",
+                        node.name, node.kind
+                    ),
+                ));
+            }
+        }
+
+        if self.require_error_workflow
+            && workflow.error_workflow().is_none()
+            && !workflow.is_error_workflow()
+        {
+            violations.push(self.violation(
+                "require_error_workflow",
+                "The workflow has no error workflow set".to_string(),
+            ));
+        }
+
+        violations
+    }
+
+    /// Checks just the policy's tag rules against the names of the tags a workflow has, or is about to have.
+    pub fn check_tags(&self, tag_names: &[&str]) -> Vec<Violation> {
+        let mut violations = Vec::new();
+
+        if let Some(required_tag) = &self.required_tag
+            && !tag_names
+                .iter()
+                .any(|name| name.trim() == required_tag.trim())
+        {
+            violations.push(self.violation(
+                "required_tag",
+                format!("The workflow isn't tagged '{required_tag}'"),
+            ));
+        }
+        if let Some(prefix) = &self.required_tag_prefix
+            && !tag_names
+                .iter()
+                .any(|name| name.trim().starts_with(prefix.as_str()))
+        {
+            violations.push(self.violation(
+                "required_tag_prefix",
+                format!("The workflow has no tag starting with '{prefix}'"),
+            ));
+        }
+
+        violations
+    }
+
+    fn violation(&self, rule: &'static str, message: String) -> Violation {
+        let message = match &self.description {
+            Some(description) => format!("{message} ({description})"),
+            None => message,
+        };
+        Violation {
+            policy: self.name.clone(),
+            rule,
+            enforced: self.enforce,
+            message,
+        }
+    }
+}
+
+/// Checks a workflow against every policy.
+pub fn check(policies: &[Policy], workflow: &Workflow, tags: Option<&[Tag]>) -> Vec<Violation> {
+    policies
+        .iter()
+        .flat_map(|policy| policy.check(workflow, tags))
+        .collect()
+}
+
+/// Checks the names of a workflow's tags against every policy's tag rules.
+pub fn check_tags(policies: &[Policy], tag_names: &[&str]) -> Vec<Violation> {
+    policies
+        .iter()
+        .flat_map(|policy| policy.check_tags(tag_names))
+        .collect()
+}
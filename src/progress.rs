@@ -0,0 +1,35 @@
+use rmcp::{
+    model::ProgressNotificationParam,
+    service::{RequestContext, RoleServer},
+};
+
+tokio::task_local! {
+    static CURRENT_REQUEST: RequestContext<RoleServer>;
+}
+
+/// Runs a tool call with its request context available to [`report`].
+pub async fn scope<F: Future>(context: RequestContext<RoleServer>, f: F) -> F::Output {
+    CURRENT_REQUEST.scope(context, f).await
+}
+
+/// Sends a progress notification for the tool call currently being handled.
+///
+/// rmcp doesn't hand us the `progressToken` from the request's `_meta`, so the request ID is used instead,
+/// which is the token the official TypeScript and Python clients send.
+pub async fn report(progress: u32, total: Option<u32>) {
+    let Ok((peer, request_id)) =
+        CURRENT_REQUEST.try_with(|context| (context.peer.clone(), context.id.clone()))
+    else {
+        return;
+    };
+
+    let notification = ProgressNotificationParam {
+        progress_token: request_id,
+        progress,
+        total,
+    };
+
+    if let Err(err) = peer.notify_progress(notification).await {
+        tracing::debug!("Could not send progress notification: {err}");
+    }
+}
@@ -0,0 +1,158 @@
+use rmcp::{
+    Error as McpError,
+    model::{
+        GetPromptResult, JsonObject, Prompt, PromptArgument, PromptMessage, PromptMessageRole,
+    },
+};
+
+const WEBHOOK_TO_SLACK: &str = "webhook_to_slack_notification";
+const TRIAGE_FAILED_EXECUTIONS: &str = "triage_failed_executions";
+const CLEAN_UP_UNUSED_WORKFLOWS: &str = "clean_up_unused_workflows";
+
+fn argument(name: &str, description: &str, required: bool) -> PromptArgument {
+    PromptArgument {
+        name: name.to_string(),
+        description: Some(description.to_string()),
+        required: Some(required),
+    }
+}
+
+pub fn list() -> Vec<Prompt> {
+    vec![
+        Prompt::new(
+            WEBHOOK_TO_SLACK,
+            Some("Build a workflow that posts a Slack message whenever a webhook is called."),
+            Some(vec![
+                argument(
+                    "webhook_path",
+                    "The path the webhook should listen on.",
+                    true,
+                ),
+                argument(
+                    "slack_channel",
+                    "The Slack channel to post to, e.g. #alerts.",
+                    true,
+                ),
+                argument(
+                    "message",
+                    "The message to post. Can reference fields from the webhook body.",
+                    false,
+                ),
+            ]),
+        ),
+        Prompt::new(
+            TRIAGE_FAILED_EXECUTIONS,
+            Some("Find recently failed executions, group them by cause and suggest fixes."),
+            Some(vec![
+                argument(
+                    "workflow_id",
+                    "Only triage failures of this workflow. Leave blank to triage every workflow.",
+                    false,
+                ),
+                argument(
+                    "since",
+                    "Only triage failures since this date (YYYY-MM-DD). Defaults to the last day.",
+                    false,
+                ),
+            ]),
+        ),
+        Prompt::new(
+            CLEAN_UP_UNUSED_WORKFLOWS,
+            Some(
+                "Find workflows that are inactive or haven't run recently and propose removing them.",
+            ),
+            Some(vec![argument(
+                "unused_for_days",
+                "How many days without an execution makes a workflow unused. Defaults to 30.",
+                false,
+            )]),
+        ),
+    ]
+}
+
+pub fn get(name: &str, arguments: Option<JsonObject>) -> Result<GetPromptResult, McpError> {
+    let arguments = arguments.unwrap_or_default();
+    let optional = |key: &str| {
+        arguments
+            .get(key)
+            .and_then(|value| value.as_str())
+            .filter(|value| !value.is_empty())
+    };
+    let required = |key: &str| {
+        optional(key).ok_or_else(|| {
+            McpError::invalid_params(format!("Missing required argument: {key}"), None)
+        })
+    };
+
+    let (description, text) = match name {
+        WEBHOOK_TO_SLACK => {
+            let webhook_path = required("webhook_path")?;
+            let slack_channel = required("slack_channel")?;
+            let message = optional("message").unwrap_or("A new webhook event was received.");
+
+            (
+                "Build a webhook to Slack notification workflow",
+                format!(
+                    "Create an n8n workflow that sends a Slack notification whenever its webhook is called.
+
+1. The first node must be of type 'n8n-nodes-base.webhook', listening for POST requests on the path '{webhook_path}'.
+2. Connect it to a node of type 'n8n-nodes-base.slack' that posts to the channel '{slack_channel}' with the message: {message}
+3. Create the workflow with the create_workflow tool, then activate it with activate_workflow_by_id.
+4. Check that it works by calling run_workflow with the webhook path '{webhook_path}' and some sample data.
+
+If activation fails because of missing Slack credentials, tell me which node needs credentials rather than retrying."
+                ),
+            )
+        }
+        TRIAGE_FAILED_EXECUTIONS => {
+            let scope = match optional("workflow_id") {
+                Some(workflow_id) => format!("of the workflow with ID {workflow_id}"),
+                None => "across every workflow".to_string(),
+            };
+            let since = match optional("since") {
+                Some(since) => format!("since {since}"),
+                None => "in the last day".to_string(),
+            };
+
+            (
+                "Triage failed executions",
+                format!(
+                    "Triage the executions that failed {since} {scope}.
+
+1. Use retrieve_executions_in_range with status 'error' to find the failed executions.
+2. For each failure, use analyze_execution_failure to find which node failed, why, and the input it failed on.
+3. Group the failures by their cause, and for each group tell me how many executions failed, which workflows were affected and what the likely fix is.
+
+Don't change any workflows - just report what you found."
+                ),
+            )
+        }
+        CLEAN_UP_UNUSED_WORKFLOWS => {
+            let unused_for_days = optional("unused_for_days").unwrap_or("30");
+
+            (
+                "Clean up unused workflows",
+                format!(
+                    "Help me clean up workflows that are no longer used.
+
+1. Retrieve all workflows.
+2. For each workflow, find its most recent execution. A workflow is unused if it is inactive, or if it hasn't run in the last {unused_for_days} days.
+3. List the unused workflows with their name, ID, tags, whether they're active and when they last ran.
+
+Don't delete or deactivate anything until I've confirmed which workflows to remove."
+                ),
+            )
+        }
+        _ => {
+            return Err(McpError::invalid_params(
+                format!("Unknown prompt: {name}"),
+                None,
+            ));
+        }
+    };
+
+    Ok(GetPromptResult {
+        description: Some(description.to_string()),
+        messages: vec![PromptMessage::new_text(PromptMessageRole::User, text)],
+    })
+}
@@ -0,0 +1,90 @@
+use serde::{Serialize, Serializer};
+use std::fmt::Display;
+
+/// The query string of a request to n8n's API.
+///
+/// Serializing a `json!` object or a struct straight into a query string doesn't work for n8n: a `null` either fails
+/// to serialize or is sent as an empty value, which n8n then filters by, and nested values can't be expressed at all.
+/// Here, values that aren't set are left out, and everything else is sent as its plain text form, e.g. `active=true`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Query(Vec<(&'static str, String)>);
+
+impl Query {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(mut self, name: &'static str, value: impl Display) -> Self {
+        self.0.push((name, value.to_string()));
+        self
+    }
+
+    pub fn set_optional(self, name: &'static str, value: Option<impl Display>) -> Self {
+        match value {
+            Some(value) => self.set(name, value),
+            None => self,
+        }
+    }
+}
+
+impl Serialize for Query {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query_string(query: &Query) -> Option<String> {
+        reqwest::Client::new()
+            .get("http://localhost:5678/api/v1/workflows")
+            .query(query)
+            .build()
+            .unwrap()
+            .url()
+            .query()
+            .map(ToString::to_string)
+    }
+
+    #[test]
+    fn leaves_out_unset_values() {
+        let query = Query::new()
+            .set("limit", 10)
+            .set_optional("projectId", None::<&str>)
+            .set_optional("workflowId", Some("abc"));
+
+        assert_eq!(
+            query_string(&query).as_deref(),
+            Some("limit=10&workflowId=abc")
+        );
+    }
+
+    #[test]
+    fn an_empty_query_adds_nothing_to_the_url() {
+        assert_eq!(query_string(&Query::new()), None);
+    }
+
+    #[test]
+    fn sends_booleans_as_true_or_false() {
+        let query = Query::new()
+            .set("active", true)
+            .set_optional("excludePinnedData", Some(false));
+
+        assert_eq!(
+            query_string(&query).as_deref(),
+            Some("active=true&excludePinnedData=false")
+        );
+    }
+
+    #[test]
+    fn encodes_values() {
+        let query = Query::new().set("name", "Orders & invoices");
+
+        assert_eq!(
+            query_string(&query).as_deref(),
+            Some("name=Orders+%26+invoices")
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use std::path::{Path, PathBuf};
+use tokio::signal::unix::{SignalKind, signal};
+
+use crate::{base_url::BaseUrl, client::N8nClient, config::Config, server::Server};
+
+/// Reloads the n8n connection every time the process receives SIGHUP, so a rotated API key or a moved instance can
+/// be picked up without restarting the server and dropping every session.
+///
+/// The config file is read again from scratch, so that's where credentials need to live for this to be useful; a
+/// running process's environment variables can't be changed from outside it.
+pub async fn on_hangup(config_path: Option<PathBuf>, n8n: N8nClient) {
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(err) => {
+            tracing::error!("Could not listen for SIGHUP, so credentials can't be reloaded: {err}");
+            return;
+        }
+    };
+
+    while hangup.recv().await.is_some() {
+        tracing::info!("Received SIGHUP, reloading the n8n connection");
+
+        match reload(config_path.as_deref(), &n8n).await {
+            Ok(base_url) => tracing::info!("Reloaded the connection to n8n at {base_url}"),
+            Err(err) => tracing::error!(
+                "Could not reload the n8n connection, so the old one is still in use: {err}"
+            ),
+        }
+    }
+}
+
+/// Only swaps the new connection in once n8n has accepted it, so a typo in the config file can't break every session.
+async fn reload(config_path: Option<&Path>, n8n: &N8nClient) -> anyhow::Result<BaseUrl> {
+    let config = Config::load(config_path)?;
+    let connection = config.connection()?;
+
+    Server::new(N8nClient::new(connection.clone())?, config.features)
+        .check_connection()
+        .await?;
+
+    let base_url = connection.base_url.clone();
+    n8n.replace(connection)?;
+
+    Ok(base_url)
+}
@@ -0,0 +1,71 @@
+use rmcp::model::{AnnotateAble, RawResourceTemplate, ResourceTemplate};
+use std::fmt;
+
+use crate::id::{ExecutionId, TagId, WorkflowId};
+
+const SCHEME: &str = "n8n://";
+
+/// The n8n objects that are exposed as MCP resources.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceUri {
+    Workflow(WorkflowId),
+    Execution(ExecutionId),
+    Tag(TagId),
+}
+
+impl ResourceUri {
+    pub fn parse(uri: &str) -> Option<Self> {
+        let (kind, id) = uri.strip_prefix(SCHEME)?.split_once('/')?;
+
+        if id.is_empty() || id.contains('/') {
+            return None;
+        }
+
+        match kind {
+            "workflow" => Some(Self::Workflow(id.into())),
+            "execution" => Some(Self::Execution(id.into())),
+            "tag" => Some(Self::Tag(id.into())),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ResourceUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Workflow(id) => write!(f, "{SCHEME}workflow/{id}"),
+            Self::Execution(id) => write!(f, "{SCHEME}execution/{id}"),
+            Self::Tag(id) => write!(f, "{SCHEME}tag/{id}"),
+        }
+    }
+}
+
+fn template(uri_template: &str, name: &str, description: &str) -> ResourceTemplate {
+    RawResourceTemplate {
+        uri_template: uri_template.to_string(),
+        name: name.to_string(),
+        description: Some(description.to_string()),
+        mime_type: Some("application/json".to_string()),
+    }
+    .no_annotation()
+}
+
+pub fn templates() -> Vec<ResourceTemplate> {
+    vec![
+        template(
+            "n8n://workflow/{id}",
+            "Workflow",
+            "A workflow, including its nodes, connections and settings.",
+        ),
+        template(
+            "n8n://execution/{id}",
+            "Execution",
+            "An execution, including the data produced by each node.",
+        ),
+        template(
+            "n8n://tag/{id}",
+            "Tag",
+            "A tag that can be assigned to workflows.",
+        ),
+    ]
+}
@@ -0,0 +1,63 @@
+use crate::error::ServerError;
+
+/// How much of a body that isn't JSON is included in an error, e.g. the start of a proxy's HTML error page.
+const MAX_BODY_CHARS: usize = 500;
+
+/// Passes a 2xx response through, and turns anything else into an error carrying n8n's explanation of it.
+pub async fn ok(res: reqwest::Response) -> Result<reqwest::Response, ServerError> {
+    let status = res.status();
+    if status.is_success() {
+        return Ok(res);
+    }
+
+    let body = res.text().await?;
+    Err(status_error(status, &body))
+}
+
+/// Reads a 2xx response's body as JSON. An empty body, like the 204 some deletes respond with, is read as `null`.
+pub async fn json(res: reqwest::Response) -> Result<serde_json::Value, ServerError> {
+    let status = res.status();
+    let body = res.text().await?;
+
+    parse(status, &body)
+}
+
+/// Parses a body that has already been read, in the same way as [`json`].
+pub fn parse(status: reqwest::StatusCode, body: &str) -> Result<serde_json::Value, ServerError> {
+    if !status.is_success() {
+        return Err(status_error(status, body));
+    }
+
+    if body.trim().is_empty() {
+        return Ok(serde_json::Value::Null);
+    }
+
+    serde_json::from_str(body).map_err(|_| ServerError::NotJson {
+        status,
+        body: truncate(body),
+    })
+}
+
+/// n8n's error bodies look like `{"code": 404, "message": "...", "hint": "..."}`, though only `message` is always
+/// there.
+fn status_error(status: reqwest::StatusCode, body: &str) -> ServerError {
+    let Ok(error) = serde_json::from_str::<serde_json::Value>(body) else {
+        return ServerError::Status {
+            status,
+            message: Some(truncate(body)).filter(|body| !body.trim().is_empty()),
+            code: None,
+            hint: None,
+        };
+    };
+
+    ServerError::Status {
+        status,
+        message: error["message"].as_str().map(ToString::to_string),
+        code: Some(error["code"].clone()).filter(|code| !code.is_null()),
+        hint: error["hint"].as_str().map(ToString::to_string),
+    }
+}
+
+fn truncate(body: &str) -> String {
+    body.chars().take(MAX_BODY_CHARS).collect()
+}
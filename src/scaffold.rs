@@ -0,0 +1,407 @@
+use rmcp::schemars::{self, JsonSchema};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::collections::HashSet;
+
+use crate::workflow::{self, Connection, Connections, Node};
+
+/// What starts a scaffolded workflow.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerType {
+    /// An HTTP request to the workflow's webhook URL.
+    Webhook,
+    /// A schedule, e.g. every hour.
+    Schedule,
+    /// Only run by hand, from the editor.
+    Manual,
+    /// A form n8n hosts.
+    Form,
+    /// A chat message, e.g. for an AI agent.
+    Chat,
+    /// Another workflow, through an Execute Workflow node.
+    SubWorkflow,
+    /// Another workflow failing, for workflows used as an error workflow.
+    Error,
+}
+
+/// What one step of a scaffolded workflow does.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StepIntent {
+    /// Call an API or fetch a URL.
+    HttpRequest,
+    /// Add, change or remove fields of each item.
+    SetFields,
+    /// Run JavaScript.
+    Code,
+    /// Only let through items matching conditions.
+    Filter,
+    /// Branch on a condition. The following steps go on from the true branch.
+    If,
+    /// Turn a list in each item into separate items.
+    SplitOut,
+    /// Combine items into one.
+    Aggregate,
+    /// Pause for a while, or until a webhook is called.
+    Wait,
+    /// Send a Slack message.
+    Slack,
+    /// Send an email with Gmail.
+    Gmail,
+    /// Read or write rows of a Google Sheet.
+    GoogleSheets,
+    /// Run another workflow.
+    ExecuteWorkflow,
+    /// Run an AI agent with an OpenAI chat model.
+    AiAgent,
+    /// Send the webhook's response. Only for webhook triggers.
+    RespondToWebhook,
+    /// Do nothing, e.g. as a placeholder.
+    NoOp,
+}
+
+/// One step of the plan `scaffold_workflow` builds a workflow from.
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Step {
+    #[schemars(
+        description = "What the step does: 'http_request', 'set_fields', 'code', 'filter', 'if', 'split_out', 'aggregate', 'wait', 'slack', 'gmail', 'google_sheets', 'execute_workflow', 'ai_agent', 'respond_to_webhook' or 'no_op'."
+    )]
+    pub intent: StepIntent,
+    #[schemars(
+        description = "The node's name, e.g. 'Fetch orders'. Optional - defaults to the node type's usual name."
+    )]
+    pub name: Option<String>,
+    #[schemars(
+        description = "What the step should do, in words, e.g. 'Get yesterday's orders from the shop API'. Kept as the node's notes, as a reminder of what to fill in. Optional."
+    )]
+    pub description: Option<String>,
+}
+
+/// A workflow with every node in place and connected, but with the parameters that depend on what it's for left to be
+/// filled in.
+#[derive(Debug, Serialize)]
+pub struct Scaffold {
+    pub nodes: Vec<Node>,
+    pub connections: Connections,
+    /// What still needs doing before the workflow will work, one entry per node that needs something.
+    pub todo: Vec<String>,
+}
+
+impl TriggerType {
+    /// The node type, the usual name, starting parameters and what's left to fill in.
+    fn node(self) -> (&'static str, &'static str, Value, Option<&'static str>) {
+        match self {
+            Self::Webhook => (
+                "n8n-nodes-base.webhook",
+                "Webhook",
+                json!({ "httpMethod": "POST", "path": new_uuid(), "options": {} }),
+                Some("check the HTTP method, and change the path if a readable URL is wanted"),
+            ),
+            Self::Schedule => (
+                "n8n-nodes-base.scheduleTrigger",
+                "Schedule Trigger",
+                json!({ "rule": { "interval": [{ "field": "hours", "hoursInterval": 1 }] } }),
+                Some("set how often it runs (every hour for now)"),
+            ),
+            Self::Manual => (
+                "n8n-nodes-base.manualTrigger",
+                "When clicking 'Test workflow'",
+                json!({}),
+                None,
+            ),
+            Self::Form => (
+                "n8n-nodes-base.formTrigger",
+                "On form submission",
+                json!({ "formTitle": "", "formFields": { "values": [] }, "options": {} }),
+                Some("set the form's title and fields"),
+            ),
+            Self::Chat => (
+                "@n8n/n8n-nodes-langchain.chatTrigger",
+                "When chat message received",
+                json!({ "options": {} }),
+                None,
+            ),
+            Self::SubWorkflow => (
+                "n8n-nodes-base.executeWorkflowTrigger",
+                "When Executed by Another Workflow",
+                json!({ "inputSource": "passthrough" }),
+                None,
+            ),
+            Self::Error => (
+                "n8n-nodes-base.errorTrigger",
+                "Error Trigger",
+                json!({}),
+                None,
+            ),
+        }
+    }
+}
+
+impl StepIntent {
+    /// The node type, the usual name, starting parameters and what's left to fill in.
+    fn node(self) -> (&'static str, &'static str, Value, Option<&'static str>) {
+        match self {
+            Self::HttpRequest => (
+                "n8n-nodes-base.httpRequest",
+                "HTTP Request",
+                json!({ "method": "GET", "url": "", "options": {} }),
+                Some("set the method and URL, and the credentials or headers the API needs"),
+            ),
+            Self::SetFields => (
+                "n8n-nodes-base.set",
+                "Edit Fields",
+                json!({ "assignments": { "assignments": [] }, "options": {} }),
+                Some("add the fields to set"),
+            ),
+            Self::Code => (
+                "n8n-nodes-base.code",
+                "Code",
+                json!({ "jsCode": "return $input.all();" }),
+                Some("write the code (it passes items through unchanged for now)"),
+            ),
+            Self::Filter => (
+                "n8n-nodes-base.filter",
+                "Filter",
+                json!({ "conditions": { "conditions": [], "combinator": "and" }, "options": {} }),
+                Some("add the conditions items have to match"),
+            ),
+            Self::If => (
+                "n8n-nodes-base.if",
+                "If",
+                json!({ "conditions": { "conditions": [], "combinator": "and" }, "options": {} }),
+                Some(
+                    "add the condition. The false branch (output 1) isn't connected to anything yet",
+                ),
+            ),
+            Self::SplitOut => (
+                "n8n-nodes-base.splitOut",
+                "Split Out",
+                json!({ "fieldToSplitOut": "", "options": {} }),
+                Some("set the field holding the list to split out"),
+            ),
+            Self::Aggregate => (
+                "n8n-nodes-base.aggregate",
+                "Aggregate",
+                json!({ "aggregate": "aggregateAllItemData", "options": {} }),
+                None,
+            ),
+            Self::Wait => (
+                "n8n-nodes-base.wait",
+                "Wait",
+                json!({ "amount": 1 }),
+                Some("set how long to wait (one second for now)"),
+            ),
+            Self::Slack => (
+                "n8n-nodes-base.slack",
+                "Slack",
+                json!({ "select": "channel", "text": "" }),
+                Some("choose the channel, write the message, and assign Slack credentials"),
+            ),
+            Self::Gmail => (
+                "n8n-nodes-base.gmail",
+                "Gmail",
+                json!({ "sendTo": "", "subject": "", "message": "", "options": {} }),
+                Some("set the recipient, subject and message, and assign Gmail credentials"),
+            ),
+            Self::GoogleSheets => (
+                "n8n-nodes-base.googleSheets",
+                "Google Sheets",
+                json!({ "operation": "append" }),
+                Some(
+                    "choose the operation, document and sheet, and assign Google Sheets credentials",
+                ),
+            ),
+            Self::ExecuteWorkflow => (
+                "n8n-nodes-base.executeWorkflow",
+                "Execute Workflow",
+                json!({ "workflowId": { "__rl": true, "value": "", "mode": "id" }, "options": {} }),
+                Some("set the ID of the workflow to run"),
+            ),
+            Self::AiAgent => (
+                "@n8n/n8n-nodes-langchain.agent",
+                "AI Agent",
+                json!({ "options": {} }),
+                Some("write the prompt, and assign OpenAI credentials to its chat model"),
+            ),
+            Self::RespondToWebhook => (
+                "n8n-nodes-base.respondToWebhook",
+                "Respond to Webhook",
+                json!({ "options": {} }),
+                Some("choose what to respond with (the incoming items for now)"),
+            ),
+            Self::NoOp => (
+                "n8n-nodes-base.noOp",
+                "No Operation, do nothing",
+                json!({}),
+                None,
+            ),
+        }
+    }
+}
+
+/// A new `trigger` node, with n8n's usual name for it unless one of `names` is already that. Also returns what's left
+/// to fill in, if anything.
+pub fn trigger_node(trigger: TriggerType, names: &[&str]) -> (Node, Option<&'static str>) {
+    let (kind, name, parameters, todo_item) = trigger.node();
+    let mut names = names.iter().map(|name| name.to_string()).collect();
+    let name = unique_name(&mut names, name.to_string());
+
+    // n8n registers a webhook under its node's webhook ID as well as its path
+    let mut extra = Map::new();
+    if matches!(trigger, TriggerType::Webhook) {
+        extra.insert("webhookId".to_string(), parameters["path"].clone());
+    }
+
+    let node = Node {
+        id: Some(new_uuid()),
+        name,
+        kind: kind.to_string(),
+        type_version: None,
+        position: None,
+        parameters: match parameters {
+            Value::Object(parameters) => Some(parameters),
+            _ => None,
+        },
+        disabled: None,
+        credentials: None,
+        extra,
+    };
+
+    (node, todo_item)
+}
+
+/// Builds a workflow that starts with `trigger` and runs `steps` in order, each connected to the one before it.
+///
+/// Nodes get the latest known type version and are laid out left to right. An AI agent gets an OpenAI chat model
+/// attached, which n8n won't run one without, and a webhook that's followed by a Respond to Webhook step is set to
+/// respond from it.
+pub fn scaffold(trigger: TriggerType, steps: &[Step]) -> Scaffold {
+    let mut nodes = Vec::new();
+    let mut connections = Connections::new();
+    let mut todo = Vec::new();
+    let mut names = HashSet::new();
+
+    let mut add = |nodes: &mut Vec<Node>,
+                   kind: &str,
+                   name: String,
+                   parameters: Value,
+                   todo_item: Option<&str>,
+                   notes: Option<&String>| {
+        let name = unique_name(&mut names, name);
+        if let Some(todo_item) = todo_item {
+            todo.push(format!("'{name}': {todo_item}"));
+        }
+
+        let mut extra = Map::new();
+        if let Some(notes) = notes {
+            extra.insert("notes".to_string(), json!(notes));
+        }
+        nodes.push(Node {
+            id: Some(new_uuid()),
+            name: name.clone(),
+            kind: kind.to_string(),
+            type_version: None,
+            position: None,
+            parameters: match parameters {
+                Value::Object(parameters) => Some(parameters),
+                _ => None,
+            },
+            disabled: None,
+            credentials: None,
+            extra,
+        });
+
+        name
+    };
+
+    let (kind, name, mut parameters, todo_item) = trigger.node();
+    let responds_from_node = steps
+        .iter()
+        .any(|step| matches!(step.intent, StepIntent::RespondToWebhook));
+    if matches!(trigger, TriggerType::Webhook) && responds_from_node {
+        parameters["responseMode"] = json!("responseNode");
+    }
+    let mut previous = add(
+        &mut nodes,
+        kind,
+        name.to_string(),
+        parameters,
+        todo_item,
+        None,
+    );
+
+    for step in steps {
+        let (kind, default_name, parameters, todo_item) = step.intent.node();
+        let name = step
+            .name
+            .clone()
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or_else(|| default_name.to_string());
+        let name = add(
+            &mut nodes,
+            kind,
+            name,
+            parameters,
+            todo_item,
+            step.description.as_ref(),
+        );
+        workflow::connect(&mut connections, &previous, 0, &name, 0);
+
+        if matches!(step.intent, StepIntent::AiAgent) {
+            let model = add(
+                &mut nodes,
+                "@n8n/n8n-nodes-langchain.lmChatOpenAi",
+                "OpenAI Chat Model".to_string(),
+                json!({ "options": {} }),
+                None,
+                None,
+            );
+            connections.entry(model).or_default().insert(
+                "ai_languageModel".to_string(),
+                vec![Some(vec![Connection {
+                    node: name.clone(),
+                    kind: "ai_languageModel".to_string(),
+                    index: 0,
+                }])],
+            );
+        }
+
+        previous = name;
+    }
+
+    workflow::fill_node_defaults(&mut nodes, &connections);
+
+    Scaffold {
+        nodes,
+        connections,
+        todo,
+    }
+}
+
+/// `name`, or if it's taken, `name` with the lowest number after it that isn't, the way the n8n editor names copies.
+fn unique_name(names: &mut HashSet<String>, name: String) -> String {
+    if names.insert(name.clone()) {
+        return name;
+    }
+
+    (1..)
+        .map(|n| format!("{name}{n}"))
+        .find(|numbered| names.insert(numbered.clone()))
+        .unwrap_or(name)
+}
+
+/// A random (version 4) UUID, the form n8n gives node IDs and webhook paths.
+fn new_uuid() -> String {
+    let bits = rand::random::<u128>() & !(0xf << 76) & !(0x3 << 62) | (0x4 << 76) | (0x2 << 62);
+    let hex = format!("{bits:032x}");
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
@@ -0,0 +1,218 @@
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{error::ServerError, execution::Execution, workflow::Node};
+
+/// The most hits `search_workflows` returns, so a pattern that matches nearly everything doesn't flood the result.
+pub const MAX_HITS: usize = 200;
+
+/// How much of a matching value is returned with a hit. Longer values (e.g. code) are cut down to the part around
+/// the first match.
+const MAX_EXCERPT_CHARS: usize = 200;
+
+/// Builds the matcher for `search_workflows`. A plain search string matches literally, so e.g. the dots in a URL
+/// don't need escaping.
+pub fn matcher(pattern: &str, regex: bool, case_sensitive: bool) -> Result<Regex, ServerError> {
+    let pattern = if regex {
+        pattern.to_string()
+    } else {
+        regex::escape(pattern)
+    };
+
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!case_sensitive)
+        .build()
+        .map_err(|err| ServerError::validation(format!("The pattern isn't a valid regex: {err}")))
+}
+
+/// Where in a workflow a search matched.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hit {
+    pub node: String,
+    pub node_type: String,
+    /// e.g. `parameters.options.headers[0].value`.
+    pub path: String,
+    pub kind: HitKind,
+    pub excerpt: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HitKind {
+    /// A plain parameter value.
+    Parameter,
+    /// A parameter set to an expression, e.g. `={{ $json.url }}`.
+    Expression,
+    /// The text of a sticky note.
+    StickyNote,
+    /// The notes attached to a node in the editor.
+    Notes,
+}
+
+/// Finds every string in the nodes' parameters and notes that the matcher matches. Parameter names aren't searched,
+/// only their values.
+pub fn search_nodes(nodes: &[Node], matcher: &Regex) -> Vec<Hit> {
+    let mut hits = Vec::new();
+
+    for node in nodes {
+        let mut strings = Vec::new();
+        if let Some(parameters) = &node.parameters {
+            for (name, value) in parameters {
+                collect_strings(value, format!("parameters.{name}"), &mut strings);
+            }
+        }
+        if let Some(Value::String(notes)) = node.extra.get("notes") {
+            strings.push(("notes".to_string(), notes.as_str()));
+        }
+
+        for (path, text) in strings {
+            let Some(found) = matcher.find(text) else {
+                continue;
+            };
+
+            let kind = if path == "notes" {
+                HitKind::Notes
+            } else if node.is_sticky_note() {
+                HitKind::StickyNote
+            } else if text.starts_with('=') {
+                HitKind::Expression
+            } else {
+                HitKind::Parameter
+            };
+
+            hits.push(Hit {
+                node: node.name.clone(),
+                node_type: node.kind.clone(),
+                path,
+                kind,
+                excerpt: excerpt(text, found.start()),
+            });
+        }
+    }
+
+    hits
+}
+
+/// Where in an execution's data a search matched.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataHit {
+    pub node: String,
+    /// Which of the node's runs the item was output by, e.g. the batch in a loop.
+    pub run: usize,
+    /// Which of the node's outputs the item came out of, e.g. the false branch of an If node.
+    pub output: usize,
+    /// The item's position in that output.
+    pub item: usize,
+    /// e.g. `customer.email`.
+    pub path: String,
+    pub excerpt: String,
+}
+
+/// Finds every value in the items the execution's nodes output that the matcher matches. Numbers and booleans are
+/// matched as text, so an order ID is found whether it was a number or a string.
+pub fn search_execution(execution: &Execution, matcher: &Regex) -> Vec<DataHit> {
+    let mut hits = Vec::new();
+
+    for node in execution.nodes_run() {
+        let runs = execution
+            .node_runs(node)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten();
+        for (run, data) in runs.enumerate() {
+            let outputs = data
+                .pointer("/data/main")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten();
+            for (output, items) in outputs.enumerate() {
+                for (item, value) in items.as_array().into_iter().flatten().enumerate() {
+                    let mut values = Vec::new();
+                    collect_scalars(&value["json"], String::new(), &mut values);
+
+                    for (path, text) in values {
+                        if let Some(found) = matcher.find(&text) {
+                            hits.push(DataHit {
+                                node: node.to_string(),
+                                run,
+                                output,
+                                item,
+                                path,
+                                excerpt: excerpt(&text, found.start()),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    hits
+}
+
+/// Every string, number and boolean in `value` as text, along with its path from `path`, e.g. `customer.email`.
+fn collect_scalars(value: &Value, path: String, values: &mut Vec<(String, String)>) {
+    match value {
+        Value::String(text) => values.push((path, text.clone())),
+        Value::Number(number) => values.push((path, number.to_string())),
+        Value::Bool(boolean) => values.push((path, boolean.to_string())),
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                collect_scalars(item, format!("{path}[{i}]"), values);
+            }
+        }
+        Value::Object(fields) => {
+            for (name, field) in fields {
+                let path = if path.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{path}.{name}")
+                };
+                collect_scalars(field, path, values);
+            }
+        }
+        Value::Null => {}
+    }
+}
+
+/// Every string in `value`, along with its path from `path`, e.g. `parameters.options.headers[0].value`.
+pub fn collect_strings<'a>(value: &'a Value, path: String, strings: &mut Vec<(String, &'a str)>) {
+    match value {
+        Value::String(text) => strings.push((path, text)),
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                collect_strings(item, format!("{path}[{i}]"), strings);
+            }
+        }
+        Value::Object(fields) => {
+            for (name, field) in fields {
+                collect_strings(field, format!("{path}.{name}"), strings);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Up to [`MAX_EXCERPT_CHARS`] characters of `text`, starting a little before the match at byte `start`.
+fn excerpt(text: &str, start: usize) -> String {
+    if text.chars().count() <= MAX_EXCERPT_CHARS {
+        return text.to_string();
+    }
+
+    let skip = text[..start]
+        .chars()
+        .count()
+        .saturating_sub(MAX_EXCERPT_CHARS / 4);
+    let mut excerpt: String = text.chars().skip(skip).take(MAX_EXCERPT_CHARS).collect();
+    if skip > 0 {
+        excerpt.insert(0, '…');
+    }
+    if skip + MAX_EXCERPT_CHARS < text.chars().count() {
+        excerpt.push('…');
+    }
+
+    excerpt
+}
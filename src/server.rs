@@ -1,586 +1,5939 @@
-use axum::http::{HeaderMap, HeaderValue};
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+use futures::{StreamExt, stream};
 use rmcp::{
     Error as McpError, ServerHandler,
+    handler::server::tool::ToolCallContext,
     model::*,
     schemars::{self, JsonSchema},
+    service::{Peer, RequestContext, RoleServer},
     tool,
 };
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json::json;
-use std::{env, fmt};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio_util::task::TaskTracker;
+
+use crate::{
+    access::ToolAccess,
+    annotations, backup,
+    base_url::BaseUrl,
+    client::{DeploymentKind, Instances, N8nClient},
+    compare,
+    completion::{self, Candidate, CompletionCache, CompletionKind},
+    config::Features,
+    dependencies, diagram, docs, duplicate,
+    endpoints::Endpoint,
+    error::ServerError,
+    execution::{self, Execution},
+    id::{ExecutionId, ProjectId, TagId, WorkflowId},
+    lint,
+    logging::{RequestLogger, SendLogged},
+    output,
+    page::Paginated,
+    policy::{self, Policies},
+    progress, prompts,
+    query::Query,
+    resources::{self, ResourceUri},
+    response, scaffold, search, snapshot, stats,
+    tag::{self, Tag},
+    validate::{self, MAX_PAGE_SIZE},
+    watcher::{ExecutionWatcher, FailureWatcher, Subscriptions},
+    workflow::{self, Connections, Node, Workflow},
+};
 
 #[derive(Clone)]
 pub struct Server {
-    client: reqwest::Client,
-    base_url: String,
-    n8n_user: Option<String>,
-    n8n_password: Option<String>,
+    n8n: N8nClient,
+    peer: Option<Peer<RoleServer>>,
+    subscriptions: Arc<Subscriptions>,
+    completions: Arc<CompletionCache>,
+    logger: Arc<RequestLogger>,
+    in_flight: TaskTracker,
+    access: ToolAccess,
+    features: Features,
+    instances: Instances,
+    policies: Policies,
 }
 
 impl Server {
-    pub fn from_env() -> Self {
-        let api_key = env::var("N8N_API_KEY").expect("N8N_API_KEY to exist");
-        let base_url = env::var("N8N_BASE_URL").expect("N8N_BASE_URL to exist");
-        let n8n_user = env::var("N8N_USER").ok();
-        let n8n_password = env::var("N8N_PASSWORD").ok();
-
-        let mut headers = HeaderMap::new();
-        headers.insert("X-N8N-API-KEY", HeaderValue::from_str(&api_key).unwrap());
-
-        let client = reqwest::Client::builder()
-            .default_headers(headers)
-            .build()
-            .unwrap();
-
+    pub fn new(n8n: N8nClient, features: Features) -> Self {
         Self {
-            client,
-            base_url,
-            n8n_user,
-            n8n_password,
+            n8n,
+            peer: None,
+            subscriptions: Arc::default(),
+            completions: Arc::default(),
+            logger: Arc::new(RequestLogger::new(features.request_logging)),
+            in_flight: TaskTracker::new(),
+            access: ToolAccess::default(),
+            features,
+            instances: Instances::default(),
+            policies: Policies::default(),
         }
     }
-}
 
-#[derive(Deserialize, Serialize, JsonSchema, Default)]
-#[serde(rename = "camelCase")]
-pub struct WorkflowSettings {
-    save_execution_progress: bool,
-    save_manual_executions: bool,
-    save_data_error_execution: AllOrNone,
-    save_data_success_execution: AllOrNone,
-    execution_timeout: u16,
-}
+    /// Every tool the server provides, as listed to clients.
+    pub fn tools() -> Vec<Tool> {
+        let mut tools: Vec<Tool> = Self::tool_box()
+            .list()
+            .into_iter()
+            .map(annotations::annotate)
+            .map(output::describe)
+            .collect();
+        tools.sort_by(|a, b| a.name.cmp(&b.name));
 
-#[derive(Deserialize, Serialize, JsonSchema)]
-#[serde(rename = "camelCase")]
-pub struct RetrieveAllWorkflowParams {
-    active: Option<bool>,
-    tags: Option<String>,
-    name: Option<String>,
-    project_id: Option<String>,
-    exclude_pinned_data: Option<String>,
-    limit: Option<u8>,
-    cursor: Option<String>,
-}
+        tools
+    }
 
-#[derive(Deserialize, Serialize, JsonSchema)]
-#[serde(rename = "camelCase")]
-pub struct RetrieveSingleWorkflowParams {
-    id: String,
-    exclude_pinned_data: Option<bool>,
-}
+    fn client(&self) -> reqwest::Client {
+        self.n8n.http()
+    }
+
+    fn base_url(&self) -> BaseUrl {
+        self.n8n.base_url()
+    }
+
+    fn url(&self, endpoint: Endpoint) -> String {
+        endpoint.url(&self.base_url())
+    }
+
+    /// Tracks this server's tool calls with a tracker shared by every session, so shutdown can wait for them.
+    pub fn with_in_flight(mut self, in_flight: TaskTracker) -> Self {
+        self.in_flight = in_flight;
+        self
+    }
+
+    /// Limits which tools this server's clients can see and call.
+    pub fn with_access(mut self, access: ToolAccess) -> Self {
+        self.access = access;
+        self
+    }
+
+    /// Lets tools that compare or copy workflows between instances use these other instances as well.
+    pub fn with_instances(mut self, instances: Instances) -> Self {
+        self.instances = instances;
+        self
+    }
+
+    /// Checks workflows against these policies, and stops those that break an enforced one from being created or updated.
+    pub fn with_policies(mut self, policies: Policies) -> Self {
+        self.policies = policies;
+        self
+    }
+
+    /// Checks that the n8n instance can be reached and accepts the API key, so bad configuration is caught at
+    /// startup rather than on the first tool call.
+    pub async fn check_connection(&self) -> anyhow::Result<()> {
+        let url = self.url(Endpoint::Workflows);
+
+        let res = self
+            .client()
+            .get(&url)
+            .query(&Query::new().set("limit", 1))
+            .send_logged(&self.logger)
+            .await
+            .map_err(|err| anyhow::anyhow!("Could not reach n8n at {}: {err}", self.base_url()))?;
+
+        let deployment = self.n8n.deployment();
+
+        match res.status() {
+            status if status.is_success() => Ok(()),
+            reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => {
+                match deployment {
+                    DeploymentKind::Cloud => anyhow::bail!(
+                        "n8n Cloud at {} rejected the API key - create one under Settings > n8n API (the API isn't available on free trials)",
+                        self.base_url()
+                    ),
+                    DeploymentKind::SelfHosted => {
+                        anyhow::bail!("n8n at {} rejected the API key", self.base_url())
+                    }
+                }
+            }
+            reqwest::StatusCode::NOT_FOUND => match deployment {
+                DeploymentKind::Cloud => anyhow::bail!(
+                    "{url} wasn't found - check the instance name in N8N_BASE_URL (https://<name>.app.n8n.cloud)"
+                ),
+                DeploymentKind::SelfHosted => anyhow::bail!(
+                    "{url} wasn't found - check that N8N_BASE_URL points at an n8n instance with the public API enabled"
+                ),
+            },
+            status => anyhow::bail!("n8n at {} responded with {status}", self.base_url()),
+        }
+    }
+
+    async fn fetch_workflow(&self, workflow_id: &WorkflowId) -> Result<Workflow, ServerError> {
+        let url = self.url(Endpoint::Workflow(workflow_id));
+
+        let res = self.client().get(url).send_logged(&self.logger).await?;
+
+        Ok(serde_json::from_value(response::json(res).await?)?)
+    }
+
+    async fn put_workflow(
+        &self,
+        workflow_id: &WorkflowId,
+        workflow: &Workflow,
+    ) -> Result<serde_json::Value, ServerError> {
+        self.put_workflow_body(workflow_id, workflow, workflow.update_body())
+            .await
+    }
+
+    /// Updates a workflow including its pinned data, which [`Self::put_workflow`] leaves as it is.
+    async fn put_workflow_with_pin_data(
+        &self,
+        workflow_id: &WorkflowId,
+        workflow: &Workflow,
+    ) -> Result<serde_json::Value, ServerError> {
+        self.put_workflow_body(workflow_id, workflow, workflow.update_body_with_pin_data())
+            .await
+    }
+
+    async fn put_workflow_body(
+        &self,
+        workflow_id: &WorkflowId,
+        workflow: &Workflow,
+        body: serde_json::Value,
+    ) -> Result<serde_json::Value, ServerError> {
+        self.enforce_policies(Some(workflow_id), workflow).await?;
+        self.snapshot(workflow_id, snapshot::Reason::Update).await?;
+
+        let url = self.url(Endpoint::Workflow(workflow_id));
+
+        let res = self
+            .client()
+            .put(url)
+            .json(&body)
+            .send_logged(&self.logger)
+            .await?;
+
+        response::json(res).await
+    }
+
+    /// Creates a workflow from the body of a `POST`, unless it breaks an enforced policy.
+    async fn post_workflow(
+        &self,
+        body: &serde_json::Value,
+    ) -> Result<serde_json::Value, ServerError> {
+        self.enforce_policies(None, &serde_json::from_value(body.clone())?)
+            .await?;
+
+        let res = self
+            .client()
+            .post(self.url(Endpoint::Workflows))
+            .json(body)
+            .send_logged(&self.logger)
+            .await?;
+
+        response::json(res).await
+    }
+
+    /// Checks a workflow against the configured policies, before it's created (with no ID) or updated. Tag rules are
+    /// only checked on updates, since a workflow can't be tagged until it exists.
+    async fn policy_violations(
+        &self,
+        workflow_id: Option<&WorkflowId>,
+        workflow: &Workflow,
+    ) -> Result<Vec<policy::Violation>, ServerError> {
+        let tags = match workflow_id {
+            Some(workflow_id) if self.policies.iter().any(|policy| policy.checks_tags()) => {
+                let url = self.url(Endpoint::WorkflowTags(workflow_id));
+                let res = self.client().get(url).send_logged(&self.logger).await?;
+                let tags: Vec<Tag> = serde_json::from_value(response::json(res).await?)?;
+                Some(tags)
+            }
+            _ => None,
+        };
+
+        Ok(policy::check(&self.policies, workflow, tags.as_deref()))
+    }
+
+    /// Fails if the workflow breaks an enforced policy.
+    async fn enforce_policies(
+        &self,
+        workflow_id: Option<&WorkflowId>,
+        workflow: &Workflow,
+    ) -> Result<(), ServerError> {
+        if !self.policies.iter().any(|policy| policy.enforced()) {
+            return Ok(());
+        }
+
+        let violations = self.policy_violations(workflow_id, workflow).await?;
+        policy_error(&workflow.name, &violations).map_or(Ok(()), Err)
+    }
+
+    /// Fails if giving a workflow tags with these names would break an enforced policy, e.g. by removing the tag every
+    /// workflow has to have. The workflow's name, for the error, is only looked up if it isn't given and a policy is
+    /// broken.
+    async fn enforce_tag_policies(
+        &self,
+        workflow_id: &WorkflowId,
+        workflow_name: Option<&str>,
+        tag_names: &[&str],
+    ) -> Result<(), ServerError> {
+        let violations = policy::check_tags(&self.policies, tag_names);
+        if !violations.iter().any(|violation| violation.enforced) {
+            return Ok(());
+        }
+
+        let workflow_name = match workflow_name {
+            Some(workflow_name) => workflow_name.to_string(),
+            None => self.fetch_workflow(workflow_id).await?.name,
+        };
+        policy_error(&workflow_name, &violations).map_or(Ok(()), Err)
+    }
+
+    /// The workflow a tool that takes either a workflow ID or a workflow document was given.
+    async fn workflow_or_document(
+        &self,
+        workflow_id: Option<WorkflowId>,
+        workflow: Option<serde_json::Value>,
+    ) -> Result<Workflow, ServerError> {
+        match (workflow_id, workflow) {
+            (Some(workflow_id), None) => {
+                validate::id("workflow", &workflow_id)?;
+                self.fetch_workflow(&workflow_id).await
+            }
+            (None, Some(workflow)) => serde_json::from_value(workflow).map_err(|err| {
+                ServerError::validation(format!(
+                    "The workflow isn't valid: {err}. Check it with validate_workflow_json"
+                ))
+            }),
+            _ => Err(ServerError::validation(
+                "Give either workflow_id, for a workflow in n8n, or workflow, for a workflow document",
+            )),
+        }
+    }
+
+    /// The server for one of the other configured instances, or this server itself when no instance is named.
+    fn on_instance(&self, instance: Option<&str>) -> Result<Self, ServerError> {
+        let Some(name) = instance else {
+            return Ok(self.clone());
+        };
+
+        let Some(n8n) = self.instances.get(name) else {
+            let configured: Vec<&str> = self.instances.keys().map(String::as_str).collect();
+            return Err(ServerError::validation(if configured.is_empty() {
+                format!(
+                    "There's no n8n instance called {name} - no other instances are configured (see the [instances] section of the config file)"
+                )
+            } else {
+                format!(
+                    "There's no n8n instance called {name} - the configured instances are {}",
+                    configured.join(", ")
+                )
+            }));
+        };
+
+        let mut server = Self {
+            n8n: n8n.clone(),
+            ..self.clone()
+        };
+        // Snapshots are kept by workflow ID, which only identifies a workflow on this server's own instance
+        server.features.snapshot_dir = None;
+
+        Ok(server)
+    }
+
+    /// Saves the workflow as it is in n8n, before it's updated, deleted or archived, when a snapshot directory is
+    /// configured, returning where it was saved. If the snapshot can't be saved, the error is returned so that the
+    /// change isn't made without one.
+    async fn snapshot(
+        &self,
+        workflow_id: &WorkflowId,
+        reason: snapshot::Reason,
+    ) -> Result<Option<PathBuf>, ServerError> {
+        let Some(snapshot_dir) = &self.features.snapshot_dir else {
+            return Ok(None);
+        };
+
+        let url = self.url(Endpoint::Workflow(workflow_id));
+
+        let res = self.client().get(url).send_logged(&self.logger).await?;
+
+        let workflow = response::json(res).await?;
+        let path = snapshot::save(snapshot_dir, workflow_id, &workflow, reason).await?;
+
+        Ok(Some(path))
+    }
+
+    /// Replaces a workflow's tags with the given ones, unless that would break an enforced policy.
+    async fn put_workflow_tags(
+        &self,
+        workflow: &Workflow,
+        workflow_id: &WorkflowId,
+        tags: Vec<&Tag>,
+    ) -> Result<(), ServerError> {
+        let tag_names: Vec<&str> = tags.iter().map(|tag| tag.name.as_str()).collect();
+        self.enforce_tag_policies(workflow_id, Some(&workflow.name), &tag_names)
+            .await?;
+
+        let url = self.url(Endpoint::WorkflowTags(workflow_id));
+        let body: Vec<_> = tags.into_iter().map(|tag| json!({"id": tag.id})).collect();
+
+        let res = self
+            .client()
+            .put(url)
+            .json(&body)
+            .send_logged(&self.logger)
+            .await?;
+        response::ok(res).await?;
+
+        Ok(())
+    }
+
+    async fn recent_execution_resources(&self) -> Result<Vec<Resource>, McpError> {
+        let url = self.url(Endpoint::Executions);
+
+        let res = self
+            .client()
+            .get(url)
+            .query(&Query::new().set("limit", 20))
+            .send_logged(&self.logger)
+            .await
+            .map_err(ServerError::from)?;
+
+        let res = response::json(res).await?;
+
+        let resources = res["data"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|execution| {
+                let id = match &execution["id"] {
+                    serde_json::Value::String(id) => id.clone(),
+                    serde_json::Value::Number(id) => id.to_string(),
+                    _ => return None,
+                };
+
+                let mut resource = RawResource::new(
+                    ResourceUri::Execution(id.as_str().into()).to_string(),
+                    format!("Execution {id}"),
+                );
+                resource.description = Some(format!(
+                    "Execution of workflow {} ({}), started at {}",
+                    execution["workflowId"],
+                    execution["status"].as_str().unwrap_or("unknown status"),
+                    execution["startedAt"].as_str().unwrap_or("an unknown time"),
+                ));
+                resource.mime_type = Some("application/json".to_string());
+
+                Some(resource.no_annotation())
+            })
+            .collect();
+
+        Ok(resources)
+    }
+
+    async fn completion_candidates(
+        &self,
+        kind: CompletionKind,
+    ) -> Result<Vec<Candidate>, ServerError> {
+        if let Some(candidates) = self.completions.get(kind) {
+            return Ok(candidates);
+        }
+
+        let to_candidate = |item: &serde_json::Value, label: &serde_json::Value| {
+            let id = match &item["id"] {
+                serde_json::Value::String(id) => id.clone(),
+                serde_json::Value::Number(id) => id.to_string(),
+                _ => return None,
+            };
+            let label = match label {
+                serde_json::Value::String(label) => label.clone(),
+                label => label.to_string(),
+            };
+
+            Some(Candidate { id, label })
+        };
+
+        let candidates: Vec<Candidate> = match kind {
+            CompletionKind::Workflow => {
+                let url = self.url(Endpoint::Workflows);
+                let res = self
+                    .client()
+                    .get(url)
+                    .query(&Query::new().set("limit", MAX_PAGE_SIZE))
+                    .send_logged(&self.logger)
+                    .await?;
+                let res = response::json(res).await?;
+
+                res["data"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|workflow| to_candidate(workflow, &workflow["name"]))
+                    .collect()
+            }
+            CompletionKind::Execution => {
+                let url = self.url(Endpoint::Executions);
+                let res = self
+                    .client()
+                    .get(url)
+                    .query(&Query::new().set("limit", 100))
+                    .send_logged(&self.logger)
+                    .await?;
+                let res = response::json(res).await?;
+
+                res["data"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|execution| to_candidate(execution, &execution["workflowId"]))
+                    .collect()
+            }
+            CompletionKind::Tag => self
+                .fetch_all_tags()
+                .await?
+                .into_iter()
+                .map(|tag| Candidate {
+                    id: tag.id.to_string(),
+                    label: tag.name,
+                })
+                .collect(),
+        };
+
+        self.completions.insert(kind, candidates.clone());
+
+        Ok(candidates)
+    }
+
+    /// Every tag on the instance, up to [`MAX_FETCH_ALL_PAGES`] pages of them, which is far more than instances have.
+    async fn fetch_all_tags(&self) -> Result<Vec<Tag>, ServerError> {
+        let query = Query::new().set("limit", MAX_PAGE_SIZE);
+        let tags: Paginated<Tag> = self.fetch_all_pages(Endpoint::Tags, query, None).await?;
+
+        Ok(tags.data)
+    }
+
+    /// Fetches a single page of a list.
+    async fn fetch_page<T: DeserializeOwned>(
+        &self,
+        endpoint: Endpoint<'_>,
+        query: &Query,
+    ) -> Result<Paginated<T>, ServerError> {
+        let res = self
+            .client()
+            .get(self.url(endpoint))
+            .query(query)
+            .send_logged(&self.logger)
+            .await?;
+
+        Ok(serde_json::from_value(response::json(res).await?)?)
+    }
+
+    /// Fetches an execution with its data, which holds the input and output of every node and why it failed if it did.
+    async fn fetch_execution(&self, execution_id: &ExecutionId) -> Result<Execution, ServerError> {
+        let url = self.url(Endpoint::Execution(execution_id));
+
+        let res = self
+            .client()
+            .get(url)
+            .query(&Query::new().set("includeData", true))
+            .send_logged(&self.logger)
+            .await?;
+
+        Ok(serde_json::from_value(response::json(res).await?)?)
+    }
+
+    async fn delete_execution(
+        &self,
+        execution_id: &ExecutionId,
+    ) -> Result<serde_json::Value, ServerError> {
+        let url = self.url(Endpoint::Execution(execution_id));
+
+        let res = self.client().delete(url).send_logged(&self.logger).await?;
+
+        response::json(res).await
+    }
+
+    /// The most recent executions of a workflow, newest first.
+    async fn recent_executions(
+        &self,
+        workflow_id: &WorkflowId,
+    ) -> Result<Vec<Execution>, ServerError> {
+        let query = Query::new().set("workflowId", workflow_id).set("limit", 20);
+        let res: Paginated<Execution> = self.fetch_page(Endpoint::Executions, &query).await?;
+
+        Ok(res.data)
+    }
+
+    /// The executions that match a filter, newest first. Stops after [`MAX_RANGE_PAGES`] pages or the filter's limit,
+    /// saying whether there were more. Pages that only hold executions newer than `started_before` don't count towards
+    /// the cap, so that a window in the past can be reached on a busy instance, but only up to [`MAX_SKIPPED_PAGES`] of
+    /// them are followed.
+    ///
+    /// With `include_data`, pages are [`DATA_PAGE_SIZE`] executions rather than the most the API allows, since each
+    /// execution's data can be large.
+    async fn executions_in_range(
+        &self,
+        filter: ExecutionFilter<'_>,
+    ) -> Result<(Vec<Execution>, bool), ServerError> {
+        let ExecutionFilter {
+            started_after,
+            started_before,
+            status,
+            workflow_id,
+            include_data,
+            limit,
+        } = filter;
+        let mut executions = Vec::new();
+        let mut cursor: Option<String> = None;
+        let mut truncated = false;
+        let mut pages = 0;
+        let mut skipped_pages = 0;
+
+        'pages: loop {
+            let query = Query::new()
+                .set(
+                    "limit",
+                    if include_data {
+                        DATA_PAGE_SIZE
+                    } else {
+                        MAX_PAGE_SIZE
+                    },
+                )
+                .set_optional("includeData", include_data.then_some(true))
+                .set_optional("status", status)
+                .set_optional("workflowId", workflow_id)
+                .set_optional("cursor", cursor);
+
+            let res: Paginated<Execution> = self.fetch_page(Endpoint::Executions, &query).await?;
+
+            // Whether every execution on the page is too new to be in range
+            let mut skipped = true;
+            for execution in res.data {
+                let Some(started_at) = execution.started_at.as_deref().and_then(parse_timestamp)
+                else {
+                    continue;
+                };
+
+                if started_before.is_some_and(|before| started_at >= before) {
+                    continue;
+                }
+                skipped = false;
+                // Executions are returned newest first, so everything after this is out of range too
+                if started_after.is_some_and(|after| started_at < after) {
+                    break 'pages;
+                }
+                if limit.is_some_and(|limit| executions.len() == limit) {
+                    truncated = true;
+                    break 'pages;
+                }
+
+                executions.push(execution);
+            }
+
+            if skipped {
+                skipped_pages += 1;
+            } else {
+                pages += 1;
+                progress::report(pages as u32, Some(MAX_RANGE_PAGES as u32)).await;
+            }
+
+            cursor = res.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+            if pages == MAX_RANGE_PAGES || skipped_pages == MAX_SKIPPED_PAGES {
+                truncated = true;
+                break;
+            }
+        }
+
+        Ok((executions, truncated))
+    }
+
+    /// The `data` of one of the endpoints the n8n editor uses. They aren't part of the public API and often don't
+    /// accept the server's credentials, so a failure is only logged, and read as `null`.
+    async fn editor_data(&self, endpoint: Endpoint<'_>) -> serde_json::Value {
+        let url = self.url(endpoint);
+        let res = match self.client().get(url).send_logged(&self.logger).await {
+            Ok(res) => response::json(res).await,
+            Err(err) => Err(err.into()),
+        };
+
+        match res {
+            Ok(body) => body["data"].clone(),
+            Err(err) => {
+                tracing::debug!("Could not read {endpoint} from n8n: {err}");
+                serde_json::Value::Null
+            }
+        }
+    }
+
+    /// The name of every workflow, by ID.
+    async fn workflow_names(&self) -> Result<HashMap<WorkflowId, String>, ServerError> {
+        let query = Query::new().set("limit", MAX_PAGE_SIZE);
+        let workflows: Paginated<Workflow> = self
+            .fetch_all_pages(Endpoint::Workflows, query, None)
+            .await?;
+
+        Ok(workflows
+            .data
+            .into_iter()
+            .filter_map(|workflow| Some((workflow.id?, workflow.name)))
+            .collect())
+    }
+
+    /// The active workflow with a webhook trigger listening on `path`.
+    async fn find_webhook_workflow(&self, path: &str) -> Result<WorkflowId, ServerError> {
+        let query = Query::new().set("active", true).set("limit", MAX_PAGE_SIZE);
+        let workflows: Paginated<Workflow> = self
+            .fetch_all_pages(Endpoint::Workflows, query, None)
+            .await?;
+
+        let path = path.trim_matches('/');
+        workflows
+            .data
+            .into_iter()
+            .find(|workflow| {
+                workflow::trigger_summary(&workflow.nodes)
+                    .webhooks
+                    .iter()
+                    .any(|webhook| webhook.path.trim_matches('/') == path)
+            })
+            .and_then(|workflow| workflow.id)
+            .ok_or_else(|| {
+                ServerError::validation(format!(
+                    "No active workflow has a webhook at '{path}', so its execution can't be found. Set workflow_id to the workflow the webhook belongs to"
+                ))
+            })
+    }
+
+    /// Waits for the execution of a workflow that started at or after `since`, other than those in `earlier`, to
+    /// finish. Gives back the execution as it was when `timeout` ran out if it hasn't finished by then, or `None` if
+    /// it can't be found at all, e.g. because the workflow doesn't save its executions.
+    async fn wait_for_execution(
+        &self,
+        workflow_id: &WorkflowId,
+        since: DateTime<Utc>,
+        earlier: &HashSet<ExecutionId>,
+        timeout: Duration,
+    ) -> Result<Option<Execution>, ServerError> {
+        let started = Instant::now();
+
+        loop {
+            let execution = self
+                .recent_executions(workflow_id)
+                .await?
+                .into_iter()
+                // The oldest is the one that started closest to the webhook call
+                .rfind(|execution| {
+                    !earlier.contains(&execution.id)
+                        && execution
+                            .started_at
+                            .as_deref()
+                            .and_then(parse_timestamp)
+                            .is_some_and(|started_at| started_at >= since - MAX_CLOCK_SKEW)
+                });
+
+            let timed_out = started.elapsed() >= timeout;
+            match execution {
+                Some(execution) if execution.is_finished() || timed_out => {
+                    return Ok(Some(self.fetch_execution(&execution.id).await?));
+                }
+                None if timed_out => return Ok(None),
+                _ => {}
+            }
+
+            progress::report(
+                started.elapsed().as_secs() as u32,
+                Some(timeout.as_secs() as u32),
+            )
+            .await;
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+    }
+
+    /// Calls a workflow's webhook (its production or test URL), with `data` as the body of a `POST` or, without any, as
+    /// a `GET`.
+    async fn call_webhook(
+        &self,
+        webhook: Endpoint<'_>,
+        data: Option<serde_json::Value>,
+    ) -> Result<reqwest::Response, ServerError> {
+        let url = webhook.url(&self.n8n.webhook_base_url());
+
+        let res = if let Some(data) = data {
+            self.client()
+                .post(url)
+                .json(&data)
+                .send_logged(&self.logger)
+                .await
+        } else {
+            self.client().get(url).send_logged(&self.logger).await
+        };
+
+        // A webhook that doesn't exist (or belongs to an inactive workflow) responds with a 404
+        response::ok(res?).await
+    }
+
+    /// Calls a workflow's webhook, then waits for the execution it starts and reports how it went.
+    async fn run_workflow_and_wait(
+        &self,
+        webhook_path: &str,
+        data: Option<serde_json::Value>,
+        workflow_id: WorkflowId,
+        timeout: Duration,
+    ) -> Result<serde_json::Value, ServerError> {
+        let earlier: HashSet<ExecutionId> = self
+            .recent_executions(&workflow_id)
+            .await?
+            .into_iter()
+            .map(|execution| execution.id)
+            .collect();
+        let since = Utc::now();
+
+        let body = self
+            .call_webhook(Endpoint::Webhook(webhook_path), data)
+            .await?
+            .text()
+            .await?;
+        // Webhooks can respond with anything, so a body that isn't JSON is kept as text
+        let webhook_response = serde_json::from_str(&body).unwrap_or(json!(body));
+
+        let Some(execution) = self
+            .wait_for_execution(&workflow_id, since, &earlier, timeout)
+            .await?
+        else {
+            return Err(ServerError::Detailed(json!({
+                "error": "The webhook was called, but the execution it started couldn't be found",
+                "workflowId": workflow_id,
+                "webhookResponse": webhook_response,
+                "hint": "The workflow may not save its executions (see the saveDataSuccessExecution and saveDataErrorExecution settings), or it may still be queued. Look for it later with retrieve_all_executions",
+            })));
+        };
+
+        let last_node = execution.last_node().map(ToString::to_string);
+        let output = last_node
+            .as_deref()
+            .and_then(|node| execution.node_output(node));
+
+        let mut json_object = json!({
+            "workflowId": workflow_id,
+            "webhookResponse": webhook_response,
+            "execution": execution.summary(),
+            "lastNode": last_node,
+            "output": output,
+        });
+        if !execution.is_finished() {
+            json_object["warning"] = json!(format!(
+                "The execution hadn't finished after {} seconds. Check on it later with retrieve_execution_by_id",
+                timeout.as_secs()
+            ));
+        }
+
+        Ok(json_object)
+    }
+
+    /// Follows `nextCursor` from `cursor` onwards, for tools' `fetch_all` option. Returns every item fetched as a single
+    /// page, whose `nextCursor` is only set if [`MAX_FETCH_ALL_PAGES`] was reached before the last page.
+    async fn fetch_all_pages<T: DeserializeOwned>(
+        &self,
+        endpoint: Endpoint<'_>,
+        query: Query,
+        mut cursor: Option<String>,
+    ) -> Result<Paginated<T>, ServerError> {
+        let mut data = Vec::new();
+
+        for page in 1..=MAX_FETCH_ALL_PAGES {
+            let query = query.clone().set_optional("cursor", cursor);
+            let res: Paginated<T> = self.fetch_page(endpoint, &query).await?;
+
+            data.extend(res.data);
+            progress::report(page, Some(MAX_FETCH_ALL_PAGES)).await;
+
+            cursor = res.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+
+        Ok(Paginated {
+            data,
+            next_cursor: cursor,
+        })
+    }
+
+    /// Activates a workflow. If n8n refuses, the error says why, along with a hint on how to fix it.
+    async fn activate_workflow(
+        &self,
+        workflow_id: &WorkflowId,
+    ) -> Result<serde_json::Value, ServerError> {
+        let url = self.url(Endpoint::ActivateWorkflow(workflow_id));
+
+        let res = self.client().post(url).send_logged(&self.logger).await?;
+
+        let status = res.status();
+        let body = res.text().await?;
+
+        if !status.is_success() {
+            // n8n reports why activation failed in the `message` field, but the body isn't guaranteed to be JSON
+            let message = serde_json::from_str::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|body| body["message"].as_str().map(ToString::to_string))
+                .unwrap_or(body);
+
+            let json_object = json!({
+                "error": "Workflow activation failed",
+                "workflowId": workflow_id,
+                "status": status.as_u16(),
+                "message": message,
+                "hint": activation_error_hint(&message),
+            });
+
+            return Err(ServerError::Detailed(json_object));
+        }
+
+        response::parse(status, &body)
+    }
+
+    async fn deactivate_workflow(
+        &self,
+        workflow_id: &WorkflowId,
+    ) -> Result<serde_json::Value, ServerError> {
+        let url = self.url(Endpoint::DeactivateWorkflow(workflow_id));
+
+        let res = self.client().post(url).send_logged(&self.logger).await?;
+
+        response::json(res).await
+    }
+
+    async fn delete_workflow(
+        &self,
+        workflow_id: &WorkflowId,
+    ) -> Result<serde_json::Value, ServerError> {
+        self.snapshot(workflow_id, snapshot::Reason::Delete).await?;
+
+        let url = self.url(Endpoint::Workflow(workflow_id));
+
+        let res = self.client().delete(url).send_logged(&self.logger).await?;
+
+        response::json(res).await
+    }
+
+    /// Finds every workflow a bulk tool's filter picks out. Rather than only acting on some of them, it fails if there
+    /// are more than [`MAX_FETCH_ALL_PAGES`] pages of them.
+    async fn find_workflows(&self, filter: &WorkflowFilter) -> Result<Vec<Workflow>, ServerError> {
+        let workflows: Paginated<Workflow> = self
+            .fetch_all_pages(Endpoint::Workflows, filter.query(), None)
+            .await?;
+
+        if workflows.next_cursor.is_some() {
+            return Err(ServerError::validation(format!(
+                "More than {} workflows match the filter - narrow it down and try again",
+                workflows.data.len()
+            )));
+        }
+
+        Ok(workflows
+            .data
+            .into_iter()
+            .filter(|workflow| filter.matches(workflow))
+            .collect())
+    }
+
+    /// Finds the workflows called `name`. n8n's own filter isn't relied on to match the name exactly.
+    async fn find_workflows_named(&self, name: &str) -> Result<Vec<Workflow>, ServerError> {
+        let query = Query::new()
+            .set("name", name.trim())
+            .set("limit", MAX_PAGE_SIZE);
+        let workflows: Paginated<Workflow> = self
+            .fetch_all_pages(Endpoint::Workflows, query, None)
+            .await?;
+
+        Ok(workflows
+            .data
+            .into_iter()
+            .filter(|workflow| workflow.name.trim() == name.trim())
+            .collect())
+    }
+
+    /// Restores a single workflow from a backup, returning whether it was `created` or `updated`, and its ID and name.
+    async fn import_workflow(
+        &self,
+        workflow: Workflow,
+        match_by: ImportMatch,
+    ) -> Result<serde_json::Value, ServerError> {
+        validate::not_empty("The workflow name", &workflow.name)?;
+        validate::nodes(&workflow.nodes)?;
+
+        let existing_id = match match_by {
+            ImportMatch::Id => match &workflow.id {
+                Some(workflow_id) => {
+                    validate::id("workflow", workflow_id)?;
+                    match self.fetch_workflow(workflow_id).await {
+                        Ok(_) => Some(workflow_id.clone()),
+                        Err(err) if err.status() == Some(reqwest::StatusCode::NOT_FOUND) => None,
+                        Err(err) => return Err(err),
+                    }
+                }
+                None => None,
+            },
+            ImportMatch::Name => {
+                match self.find_workflows_named(&workflow.name).await?.as_slice() {
+                    [] => None,
+                    [existing] => existing.id.clone(),
+                    existing => {
+                        return Err(ServerError::validation(format!(
+                            "There are {} workflows called '{}', so it isn't clear which to update",
+                            existing.len(),
+                            workflow.name
+                        )));
+                    }
+                }
+            }
+        };
+
+        if let Some(workflow_id) = existing_id {
+            self.put_workflow(&workflow_id, &workflow).await?;
+            return Ok(json!({
+                "action": "updated",
+                "id": workflow_id,
+                "name": workflow.name,
+            }));
+        }
+
+        let res = self.post_workflow(&workflow.update_body()).await?;
+
+        Ok(json!({
+            "action": "created",
+            "id": res["id"],
+            "name": workflow.name,
+        }))
+    }
+
+    /// Checks the credentials nodes use against the instance's, for the warnings returned when a workflow is created or
+    /// updated.
+    async fn credential_warnings(&self, nodes: &[Node]) -> Vec<String> {
+        if nodes.iter().all(|node| node.credentials.is_none()) {
+            return Vec::new();
+        }
+
+        let query = Query::new().set("limit", MAX_PAGE_SIZE);
+        match self
+            .fetch_all_pages(Endpoint::Credentials, query, None)
+            .await
+        {
+            Ok(credentials) => workflow::credential_warnings(nodes, &credentials.data),
+            // Older instances can't list credentials through the API
+            Err(err) => vec![format!(
+                "The nodes' credentials couldn't be checked, as n8n couldn't list its credentials: {err}"
+            )],
+        }
+    }
+
+    async fn create_tag_named(&self, name: &str) -> Result<Tag, ServerError> {
+        let url = self.url(Endpoint::Tags);
+
+        let res = self
+            .client()
+            .post(url)
+            .json(&json!({"name": name}))
+            .send_logged(&self.logger)
+            .await?;
+
+        Ok(serde_json::from_value(response::json(res).await?)?)
+    }
+}
+
+/// How many pages a `fetch_all` retrieval follows before it stops and hands back the cursor to carry on from.
+const MAX_FETCH_ALL_PAGES: u32 = 20;
+
+/// How many pages of executions are fetched for a date range. Each page holds up to 250 executions, so this caps a
+/// single call at 5000 executions, or 1000 with their data.
+const MAX_RANGE_PAGES: usize = 20;
+
+/// How many pages of executions newer than a date range are skipped over to reach it, on top of [`MAX_RANGE_PAGES`].
+const MAX_SKIPPED_PAGES: usize = 200;
+
+/// The most executions `search_executions` looks through in one call.
+const MAX_SEARCHED_EXECUTIONS: usize = 1000;
+
+/// How many executions are fetched at a time along with their data.
+const DATA_PAGE_SIZE: u8 = 50;
+
+/// How much of its execution timeout a workflow's longest run has to take for `execution_durations` to flag it.
+const TIMEOUT_WARNING_SHARE: f64 = 0.8;
+
+/// The highest number `create_workflow` adds to a name that's taken, e.g. `Sync (100)`.
+const MAX_NAME_SUFFIX: u32 = 100;
+
+/// How many executions `prune_executions` deletes at once.
+const MAX_CONCURRENT_DELETES: usize = 5;
+
+/// The tag `archive_workflow` marks archived workflows with.
+const ARCHIVED_TAG: &str = "archived";
+
+/// How long `run_workflow` waits for the execution it started to finish, unless it's told otherwise.
+const DEFAULT_RUN_TIMEOUT_SECONDS: u64 = 60;
+
+const MAX_RUN_TIMEOUT_SECONDS: u64 = 300;
+
+/// How far n8n's clock can be behind the server's, for finding the execution a webhook call started by when it
+/// started.
+const MAX_CLOCK_SKEW: TimeDelta = TimeDelta::seconds(30);
+
+/// The settings every workflow created by the server starts with, before any it was created with are applied.
+#[derive(Deserialize, Serialize, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowSettings {
+    save_execution_progress: bool,
+    save_manual_executions: bool,
+    save_data_error_execution: AllOrNone,
+    save_data_success_execution: AllOrNone,
+    execution_timeout: u16,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RetrieveAllWorkflowParams {
+    active: Option<bool>,
+    #[schemars(description = "Comma-separated tag names to filter by, e.g. 'prod,billing'.")]
+    tags: Option<String>,
+    name: Option<String>,
+    project_id: Option<ProjectId>,
+    exclude_pinned_data: Option<bool>,
+    limit: Option<u8>,
+    cursor: Option<String>,
+}
+
+impl RetrieveAllWorkflowParams {
+    fn query(&self) -> Query {
+        Query::new()
+            .set_optional("active", self.active)
+            .set_optional("tags", self.tags.as_deref())
+            .set_optional("name", self.name.as_deref())
+            .set_optional("projectId", self.project_id.as_deref())
+            .set_optional("excludePinnedData", self.exclude_pinned_data)
+            .set_optional("limit", self.limit)
+            .set_optional("cursor", self.cursor.as_deref())
+    }
+
+    fn validate(&self) -> Result<(), ServerError> {
+        validate::optional_id("project", self.project_id.as_deref())?;
+        if let Some(limit) = self.limit {
+            validate::limit(limit)?;
+        }
+        if let Some(cursor) = &self.cursor {
+            validate::cursor(cursor)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Picks out workflows for the bulk tools. At least one field has to be set, so a bulk change can't reach every
+/// workflow by accident.
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowFilter {
+    #[schemars(
+        description = "Comma-separated tag names, e.g. 'prod,billing'. Only workflows with these tags match. Optional."
+    )]
+    tags: Option<String>,
+    #[schemars(
+        description = "Only workflows whose name starts with this match, e.g. 'Billing - '. Optional."
+    )]
+    name_prefix: Option<String>,
+    #[schemars(description = "Only workflows in this project match. Optional.")]
+    project_id: Option<ProjectId>,
+    #[schemars(description = "Only active (true) or inactive (false) workflows match. Optional.")]
+    active: Option<bool>,
+}
+
+impl WorkflowFilter {
+    fn validate(&self) -> Result<(), ServerError> {
+        if self.tags.is_none()
+            && self.name_prefix.is_none()
+            && self.project_id.is_none()
+            && self.active.is_none()
+        {
+            return Err(ServerError::validation(
+                "The filter needs at least one of tags, namePrefix, projectId and active, so that it can't match every workflow by accident",
+            ));
+        }
+        if let Some(tags) = &self.tags {
+            validate::not_empty("tags", tags)?;
+        }
+        if let Some(name_prefix) = &self.name_prefix {
+            validate::not_empty("namePrefix", name_prefix)?;
+        }
+        validate::optional_id("project", self.project_id.as_deref())?;
+
+        Ok(())
+    }
+
+    /// The parts of the filter n8n can apply itself. The name prefix is matched with [`matches`](Self::matches).
+    fn query(&self) -> Query {
+        Query::new()
+            .set_optional("tags", self.tags.as_deref())
+            .set_optional("projectId", self.project_id.as_deref())
+            .set_optional("active", self.active)
+            .set("limit", MAX_PAGE_SIZE)
+    }
+
+    fn matches(&self, workflow: &Workflow) -> bool {
+        self.name_prefix
+            .as_deref()
+            .is_none_or(|name_prefix| workflow.name.starts_with(name_prefix))
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowSettingsUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "Whether to save the progress of each node while executing.")]
+    save_execution_progress: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "Whether to save manually triggered executions.")]
+    save_manual_executions: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "Whether to save failed executions. Can either be: 'all' | 'none'")]
+    save_data_error_execution: Option<AllOrNone>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        description = "Whether to save successful executions. Can either be: 'all' | 'none'"
+    )]
+    save_data_success_execution: Option<AllOrNone>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        description = "The maximum run time of an execution in seconds. Use -1 to disable the timeout."
+    )]
+    execution_timeout: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "The ID of the workflow to run when this workflow errors.")]
+    error_workflow: Option<WorkflowId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "The timezone to use for this workflow, e.g. 'Europe/London'.")]
+    timezone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "The execution order of the workflow. Can either be: 'v0' | 'v1'")]
+    execution_order: Option<ExecutionOrder>,
+}
+
+impl WorkflowSettings {
+    fn apply_to(&self, settings: &mut serde_json::Map<String, serde_json::Value>) {
+        if let serde_json::Value::Object(defaults) = json!(self) {
+            settings.extend(defaults);
+        }
+    }
+}
+
+impl WorkflowSettingsUpdate {
+    fn validate(&self) -> Result<(), ServerError> {
+        validate::optional_id("error workflow", self.error_workflow.as_deref())?;
+        if self.execution_timeout.is_some_and(|timeout| timeout < -1) {
+            return Err(ServerError::validation(
+                "executionTimeout must be a number of seconds, or -1 to disable the timeout",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Sets the given settings on top of `settings`, leaving the rest as they are.
+    fn apply_to(&self, settings: &mut serde_json::Map<String, serde_json::Value>) {
+        if let serde_json::Value::Object(update) = json!(self) {
+            settings.extend(update);
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionOrder {
+    V0,
+    V1,
+}
+
+/// How `import_workflows` finds the workflow a backup should update.
+#[derive(Deserialize, Serialize, JsonSchema, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMatch {
+    /// The workflow with the ID stored in the backup.
+    #[default]
+    Id,
+    /// The only workflow with the same name as the backup.
+    Name,
+}
+
+/// What `create_workflow` does when there's already a workflow with the name it was given.
+#[derive(Deserialize, Serialize, JsonSchema, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OnNameConflict {
+    /// Create another workflow with the same name, as n8n allows.
+    #[default]
+    Allow,
+    /// Don't create the workflow, and return an error.
+    Fail,
+    /// Create the workflow with a number appended to its name, e.g. 'Orders (2)'.
+    Suffix,
+    /// Update the existing workflow instead of creating a new one.
+    Upsert,
+}
+
+/// What `export_executions` writes.
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// The executions' summaries, or with include_data, everything about them.
+    Json,
+    /// A table with a row for each execution.
+    Csv,
+}
+
+impl ExportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+/// What `convert_trigger` turns a workflow's trigger into.
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TriggerConversion {
+    Webhook,
+    /// On a cron schedule.
+    Schedule,
+    /// Only run by hand, from the editor.
+    Manual,
+}
+
+impl TriggerConversion {
+    fn trigger_type(self) -> scaffold::TriggerType {
+        match self {
+            Self::Webhook => scaffold::TriggerType::Webhook,
+            Self::Schedule => scaffold::TriggerType::Schedule,
+            Self::Manual => scaffold::TriggerType::Manual,
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RetrieveSingleWorkflowParams {
+    id: String,
+    exclude_pinned_data: Option<bool>,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AllOrNone {
+    #[default]
+    All,
+    None,
+}
+
+impl fmt::Display for AllOrNone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => write!(f, "all"),
+            Self::None => write!(f, "none"),
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWorkflowParams {
+    name: String,
+    nodes: Vec<Node>,
+    connections: Connections,
+    settings: WorkflowSettings,
+    static_data: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionStatus {
+    Error,
+    Success,
+    Waiting,
+    Running,
+}
+
+impl fmt::Display for ExecutionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error => write!(f, "error"),
+            Self::Success => write!(f, "success"),
+            Self::Waiting => write!(f, "waiting"),
+            Self::Running => write!(f, "running"),
+        }
+    }
+}
+
+/// Suggests how to fix a workflow based on the message n8n gives when activation fails.
+fn activation_error_hint(message: &str) -> &'static str {
+    let message = message.to_lowercase();
+
+    if message.contains("trigger") || message.contains("node to start") {
+        "The workflow has no trigger node. Add a trigger (e.g. a webhook, schedule or polling node) before activating it."
+    } else if message.contains("credential") {
+        "A node in the workflow is missing credentials or its credentials are invalid. Check the credentials assigned to each node."
+    } else if message.contains("not found") {
+        "The workflow doesn't exist. Retrieve all workflows to find the correct ID."
+    } else if message.contains("license") || message.contains("limit") {
+        "The instance has reached its active workflow limit. Check the license info or deactivate another workflow first."
+    } else {
+        "Fix the issue described in the message, then try activating the workflow again."
+    }
+}
+
+/// What a create or update tool would have sent to n8n, for a dry run: the request, and whether its body matches
+/// n8n's workflow format.
+fn dry_run_request(method: &str, url: String, body: serde_json::Value) -> serde_json::Value {
+    let violations = workflow::schema_violations(&body);
+
+    json!({
+        "method": method,
+        "url": url,
+        "body": body,
+        "valid": violations.is_empty(),
+        "violations": violations,
+    })
+}
+
+/// The error for a workflow that breaks an enforced policy, if it does.
+fn policy_error(workflow_name: &str, violations: &[policy::Violation]) -> Option<ServerError> {
+    let enforced: Vec<&policy::Violation> = violations
+        .iter()
+        .filter(|violation| violation.enforced)
+        .collect();
+    if enforced.is_empty() {
+        return None;
+    }
+
+    Some(ServerError::Detailed(json!({
+        "error": "Blocked by policy",
+        "workflow": workflow_name,
+        "violations": enforced,
+        "hint": "Change the workflow so it follows these policies, then try again. check_policies lists every configured policy",
+    })))
+}
+
+/// Warnings for the policies a workflow breaks, or an error if any of them is enforced.
+fn policy_warnings(
+    workflow_name: &str,
+    violations: &[policy::Violation],
+) -> Result<Vec<String>, ServerError> {
+    if let Some(error) = policy_error(workflow_name, violations) {
+        return Err(error);
+    }
+
+    Ok(violations
+        .iter()
+        .map(|violation| format!("Policy {}: {}", violation.policy, violation.message))
+        .collect())
+}
+
+/// Which executions [`Server::executions_in_range`] fetches.
+#[derive(Default)]
+struct ExecutionFilter<'a> {
+    started_after: Option<DateTime<Utc>>,
+    started_before: Option<DateTime<Utc>>,
+    status: Option<&'a ExecutionStatus>,
+    workflow_id: Option<&'a WorkflowId>,
+    /// Fetch each execution's data as well.
+    include_data: bool,
+    /// The most executions to fetch.
+    limit: Option<usize>,
+}
+
+/// When executions have to have started after and before, either of which can be left open.
+type Range = (Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+
+/// Parses the `started_after` and `started_before` of a date range.
+fn parse_range(
+    started_after: Option<&str>,
+    started_before: Option<&str>,
+) -> Result<Range, ServerError> {
+    match (
+        started_after.map(parse_timestamp),
+        started_before.map(parse_timestamp),
+    ) {
+        (Some(None), _) | (_, Some(None)) => Err(ServerError::validation(
+            "started_after and started_before must be RFC 3339 timestamps or YYYY-MM-DD dates",
+        )),
+        (started_after, started_before) => Ok((started_after.flatten(), started_before.flatten())),
+    }
+}
+
+/// How long to wait for a run to finish, from a tool's `timeout_seconds`.
+fn run_timeout(timeout_seconds: Option<u64>) -> Result<Duration, ServerError> {
+    let timeout_seconds = timeout_seconds.unwrap_or(DEFAULT_RUN_TIMEOUT_SECONDS);
+    if !(1..=MAX_RUN_TIMEOUT_SECONDS).contains(&timeout_seconds) {
+        return Err(ServerError::validation(format!(
+            "timeout_seconds must be between 1 and {MAX_RUN_TIMEOUT_SECONDS}"
+        )));
+    }
+
+    Ok(Duration::from_secs(timeout_seconds))
+}
+
+/// The error for a node name that isn't in the workflow, listing the names that are.
+fn no_such_node(workflow_id: &WorkflowId, workflow: &Workflow, node_name: &str) -> ServerError {
+    ServerError::validation(format!(
+        "No node named '{node_name}' in workflow {workflow_id}. Available nodes: {}",
+        workflow.node_names().join(", ")
+    ))
+}
+
+/// Parses either an RFC 3339 timestamp or a plain `YYYY-MM-DD` date (taken as midnight UTC).
+fn parse_timestamp(timestamp: &str) -> Option<DateTime<Utc>> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(timestamp) {
+        return Some(timestamp.with_timezone(&Utc));
+    }
+
+    NaiveDate::parse_from_str(timestamp, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|date| date.and_utc())
+}
+
+#[tool(tool_box)]
+impl Server {
+    #[tool(description = "Retrieve all executions.")]
+    // Tool parameters are collected into a single struct by #[tool], so clients never see a long argument list
+    #[allow(clippy::too_many_arguments)]
+    async fn retrieve_all_executions(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Whether or not to include the execution's detailed data (the input and output of every node). This is very large, so leave it off unless it's needed - without it, a summary of each execution is returned."
+        )]
+        include_data: bool,
+        #[tool(param)]
+        #[schemars(
+            description = "The status of an execution. Can either be: 'error' | 'success' | 'waiting' | 'running'"
+        )]
+        status: ExecutionStatus,
+        #[tool(param)]
+        #[schemars(description = "Workflow ID to filter executions by. Optional.")]
+        workflow_id: Option<WorkflowId>,
+        #[tool(param)]
+        #[schemars(description = "Project ID to filter executions by. Optional.")]
+        project_id: Option<ProjectId>,
+        #[tool(param)]
+        #[schemars(
+            description = "The maximum number of items to return. The absolute maximum is 250 - if you go above this, you will receive an error."
+        )]
+        limit: u8,
+        #[tool(param)]
+        #[schemars(
+            description = "The nextCursor from the previous page, to get the page after it. Leave it blank to get the first page."
+        )]
+        cursor: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Whether to follow the cursor through every page and return all the results at once, instead of one page. Stops after 20 pages, in which case nextCursor is set so you can carry on from there. Optional."
+        )]
+        fetch_all: Option<bool>,
+    ) -> Result<Content, ServerError> {
+        validate::optional_id("workflow", workflow_id.as_deref())?;
+        validate::optional_id("project", project_id.as_deref())?;
+        validate::limit(limit)?;
+        if !cursor.is_empty() {
+            validate::cursor(&cursor)?;
+        }
+
+        let query = Query::new()
+            .set("includeData", include_data)
+            .set("status", status)
+            .set_optional("workflowId", workflow_id)
+            .set_optional("projectId", project_id)
+            .set("limit", limit);
+        let cursor = Some(cursor).filter(|cursor| !cursor.is_empty());
+
+        if fetch_all.unwrap_or_default() {
+            let res = self
+                .fetch_all_pages(Endpoint::Executions, query, cursor)
+                .await?;
+
+            return output::structured(&execution::summarize_page(res, include_data)?);
+        }
+
+        let res = self
+            .fetch_page(Endpoint::Executions, &query.set_optional("cursor", cursor))
+            .await?;
+
+        output::structured(&execution::summarize_page(res, include_data)?)
+    }
+
+    #[tool(
+        description = "Retrieve executions that started within a date range, newest first.
+
+            Useful for questions like 'what failed yesterday?' without having to page through every execution. Use failed_node and error_contains to narrow failures down, e.g. to every execution that failed in the Postgres node; with either, up to the 1000 most recent executions in the range are looked at."
+    )]
+    async fn retrieve_executions_in_range(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Only include executions that started at or after this time. Either an RFC 3339 timestamp or a date (YYYY-MM-DD). Optional."
+        )]
+        started_after: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Only include executions that started before this time. Either an RFC 3339 timestamp or a date (YYYY-MM-DD). Optional."
+        )]
+        started_before: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "The status of an execution. Can either be: 'error' | 'success' | 'waiting' | 'running'. Optional."
+        )]
+        status: Option<ExecutionStatus>,
+        #[tool(param)]
+        #[schemars(description = "Workflow ID to filter executions by. Optional.")]
+        workflow_id: Option<WorkflowId>,
+        #[tool(param)]
+        #[schemars(
+            description = "Only include executions that failed in the node with this name, e.g. 'Postgres'. Optional."
+        )]
+        failed_node: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Only include executions whose error message contains this text (ignoring case), e.g. 'timeout'. Optional."
+        )]
+        error_contains: Option<String>,
+    ) -> Result<Content, ServerError> {
+        validate::optional_id("workflow", workflow_id.as_deref())?;
+        let (started_after, started_before) =
+            parse_range(started_after.as_deref(), started_before.as_deref())?;
+        let failed_node = failed_node.map(|node| node.trim().to_lowercase());
+        let error_contains = error_contains.map(|text| text.to_lowercase());
+        // Why an execution failed is only in its data
+        let filter_errors = failed_node.is_some() || error_contains.is_some();
+
+        let (executions, truncated) = self
+            .executions_in_range(ExecutionFilter {
+                started_after,
+                started_before,
+                status: status.as_ref(),
+                workflow_id: workflow_id.as_ref(),
+                include_data: filter_errors,
+                ..Default::default()
+            })
+            .await?;
+        let executions: Vec<_> = executions
+            .iter()
+            .map(Execution::summary)
+            .filter(|execution| {
+                if !filter_errors {
+                    return true;
+                }
+                let Some(error) = &execution.error else {
+                    return false;
+                };
+                let node_matches = failed_node.as_ref().is_none_or(|failed_node| {
+                    error
+                        .node
+                        .as_ref()
+                        .is_some_and(|node| node.to_lowercase() == *failed_node)
+                });
+                let message_matches = error_contains
+                    .as_ref()
+                    .is_none_or(|text| error.message.to_lowercase().contains(text));
+
+                node_matches && message_matches
+            })
+            .collect();
+
+        let json_object = json!({
+            "data": executions,
+            "truncated": truncated,
+        });
+
+        output::structured(&json_object)
+    }
+
+    #[tool(
+        description = "Search the data of a workflow's recent executions for a value, e.g. an order ID or an email address, to answer questions like 'did we process this record?'.
+
+            Returns the executions it was found in, newest first, and where in each: the node, the run, output and item, and the path of the field (e.g. 'customer.email'), with an excerpt of the matching value. Only executions whose data was saved can be searched."
+    )]
+    #[allow(clippy::too_many_arguments)]
+    async fn search_executions(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "What to search for, e.g. 'ORD-1234' or 'jane@example.com'. Matched literally unless regex is true."
+        )]
+        pattern: String,
+        #[tool(param)]
+        #[schemars(description = "The ID of the workflow whose executions to search.")]
+        workflow_id: WorkflowId,
+        #[tool(param)]
+        #[schemars(
+            description = "Whether the pattern is a regular expression (Rust regex syntax). Defaults to false."
+        )]
+        regex: Option<bool>,
+        #[tool(param)]
+        #[schemars(description = "Whether to match case. Defaults to false.")]
+        case_sensitive: Option<bool>,
+        #[tool(param)]
+        #[schemars(
+            description = "Only search executions that started at or after this time. Either an RFC 3339 timestamp or a date (YYYY-MM-DD). Optional."
+        )]
+        started_after: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Only search executions that started before this time. Either an RFC 3339 timestamp or a date (YYYY-MM-DD). Optional."
+        )]
+        started_before: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "How many of the most recent executions to search, up to 1000. Defaults to 100."
+        )]
+        max_executions: Option<usize>,
+    ) -> Result<Content, ServerError> {
+        validate::not_empty("pattern", &pattern)?;
+        validate::id("workflow", &workflow_id)?;
+        let max_executions = max_executions.unwrap_or(100);
+        if !(1..=MAX_SEARCHED_EXECUTIONS).contains(&max_executions) {
+            return Err(ServerError::validation(format!(
+                "max_executions must be between 1 and {MAX_SEARCHED_EXECUTIONS}"
+            )));
+        }
+        let (started_after, started_before) =
+            parse_range(started_after.as_deref(), started_before.as_deref())?;
+        let matcher = search::matcher(
+            &pattern,
+            regex.unwrap_or(false),
+            case_sensitive.unwrap_or(false),
+        )?;
+
+        let (executions, truncated) = self
+            .executions_in_range(ExecutionFilter {
+                started_after,
+                started_before,
+                workflow_id: Some(&workflow_id),
+                include_data: true,
+                limit: Some(max_executions),
+                ..Default::default()
+            })
+            .await?;
+
+        let mut hit_count = 0;
+        let mut results = Vec::new();
+        for execution in &executions {
+            let mut hits = search::search_execution(execution, &matcher);
+            if hits.is_empty() {
+                continue;
+            }
+
+            hits.truncate(search::MAX_HITS - hit_count);
+            hit_count += hits.len();
+            results.push(json!({
+                "execution": execution.summary(),
+                "hits": hits,
+            }));
+
+            if hit_count == search::MAX_HITS {
+                break;
+            }
+        }
+
+        let mut json_object = json!({
+            "searched": executions.len(),
+            "matchedExecutions": results.len(),
+            "hits": hit_count,
+            "results": results,
+        });
+        if hit_count == search::MAX_HITS {
+            json_object["warning"] = json!(format!(
+                "Only the first {} hits are listed - narrow the pattern or date range to see the rest",
+                search::MAX_HITS
+            ));
+        } else if truncated {
+            json_object["warning"] = json!(format!(
+                "Only the {} most recent executions were searched - raise max_executions or narrow the date range to search older ones",
+                executions.len()
+            ));
+        }
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "A health check of every active workflow: how its most recent execution went and when, in one compact table - the morning 'is everything still running?' check.
+
+            Workflows whose last execution failed come first (with the failing node and error), then those that have never run, then the rest. Use tags to only check some workflows."
+    )]
+    async fn latest_executions_overview(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Only check active workflows with these tags, as comma-separated tag names, e.g. 'prod,billing'. Optional."
+        )]
+        tags: Option<String>,
+    ) -> Result<Content, ServerError> {
+        let query = Query::new()
+            .set("active", true)
+            .set_optional("tags", tags.as_deref())
+            .set("limit", MAX_PAGE_SIZE);
+        let workflows: Paginated<Workflow> = self
+            .fetch_all_pages(Endpoint::Workflows, query, None)
+            .await?;
+
+        // Busy workflows' latest executions are all in the first page, so only the rest are looked up one by one
+        let query = Query::new().set("limit", MAX_PAGE_SIZE);
+        let recent: Paginated<Execution> = self.fetch_page(Endpoint::Executions, &query).await?;
+        let mut latest: HashMap<WorkflowId, Execution> = HashMap::new();
+        for execution in recent.data {
+            if let Some(workflow_id) = execution.workflow_id.clone() {
+                latest.entry(workflow_id).or_insert(execution);
+            }
+        }
+
+        let total = workflows.data.len() as u32;
+        let mut rows = Vec::new();
+        for (i, workflow) in workflows.data.iter().enumerate() {
+            let Some(workflow_id) = &workflow.id else {
+                continue;
+            };
+
+            let mut execution = match latest.remove(workflow_id) {
+                Some(execution) => Some(execution),
+                None => {
+                    let query = Query::new().set("workflowId", workflow_id).set("limit", 1);
+                    let res: Paginated<Execution> =
+                        self.fetch_page(Endpoint::Executions, &query).await?;
+                    res.data.into_iter().next()
+                }
+            };
+            // Why it failed is only in the execution's data. The ID comes from n8n, but is checked anyway, since it
+            // ends up in the URL
+            if let Some(failed) = &execution
+                && matches!(failed.status.as_deref(), Some("error" | "crashed"))
+                && validate::id("execution", &failed.id).is_ok()
+            {
+                execution = Some(self.fetch_execution(&failed.id).await?);
+            }
+
+            let summary = execution.as_ref().map(Execution::summary);
+            rows.push(json!({
+                "workflowId": workflow_id,
+                "workflowName": workflow.name,
+                "status": summary
+                    .as_ref()
+                    .map_or("never run", |summary| summary.status.as_deref().unwrap_or("unknown")),
+                "executionId": summary.as_ref().map(|summary| &summary.id),
+                "startedAt": summary.as_ref().and_then(|summary| summary.started_at.as_ref()),
+                "error": summary.and_then(|summary| summary.error),
+            }));
+            progress::report(i as u32 + 1, Some(total)).await;
+        }
+
+        let rank = |row: &serde_json::Value| match row["status"].as_str() {
+            Some("error" | "crashed") => 0,
+            Some("never run") => 1,
+            _ => 2,
+        };
+        rows.sort_by_key(rank);
+
+        let count = |status: &[&str]| {
+            rows.iter()
+                .filter(|row| {
+                    row["status"]
+                        .as_str()
+                        .is_some_and(|value| status.contains(&value))
+                })
+                .count()
+        };
+        let mut json_object = json!({
+            "workflows": rows.len(),
+            "failing": count(&["error", "crashed"]),
+            "neverRun": count(&["never run"]),
+            "healthy": count(&["success"]),
+            "latest": rows,
+        });
+        if workflows.next_cursor.is_some() {
+            json_object["warning"] = json!(format!(
+                "Only the first {MAX_FETCH_ALL_PAGES} pages of workflows were checked - use tags to check the rest"
+            ));
+        }
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Count each workflow's executions in a time window by status - success, error, waiting, running and canceled - with its failure rate and when it last failed, to answer questions like 'which workflows fail the most?' or 'how reliable is this workflow?'.
+
+            Workflows are returned most failures first, along with the totals over every workflow. Covers the last 7 days unless told otherwise."
+    )]
+    async fn execution_metrics(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Only count executions that started at or after this time. Either an RFC 3339 timestamp or a date (YYYY-MM-DD). Defaults to 7 days ago, unless started_before is set."
+        )]
+        started_after: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Only count executions that started before this time. Either an RFC 3339 timestamp or a date (YYYY-MM-DD). Optional."
+        )]
+        started_before: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Only count the executions of this workflow. Optional.")]
+        workflow_id: Option<WorkflowId>,
+    ) -> Result<Content, ServerError> {
+        validate::optional_id("workflow", workflow_id.as_deref())?;
+        let (started_after, started_before) =
+            match parse_range(started_after.as_deref(), started_before.as_deref())? {
+                (None, None) => (Some(Utc::now() - TimeDelta::days(7)), None),
+                range => range,
+            };
+
+        let (executions, truncated) = self
+            .executions_in_range(ExecutionFilter {
+                started_after,
+                started_before,
+                workflow_id: workflow_id.as_ref(),
+                ..Default::default()
+            })
+            .await?;
+
+        let mut overall = execution::Metrics::default();
+        let mut by_workflow: HashMap<Option<WorkflowId>, execution::Metrics> = HashMap::new();
+        for execution in &executions {
+            overall.add(execution);
+            by_workflow
+                .entry(execution.workflow_id.clone())
+                .or_default()
+                .add(execution);
+        }
+
+        let names = self.workflow_names().await?;
+        let mut by_workflow: Vec<_> = by_workflow.into_iter().collect();
+        by_workflow.sort_by_key(|(_, metrics)| Reverse((metrics.error, metrics.total)));
+        let workflows: Vec<serde_json::Value> = by_workflow
+            .into_iter()
+            .map(|(workflow_id, metrics)| {
+                let mut workflow = json!(metrics);
+                workflow["workflowName"] = json!(
+                    workflow_id
+                        .as_ref()
+                        .and_then(|workflow_id| names.get(workflow_id))
+                );
+                workflow["workflowId"] = json!(workflow_id);
+                workflow
+            })
+            .collect();
+
+        let mut json_object = json!({
+            "startedAfter": started_after,
+            "startedBefore": started_before,
+            "overall": overall,
+            "workflows": workflows,
+        });
+        if truncated {
+            json_object["warning"] = json!(format!(
+                "Only the most recent {} executions were counted. Narrow the window to count all of them",
+                executions.len()
+            ));
+        }
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Work out how long each workflow's executions take over a time window - the minimum, median, 95th percentile and maximum, from when they started and stopped - and flag workflows whose runs are getting close to their execution timeout.
+
+            Workflows are returned slowest first (by 95th percentile). Only finished executions are counted. Covers the last 7 days unless told otherwise."
+    )]
+    async fn execution_durations(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Only count executions that started at or after this time. Either an RFC 3339 timestamp or a date (YYYY-MM-DD). Defaults to 7 days ago, unless started_before is set."
+        )]
+        started_after: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Only count executions that started before this time. Either an RFC 3339 timestamp or a date (YYYY-MM-DD). Optional."
+        )]
+        started_before: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Only count the executions of this workflow. Optional.")]
+        workflow_id: Option<WorkflowId>,
+    ) -> Result<Content, ServerError> {
+        validate::optional_id("workflow", workflow_id.as_deref())?;
+        let (started_after, started_before) =
+            match parse_range(started_after.as_deref(), started_before.as_deref())? {
+                (None, None) => (Some(Utc::now() - TimeDelta::days(7)), None),
+                range => range,
+            };
+
+        let (executions, truncated) = self
+            .executions_in_range(ExecutionFilter {
+                started_after,
+                started_before,
+                workflow_id: workflow_id.as_ref(),
+                ..Default::default()
+            })
+            .await?;
+
+        let mut by_workflow: HashMap<WorkflowId, Vec<i64>> = HashMap::new();
+        for execution in &executions {
+            if let Some(workflow_id) = &execution.workflow_id
+                && execution.is_finished()
+                && let Some(duration) = execution.duration_ms()
+            {
+                by_workflow
+                    .entry(workflow_id.clone())
+                    .or_default()
+                    .push(duration);
+            }
+        }
+
+        let query = Query::new().set("limit", MAX_PAGE_SIZE);
+        let workflows: Paginated<Workflow> = self
+            .fetch_all_pages(Endpoint::Workflows, query, None)
+            .await?;
+        let workflows: HashMap<WorkflowId, Workflow> = workflows
+            .data
+            .into_iter()
+            .filter_map(|workflow| Some((workflow.id.clone()?, workflow)))
+            .collect();
+
+        let mut durations: Vec<(WorkflowId, execution::Durations)> = by_workflow
+            .into_iter()
+            .filter_map(|(workflow_id, durations)| {
+                Some((workflow_id, execution::Durations::of(durations)?))
+            })
+            .collect();
+        durations.sort_by_key(|(_, durations)| Reverse(durations.p95_ms));
+
+        let mut approaching_timeout = Vec::new();
+        let durations: Vec<serde_json::Value> = durations
+            .into_iter()
+            .map(|(workflow_id, durations)| {
+                let workflow = workflows.get(&workflow_id);
+                let timeout = workflow.and_then(Workflow::execution_timeout);
+                let mut json_object = json!(durations);
+                json_object["workflowId"] = json!(workflow_id);
+                json_object["workflowName"] = json!(workflow.map(|workflow| &workflow.name));
+                json_object["timeoutSeconds"] = json!(timeout);
+
+                if let Some(timeout) = timeout {
+                    let share = durations.max_ms as f64 / (timeout * 1000) as f64;
+                    json_object["maxShareOfTimeout"] = json!((share * 100.0).round() / 100.0);
+                    if share >= TIMEOUT_WARNING_SHARE {
+                        approaching_timeout.push(workflow_id.clone());
+                    }
+                }
+                json_object
+            })
+            .collect();
+
+        let mut json_object = json!({
+            "startedAfter": started_after,
+            "startedBefore": started_before,
+            "approachingTimeout": approaching_timeout,
+            "workflows": durations,
+        });
+        if truncated {
+            json_object["warning"] = json!(format!(
+                "Only the most recent {} executions were counted. Narrow the window to count all of them",
+                executions.len()
+            ));
+        }
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "List the executions that are currently running, grouped by workflow.
+
+            Use this to answer questions like 'what is executing right now?'. Only executions with the 'running' status are listed - n8n's API can't list executions that are queued but haven't started yet."
+    )]
+    async fn list_running_executions(&self) -> Result<Content, ServerError> {
+        let query = Query::new()
+            .set("status", ExecutionStatus::Running)
+            .set("limit", MAX_PAGE_SIZE);
+        let running: Paginated<serde_json::Value> = self
+            .fetch_all_pages(Endpoint::Executions, query, None)
+            .await?;
+
+        let mut running_by_workflow: BTreeMap<Option<WorkflowId>, Vec<serde_json::Value>> =
+            BTreeMap::new();
+        for execution in &running.data {
+            let workflow_id = match &execution["workflowId"] {
+                serde_json::Value::String(id) => Some(WorkflowId::from(id.clone())),
+                serde_json::Value::Number(id) => Some(WorkflowId::from(id.to_string())),
+                _ => None,
+            };
+
+            running_by_workflow
+                .entry(workflow_id)
+                .or_default()
+                .push(json!({
+                    "id": execution["id"],
+                    "status": execution["status"],
+                    "mode": execution["mode"],
+                    "startedAt": execution["startedAt"],
+                }));
+        }
+
+        let names = if running_by_workflow.is_empty() {
+            HashMap::new()
+        } else {
+            self.workflow_names().await?
+        };
+        let workflows: Vec<_> = running_by_workflow
+            .into_iter()
+            .map(|(workflow_id, executions)| {
+                json!({
+                    "workflowName": workflow_id.as_ref().and_then(|id| names.get(id)),
+                    "workflowId": workflow_id.map_or(json!("unknown"), |id| json!(id)),
+                    "runningCount": executions.len(),
+                    "executions": executions,
+                })
+            })
+            .collect();
+
+        let mut json_object = json!({
+            "totalRunning": workflows
+                .iter()
+                .map(|workflow| workflow["runningCount"].as_u64().unwrap_or_default())
+                .sum::<u64>(),
+            "workflows": workflows,
+        });
+        if running.next_cursor.is_some() {
+            json_object["warning"] = json!(format!(
+                "Only the first {MAX_FETCH_ALL_PAGES} pages of running executions were listed, so more are running than totalRunning"
+            ));
+        }
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(description = "Retrieve an execution by ID.
+
+            An execution's full data is usually far too big to read, so use summarize_data to see what each node did - how many items it output, their fields and the first few of them - and then node to get everything about a single node that needs a closer look.")]
+    async fn retrieve_execution_by_id(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "The execution ID to use.")]
+        execution_id: ExecutionId,
+        #[tool(param)]
+        #[schemars(
+            description = "Whether to include the execution's detailed data (the input and output of every node). This is very large, so leave it off unless it's needed - without it, a summary of the execution is returned, including why it failed if it did. Optional."
+        )]
+        include_data: Option<bool>,
+        #[tool(param)]
+        #[schemars(
+            description = "Set to true to add a digest of each node that ran: how many times it ran, how many items it output, their size and fields, and the first few items. Defaults to false."
+        )]
+        summarize_data: Option<bool>,
+        #[tool(param)]
+        #[schemars(
+            description = "How many items of each node to include with summarize_data. Set to 0 for only the field names. Defaults to 3."
+        )]
+        max_items: Option<usize>,
+        #[tool(param)]
+        #[schemars(
+            description = "The name of a node to return all the data of - every run, with the items it was given and output - instead of the whole execution's. Optional."
+        )]
+        node: Option<String>,
+    ) -> Result<Content, ServerError> {
+        validate::id("execution", &execution_id)?;
+        if include_data.unwrap_or_default()
+            && (summarize_data.unwrap_or_default() || node.is_some())
+        {
+            return Err(ServerError::validation(
+                "include_data returns all of the execution's data, so it can't be used with summarize_data or node",
+            ));
+        }
+
+        // The data is always fetched, as it's where the reason for a failure is
+        let execution = self.fetch_execution(&execution_id).await?;
+
+        if include_data.unwrap_or_default() {
+            return output::structured(&execution);
+        }
+
+        let mut json_object = json!(execution.summary());
+        if let Some(node) = &node {
+            let Some(runs) = execution.node_runs(node) else {
+                let mut nodes_run = execution.nodes_run();
+                nodes_run.sort_unstable();
+                return Err(ServerError::validation(format!(
+                    "'{node}' didn't run in execution {execution_id}. The nodes that ran are: {}",
+                    nodes_run.join(", ")
+                )));
+            };
+            json_object["node"] = json!(node);
+            json_object["runs"] = runs.clone();
+        }
+        if summarize_data.unwrap_or_default() {
+            json_object["nodes"] = json!(execution.node_digests(max_items.unwrap_or(3)));
+        }
+
+        output::structured(&json_object)
+    }
+
+    #[tool(
+        description = "Work out why an execution failed: which node failed, its error message and the top of the stack trace, and the input items it failed on, with a hint of where to look.
+
+            Much quicker than digging through the execution's data with retrieve_execution_by_id."
+    )]
+    async fn analyze_execution_failure(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "The ID of the failed execution.")]
+        execution_id: ExecutionId,
+        #[tool(param)]
+        #[schemars(
+            description = "How many of the failing node's input items to include when it isn't known which one it failed on. Defaults to 3."
+        )]
+        max_items: Option<usize>,
+    ) -> Result<Content, ServerError> {
+        validate::id("execution", &execution_id)?;
+
+        let execution = self.fetch_execution(&execution_id).await?;
+        let summary = execution.summary();
+
+        let Some(diagnosis) = execution.diagnose(max_items.unwrap_or(3)) else {
+            return Err(ServerError::Detailed(json!({
+                "error": format!("No error was recorded in execution {execution_id}, so it didn't fail in a node"),
+                "execution": summary,
+            })));
+        };
+
+        let json_object = json!({
+            "execution": summary,
+            "diagnosis": diagnosis,
+        });
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Compare two executions of a workflow node by node - whether each node succeeded, failed or didn't run, how many items it output, which fields appeared or disappeared and which values in its first item changed - to find out why one run behaved differently from another.
+
+            Only nodes that did something differently are returned, unless include_unchanged is set."
+    )]
+    async fn compare_executions(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "The ID of the first execution, usually the earlier one.")]
+        first_execution_id: ExecutionId,
+        #[tool(param)]
+        #[schemars(description = "The ID of the second execution, usually the later one.")]
+        second_execution_id: ExecutionId,
+        #[tool(param)]
+        #[schemars(
+            description = "Set to true to include nodes that did the same in both executions. Defaults to false."
+        )]
+        include_unchanged: Option<bool>,
+    ) -> Result<Content, ServerError> {
+        validate::id("execution", &first_execution_id)?;
+        validate::id("execution", &second_execution_id)?;
+
+        let first = self.fetch_execution(&first_execution_id).await?;
+        let second = self.fetch_execution(&second_execution_id).await?;
+
+        let (changed, unchanged): (Vec<_>, Vec<_>) = execution::compare(&first, &second)
+            .into_iter()
+            .partition(|node| node.differs());
+
+        let mut json_object = json!({
+            "first": first.summary(),
+            "second": second.summary(),
+            "durationMs": [first.duration_ms(), second.duration_ms()],
+            "changedNodes": changed.len(),
+            "nodes": changed,
+        });
+        if include_unchanged.unwrap_or_default() {
+            json_object["unchanged"] = json!(unchanged);
+        } else {
+            json_object["unchangedNodes"] = json!(
+                unchanged
+                    .iter()
+                    .map(|node| node.node.as_str())
+                    .collect::<Vec<_>>()
+            );
+        }
+        if first.workflow_id != second.workflow_id {
+            json_object["warning"] = json!(
+                "The executions are of different workflows, so differences may just be down to the workflows being different"
+            );
+        } else if first.data.is_none() || second.data.is_none() {
+            json_object["warning"] = json!(
+                "The data of one of the executions wasn't saved, so its nodes can't be compared"
+            );
+        }
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(description = "Deletes an execution by ID.")]
+    async fn delete_execution_by_id(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "The execution ID to use.")]
+        execution_id: ExecutionId,
+    ) -> Result<Content, ServerError> {
+        validate::id("execution", &execution_id)?;
+
+        let res = self.delete_execution(&execution_id).await?;
+        let json_as_string = serde_json::to_string_pretty(&res).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Deletes old executions: those that started more than older_than_days ago, and/or all but the keep_latest most recent of each workflow. An execution is deleted if it matches either. Executions that haven't finished are never deleted.
+
+            Without confirm, nothing is deleted: how many executions would be, per workflow, is returned instead, so it can be checked first. Calling it again with the same arguments and confirm set to true deletes them, several at a time. Up to 5000 executions are looked at per call (the most recent ones, or with only older_than_days, the most recent of those old enough), so very large instances may need more than one."
+    )]
+    async fn prune_executions(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Delete executions that started more than this many days ago. Optional, but at least one of older_than_days and keep_latest has to be set."
+        )]
+        older_than_days: Option<u32>,
+        #[tool(param)]
+        #[schemars(
+            description = "Keep this many of each workflow's most recent executions, deleting the rest. Optional, but at least one of older_than_days and keep_latest has to be set."
+        )]
+        keep_latest: Option<usize>,
+        #[tool(param)]
+        #[schemars(description = "Only delete the executions of this workflow. Optional.")]
+        workflow_id: Option<WorkflowId>,
+        #[tool(param)]
+        #[schemars(
+            description = "Only delete executions with this status: 'error' | 'success'. Optional."
+        )]
+        status: Option<ExecutionStatus>,
+        #[tool(param)]
+        #[schemars(
+            description = "Set to true to actually delete the executions, after checking the counts returned without it. Defaults to false."
+        )]
+        confirm: Option<bool>,
+    ) -> Result<Content, ServerError> {
+        validate::optional_id("workflow", workflow_id.as_deref())?;
+        if older_than_days.is_none() && keep_latest.is_none() {
+            return Err(ServerError::validation(
+                "Set older_than_days, keep_latest or both to choose which executions to delete",
+            ));
+        }
+
+        let cutoff = older_than_days.map(|days| Utc::now() - TimeDelta::days(days.into()));
+        // Retention is counted over every execution, so the status filter is applied after fetching them. Without it,
+        // only executions before the cutoff are fetched, so that they're reached however many newer ones there are
+        let (executions, truncated) = self
+            .executions_in_range(ExecutionFilter {
+                started_before: cutoff.filter(|_| keep_latest.is_none()),
+                workflow_id: workflow_id.as_ref(),
+                ..Default::default()
+            })
+            .await?;
+
+        let mut seen: HashMap<Option<&WorkflowId>, usize> = HashMap::new();
+        let mut to_delete = Vec::new();
+        for execution in &executions {
+            let position = seen.entry(execution.workflow_id.as_ref()).or_default();
+            *position += 1;
+
+            let too_old = cutoff.is_some_and(|cutoff| {
+                execution
+                    .started_at
+                    .as_deref()
+                    .and_then(parse_timestamp)
+                    .is_some_and(|started_at| started_at < cutoff)
+            });
+            let beyond_retention = keep_latest.is_some_and(|keep| *position > keep);
+            let status_matches = status.as_ref().is_none_or(|status| {
+                execution.status.as_deref() == Some(status.to_string().as_str())
+            });
+
+            if (too_old || beyond_retention) && status_matches && execution.is_finished() {
+                to_delete.push(execution);
+            }
+        }
+
+        let mut by_workflow: BTreeMap<String, usize> = BTreeMap::new();
+        for execution in &to_delete {
+            let workflow_id = execution.workflow_id.as_deref().unwrap_or("unknown");
+            *by_workflow.entry(workflow_id.to_string()).or_default() += 1;
+        }
+
+        if !confirm.unwrap_or(false) {
+            let mut json_object = json!({
+                "dryRun": true,
+                "matched": to_delete.len(),
+                "byWorkflow": by_workflow,
+                "message": format!(
+                    "Nothing was deleted. Call prune_executions again with the same arguments and confirm set to true to delete these {} executions.",
+                    to_delete.len()
+                ),
+            });
+            if truncated {
+                json_object["warning"] = json!(format!(
+                    "Only {} executions were looked at, so there are more to delete by running this again once these are deleted",
+                    executions.len()
+                ));
+            }
+
+            let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+            return Ok(Content::text(json_as_string));
+        }
+
+        let total = to_delete.len() as u32;
+        let mut done = 0;
+        let mut failures = Vec::new();
+        let ids: Vec<ExecutionId> = to_delete
+            .iter()
+            .map(|execution| execution.id.clone())
+            .collect();
+        let mut deletions = stream::iter(ids)
+            .map(|execution_id| async move {
+                // The IDs come from n8n, but are checked anyway, since they end up in the URL
+                let result = match validate::id("execution", &execution_id) {
+                    Ok(()) => self.delete_execution(&execution_id).await,
+                    Err(err) => Err(err),
+                };
+                (execution_id, result)
+            })
+            .buffer_unordered(MAX_CONCURRENT_DELETES);
+        while let Some((execution_id, result)) = deletions.next().await {
+            if let Err(err) = result {
+                failures.push(json!({
+                    "id": execution_id,
+                    "error": err.to_string(),
+                }));
+            }
+            done += 1;
+            progress::report(done, Some(total)).await;
+        }
+
+        let mut json_object = json!({
+            "dryRun": false,
+            "matched": to_delete.len(),
+            "deleted": to_delete.len() - failures.len(),
+            "failed": failures.len(),
+            "byWorkflow": by_workflow,
+            "failures": failures,
+        });
+        if truncated {
+            json_object["warning"] = json!(
+                "There were more executions than could be looked at in one call. Run prune_executions again to carry on"
+            );
+        }
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(description = "Create a new workflow.
+
+            Along with the created workflow, returns warnings about any connections or credentials that will stop parts of it from running (e.g. nodes that can't be reached from a trigger, or credentials that don't exist), which should be fixed by updating the workflow. Also describes the workflow's triggers, and whether and how it can be activated and run.
+            With dry_run, nothing is created (or updated): the request that would be sent is returned instead, along with any ways its body doesn't match n8n's workflow format, and the same warnings.")]
+    async fn create_workflow(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "The name of your workflow.")]
+        name: String,
+        #[tool(param)]
+        #[schemars(description = "The nodes you want to use in your workflow.")]
+        nodes: Vec<Node>,
+        #[tool(param)]
+        #[schemars(description = "The connections you want for your workflow.")]
+        connections: Connections,
+        #[tool(param)]
+        #[schemars(
+            description = "Settings for the workflow (timeouts, execution saving, error workflow, timezone and execution order). Anything left out gets the default. Optional."
+        )]
+        settings: Option<WorkflowSettingsUpdate>,
+        #[tool(param)]
+        #[schemars(
+            description = "What to do if there's already a workflow with this name: 'allow' creates another one (the default), 'fail' returns an error instead, 'suffix' adds a number to the new workflow's name, e.g. 'Orders (2)', and 'upsert' updates the existing workflow's nodes, connections and settings instead. Optional."
+        )]
+        on_name_conflict: Option<OnNameConflict>,
+        #[tool(param)]
+        #[schemars(
+            description = "Set to true to only check the workflow and return the request that would be sent to n8n, without creating (or updating) anything. Defaults to false."
+        )]
+        dry_run: Option<bool>,
+    ) -> Result<Content, ServerError> {
+        validate::not_empty("The workflow name", &name)?;
+        validate::nodes(&nodes)?;
+        if let Some(settings) = &settings {
+            settings.validate()?;
+        }
+
+        let on_name_conflict = on_name_conflict.unwrap_or_default();
+        let existing = match on_name_conflict {
+            OnNameConflict::Allow => Vec::new(),
+            _ => self.find_workflows_named(&name).await?,
+        };
+
+        let mut name = name;
+        let mut name_conflict = None;
+        let mut upsert_into = None;
+        match (on_name_conflict, existing.as_slice()) {
+            (_, []) | (OnNameConflict::Allow, _) => {}
+            (OnNameConflict::Fail, existing) => {
+                let ids: Vec<&str> = existing
+                    .iter()
+                    .filter_map(|workflow| workflow.id.as_deref())
+                    .collect();
+                return Err(ServerError::validation(format!(
+                    "There's already a workflow called '{name}' (ID {}). Use a different name, or set on_name_conflict to 'suffix' or 'upsert'",
+                    ids.join(", ")
+                )));
+            }
+            (OnNameConflict::Suffix, _) => {
+                let original = name.trim().to_string();
+                let taken: HashSet<String> = self
+                    .workflow_names()
+                    .await?
+                    .into_values()
+                    .map(|name| name.trim().to_string())
+                    .collect();
+                name = (2..=MAX_NAME_SUFFIX)
+                    .map(|n| format!("{original} ({n})"))
+                    .find(|name| !taken.contains(name))
+                    .ok_or_else(|| {
+                        ServerError::validation(format!(
+                            "There are already workflows called '{original}' up to '{original} ({MAX_NAME_SUFFIX})'. Use a different name"
+                        ))
+                    })?;
+                name_conflict = Some(format!(
+                    "There's already a workflow called '{original}', so this one was called '{name}'"
+                ));
+            }
+            (OnNameConflict::Upsert, [workflow]) => upsert_into = workflow.id.clone(),
+            (OnNameConflict::Upsert, existing) => {
+                return Err(ServerError::validation(format!(
+                    "There are {} workflows called '{name}', so it isn't clear which to update. Update one by ID with update_workflow_by_id instead",
+                    existing.len()
+                )));
+            }
+        }
+
+        let mut warnings = workflow::connection_warnings(&nodes, &connections);
+        warnings.extend(self.credential_warnings(&nodes).await);
+        let triggers = workflow::trigger_summary(&nodes);
+
+        let (upsert, body) = match upsert_into {
+            Some(workflow_id) => {
+                let mut workflow = self.fetch_workflow(&workflow_id).await?;
+                let updated = if dry_run.unwrap_or(false) {
+                    "would be updated"
+                } else {
+                    "was updated"
+                };
+                name_conflict = Some(format!(
+                    "There was already a workflow called '{name}', so it {updated} instead of creating a new one"
+                ));
+
+                workflow.nodes = nodes;
+                workflow.connections = connections;
+                if let Some(settings) = settings {
+                    settings.apply_to(&mut workflow.settings);
+                }
+
+                let body = workflow.update_body();
+                (Some((workflow_id, workflow)), body)
+            }
+            None => {
+                let mut workflow_settings = serde_json::Map::new();
+                WorkflowSettings::default().apply_to(&mut workflow_settings);
+                if let Some(settings) = settings {
+                    settings.apply_to(&mut workflow_settings);
+                }
+
+                let body = json!({
+                    "name": name,
+                    "nodes": nodes,
+                    "connections": connections,
+                    "settings": workflow_settings,
+                    "staticData": null
+                });
+                (None, body)
+            }
+        };
+
+        let violations = match &upsert {
+            Some((workflow_id, workflow)) => {
+                self.policy_violations(Some(workflow_id), workflow).await?
+            }
+            None => {
+                self.policy_violations(None, &serde_json::from_value(body.clone())?)
+                    .await?
+            }
+        };
+        if !dry_run.unwrap_or(false) {
+            warnings.extend(policy_warnings(&name, &violations)?);
+        }
+
+        let res = if dry_run.unwrap_or(false) {
+            None
+        } else if let Some((workflow_id, workflow)) = &upsert {
+            Some(self.put_workflow(workflow_id, workflow).await?)
+        } else {
+            Some(self.post_workflow(&body).await?)
+        };
+
+        let mut json_object = match res {
+            Some(res) => json!({
+                "workflow": res,
+                "warnings": warnings,
+                "triggers": triggers,
+            }),
+            None => {
+                let request = match &upsert {
+                    Some((workflow_id, _)) => {
+                        dry_run_request("PUT", self.url(Endpoint::Workflow(workflow_id)), body)
+                    }
+                    None => dry_run_request("POST", self.url(Endpoint::Workflows), body),
+                };
+                json!({
+                    "dryRun": request,
+                    "warnings": warnings,
+                    "triggers": triggers,
+                    "policyViolations": violations,
+                })
+            }
+        };
+        if let Some(name_conflict) = name_conflict {
+            json_object["nameConflict"] = json!(name_conflict);
+        }
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Check a workflow document against the JSON Schema of n8n's workflow format, reporting every violation. Nothing is sent to n8n.
+
+            Use this before create_workflow or update_workflow_by_id, e.g. on a workflow written from scratch or exported from the n8n editor. Once the document matches the schema, its connections are checked too, with the same warnings create_workflow returns."
+    )]
+    async fn validate_workflow_json(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "The workflow document, with at least name, nodes and connections."
+        )]
+        workflow: serde_json::Value,
+    ) -> Result<Content, ServerError> {
+        let violations = workflow::schema_violations(&workflow);
+
+        // Connections can only be followed once the document has the right shape
+        let warnings = match serde_json::from_value::<Workflow>(workflow) {
+            Ok(workflow) if violations.is_empty() => {
+                workflow::connection_warnings(&workflow.nodes, &workflow.connections)
+            }
+            _ => Vec::new(),
+        };
+
+        let json_object = json!({
+            "valid": violations.is_empty(),
+            "violations": violations,
+            "warnings": warnings,
+        });
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Check a workflow for common problems that n8n doesn't stop it running with, but which make it fragile, insecure or hard to follow. Nothing is changed.
+
+            Flags: no error handling (no error workflow and no node with an error output), secrets hardcoded in parameters instead of credentials, webhooks whose response is missing or ignored, long chains of nodes that would be clearer as sub-workflows, and HTTP nodes without Retry On Fail. Lints a workflow in n8n by ID, or a workflow document (e.g. before creating it)."
+    )]
+    async fn lint_workflow(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "The ID of the workflow to lint. Give either this or workflow.")]
+        workflow_id: Option<WorkflowId>,
+        #[tool(param)]
+        #[schemars(
+            description = "The workflow document to lint, with at least name, nodes and connections. Give either this or workflow_id."
+        )]
+        workflow: Option<serde_json::Value>,
+    ) -> Result<Content, ServerError> {
+        let workflow = self.workflow_or_document(workflow_id, workflow).await?;
+
+        let issues = lint::lint(&workflow);
+        let count = |severity| {
+            issues
+                .iter()
+                .filter(|issue| issue.severity == severity)
+                .count()
+        };
+
+        let json_object = json!({
+            "errors": count(lint::Severity::Error),
+            "warnings": count(lint::Severity::Warning),
+            "info": count(lint::Severity::Info),
+            "issues": issues,
+        });
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Build a skeleton workflow from a plan: a trigger and an ordered list of steps, each a kind of node. Nothing is sent to n8n.
+
+            Returns nodes of the right types and versions, connected in order and laid out left to right, with starting parameters and a to-do list of what each node still needs (e.g. a URL or credentials). Fill those in, then pass the nodes and connections to create_workflow. Far more reliable than writing n8n's workflow JSON from scratch."
+    )]
+    async fn scaffold_workflow(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "The name of the workflow.")]
+        name: String,
+        #[tool(param)]
+        #[schemars(
+            description = "What starts the workflow: 'webhook', 'schedule', 'manual', 'form', 'chat', 'sub_workflow' (called by another workflow) or 'error' (for an error workflow)."
+        )]
+        trigger: scaffold::TriggerType,
+        #[tool(param)]
+        #[schemars(description = "The steps after the trigger, in the order they run.")]
+        steps: Vec<scaffold::Step>,
+    ) -> Result<Content, ServerError> {
+        validate::not_empty("The workflow name", &name)?;
+
+        let scaffold = scaffold::scaffold(trigger, &steps);
+        let warnings = workflow::connection_warnings(&scaffold.nodes, &scaffold.connections);
+
+        let json_object = json!({
+            "workflow": {
+                "name": name,
+                "nodes": scaffold.nodes,
+                "connections": scaffold.connections,
+            },
+            "todo": scaffold.todo,
+            "warnings": warnings,
+        });
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Draw a workflow's nodes and connections as a Mermaid flowchart (or Graphviz DOT), so it can be reviewed at a glance in chat. Nothing is changed.
+
+            Triggers are drawn rounded and branching nodes as diamonds, with branch outputs labelled (e.g. 'true' and 'false'). Connections to AI sub-nodes are dotted, disabled nodes are dashed, and sticky notes are left out. Draws a workflow in n8n by ID, or a workflow document."
+    )]
+    async fn render_workflow_diagram(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "The ID of the workflow to draw. Give either this or workflow.")]
+        workflow_id: Option<WorkflowId>,
+        #[tool(param)]
+        #[schemars(
+            description = "The workflow document to draw, with at least name, nodes and connections. Give either this or workflow_id."
+        )]
+        workflow: Option<serde_json::Value>,
+        #[tool(param)]
+        #[schemars(
+            description = "The diagram format: 'mermaid' (the default) or 'dot'. Optional."
+        )]
+        format: Option<diagram::Format>,
+    ) -> Result<Content, ServerError> {
+        let workflow = self.workflow_or_document(workflow_id, workflow).await?;
+        let format = format.unwrap_or_default();
+
+        let diagram = diagram::render(&workflow, format);
+
+        Ok(Content::text(format!(
+            "```{}\n{diagram}```",
+            format.language()
+        )))
+    }
+
+    #[tool(
+        description = "Collect the documentation written into a workflow - its sticky notes and the notes on its nodes - as Markdown. Nothing is changed.
+
+            Sticky notes are listed left to right across the canvas, each with the nodes it's drawn over. Reads a workflow in n8n by ID, or a workflow document."
+    )]
+    async fn get_workflow_docs(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "The ID of the workflow to read. Give either this or workflow.")]
+        workflow_id: Option<WorkflowId>,
+        #[tool(param)]
+        #[schemars(
+            description = "The workflow document to read, with at least name and nodes. Give either this or workflow_id."
+        )]
+        workflow: Option<serde_json::Value>,
+    ) -> Result<Content, ServerError> {
+        let workflow = self.workflow_or_document(workflow_id, workflow).await?;
+
+        Ok(Content::text(docs::render(&workflow)))
+    }
+
+    #[tool(
+        description = "Sums up how big and complicated a workflow is: how many nodes it has and of which types, its triggers, the credentials its nodes use, how many connections there are and how deep the longest chain of nodes goes. Nothing is changed.
+
+            Useful for a quick assessment before changing a workflow, or for an inventory of workflows. Reads a workflow in n8n by ID, or a workflow document."
+    )]
+    async fn workflow_stats(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "The ID of the workflow to sum up. Give either this or workflow."
+        )]
+        workflow_id: Option<WorkflowId>,
+        #[tool(param)]
+        #[schemars(
+            description = "The workflow document to sum up, with at least name and nodes. Give either this or workflow_id."
+        )]
+        workflow: Option<serde_json::Value>,
+    ) -> Result<Content, ServerError> {
+        let workflow = self.workflow_or_document(workflow_id, workflow).await?;
+
+        let mut json_object = json!(stats::of(&workflow));
+        json_object["id"] = json!(workflow.id);
+        json_object["name"] = json!(workflow.name);
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Compare the workflows on two n8n instances, e.g. dev and prod before promoting changes. Nothing is changed.
+
+            Workflows are matched up by name. Lists the workflows only on the source, only on the target, and on both but with different definitions - which nodes were added, removed or changed, and whether the connections or settings differ. IDs and node positions are ignored, and credentials are compared by name. Instances are the ones configured in the [instances] section of the config file; leave source or target out to use this server's own instance."
+    )]
+    async fn compare_instances(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "The instance to compare from, e.g. 'dev'. Optional - defaults to this server's own instance."
+        )]
+        source: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "The instance to compare with, e.g. 'prod'. Optional - defaults to this server's own instance."
+        )]
+        target: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Only compare workflows with these tags, as comma-separated tag names, e.g. 'prod,billing'. Optional."
+        )]
+        tags: Option<String>,
+    ) -> Result<Content, ServerError> {
+        if source == target {
+            return Err(ServerError::validation(
+                "source and target are the same instance - name two different instances to compare",
+            ));
+        }
+        let source_server = self.on_instance(source.as_deref())?;
+        let target_server = self.on_instance(target.as_deref())?;
+
+        let query = Query::new()
+            .set_optional("tags", tags.as_deref())
+            .set("limit", MAX_PAGE_SIZE);
+        let mut workflows = Vec::new();
+        for server in [&source_server, &target_server] {
+            let page: Paginated<Workflow> = server
+                .fetch_all_pages(Endpoint::Workflows, query.clone(), None)
+                .await?;
+            // Workflows that weren't fetched would look like they're missing from the instance
+            if page.next_cursor.is_some() {
+                return Err(ServerError::validation(format!(
+                    "{} has more than {} workflows - compare fewer with the tags filter",
+                    server.n8n.base_url(),
+                    page.data.len()
+                )));
+            }
+            workflows.push(page.data);
+        }
+
+        let mut json_object = json!(compare::compare(&workflows[0], &workflows[1]));
+        json_object["source"] = json!({
+                "instance": source,
+                "baseUrl": source_server.n8n.base_url().to_string(),
+                "workflows": workflows[0].len(),
+        });
+        json_object["target"] = json!({
+                "instance": target,
+                "baseUrl": target_server.n8n.base_url().to_string(),
+                "workflows": workflows[1].len(),
+        });
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Copy a workflow from one n8n instance to another, e.g. from dev to prod, updating the workflow with the same name on the target or creating it there.
+
+            Credentials are remapped to the target's credentials with the same type and name, and any without a match are listed so they can be created on the target. Static data isn't copied (an updated workflow keeps its own), and a created workflow starts inactive. Workflows called by ID from Execute Workflow nodes, and the error workflow, aren't remapped, so they're listed as warnings. Use dry_run to see what would be sent first. Instances are the ones configured in the [instances] section of the config file; leave source or target out to use this server's own instance."
+    )]
+    async fn sync_workflow_to_instance(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "The ID of the workflow to copy, on the source instance.")]
+        workflow_id: WorkflowId,
+        #[tool(param)]
+        #[schemars(
+            description = "The instance to copy from, e.g. 'dev'. Optional - defaults to this server's own instance."
+        )]
+        source: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "The instance to copy to, e.g. 'prod'. Optional - defaults to this server's own instance."
+        )]
+        target: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Only return the request that would be sent to the target, without changing anything. Defaults to false."
+        )]
+        dry_run: Option<bool>,
+    ) -> Result<Content, ServerError> {
+        validate::id("workflow", &workflow_id)?;
+        if source == target {
+            return Err(ServerError::validation(
+                "source and target are the same instance - name two different instances to copy between",
+            ));
+        }
+        let source_server = self.on_instance(source.as_deref())?;
+        let target_server = self.on_instance(target.as_deref())?;
+
+        let mut workflow = source_server.fetch_workflow(&workflow_id).await?;
+        validate::not_empty("The workflow name", &workflow.name)?;
+
+        let mut warnings: Vec<String> = dependencies::Graph::build(std::slice::from_ref(&workflow))
+            .calls
+            .iter()
+            .map(|call| {
+                format!(
+                    "'{}' calls workflow {} by its ID on the source instance - check that it calls the right workflow on the target",
+                    call.node, call.callee
+                )
+            })
+            .collect();
+        if let Some(error_workflow) = workflow
+            .settings
+            .get("errorWorkflow")
+            .and_then(|error_workflow| error_workflow.as_str())
+        {
+            warnings.push(format!(
+                "The error workflow is set to {error_workflow}, its ID on the source instance - check that it's the right workflow on the target"
+            ));
+        }
+
+        let credentials = if workflow.nodes.iter().any(|node| node.credentials.is_some()) {
+            let query = Query::new().set("limit", MAX_PAGE_SIZE);
+            let credentials: Paginated<serde_json::Value> = target_server
+                .fetch_all_pages(Endpoint::Credentials, query, None)
+                .await
+                .map_err(|err| {
+                    ServerError::validation(format!(
+                        "The workflow's credentials can't be remapped, as the target instance couldn't list its credentials: {err}"
+                    ))
+                })?;
+            credentials.data
+        } else {
+            Vec::new()
+        };
+        let unmapped = workflow::remap_credentials(&mut workflow.nodes, &credentials);
+        if !unmapped.is_empty() {
+            warnings.push(format!(
+                "{} credential reference(s) have no credential with the same type and name on the target - create them there, then select them in the nodes",
+                unmapped.len()
+            ));
+        }
+
+        let existing = match target_server
+            .find_workflows_named(&workflow.name)
+            .await?
+            .as_slice()
+        {
+            [] => None,
+            [existing] => Some(existing.clone()),
+            existing => {
+                return Err(ServerError::validation(format!(
+                    "There are {} workflows called '{}' on the target instance, so it isn't clear which to update",
+                    existing.len(),
+                    workflow.name
+                )));
+            }
+        };
+        match &existing {
+            Some(existing) => {
+                workflow.id = existing.id.clone();
+                workflow.static_data = existing.static_data.clone();
+            }
+            None => {
+                workflow.id = None;
+                workflow.static_data = serde_json::Value::Null;
+            }
+        }
+
+        let mut json_object = if dry_run.unwrap_or(false) {
+            let request = match &workflow.id {
+                Some(target_id) => dry_run_request(
+                    "PUT",
+                    target_server.url(Endpoint::Workflow(target_id)),
+                    workflow.update_body(),
+                ),
+                None => dry_run_request(
+                    "POST",
+                    target_server.url(Endpoint::Workflows),
+                    workflow.update_body(),
+                ),
+            };
+            json!({
+                "dryRun": request,
+                "action": if workflow.id.is_some() { "update" } else { "create" },
+                "id": workflow.id,
+                "name": workflow.name,
+            })
+        } else {
+            target_server
+                .import_workflow(workflow, ImportMatch::Id)
+                .await?
+        };
+        json_object["source"] = json!({
+            "instance": source,
+            "baseUrl": source_server.n8n.base_url().to_string(),
+            "workflowId": workflow_id,
+        });
+        json_object["target"] = json!({
+            "instance": target,
+            "baseUrl": target_server.n8n.base_url().to_string(),
+        });
+        json_object["unmappedCredentials"] = json!(unmapped);
+        json_object["warnings"] = json!(warnings);
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Fill in the parts of nodes that n8n needs but that are easy to leave out: an empty parameters object, the latest known typeVersion of the node's type, and a position.
+
+            Use this on nodes written from scratch before create_workflow, so each node only needs a name, a type and the parameters it actually uses. Nodes without a position are laid out left to right, following the connections. Anything already set is kept, and nothing is sent to n8n."
+    )]
+    async fn fill_node_defaults(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "The nodes to fill in.")]
+        nodes: Vec<Node>,
+        #[tool(param)]
+        #[schemars(
+            description = "The connections between the nodes, used to lay them out. Optional - without them, nodes are all placed in one column."
+        )]
+        connections: Option<Connections>,
+    ) -> Result<Content, ServerError> {
+        let mut nodes = nodes;
+        let filled = workflow::fill_node_defaults(&mut nodes, &connections.unwrap_or_default());
+
+        let json_object = json!({
+            "nodes": nodes,
+            "filled": filled,
+        });
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Retrieve all workflows (with optional parameters for filtering).
+
+            A workflow can be run with run_workflow if it's active and has a webhook trigger node (type 'n8n-nodes-base.webhook'), using the path set on that node.
+            "
+    )]
+    async fn retrieve_workflows(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "The parameters to fetch workflows by. If you leave all fields as blank, it will attempt to fetch everything.
+
+                To get the next page, set cursor to the nextCursor of the previous one."
+        )]
+        retrieve_workflow_params: RetrieveAllWorkflowParams,
+        #[tool(param)]
+        #[schemars(
+            description = "Whether to follow the cursor through every page and return all the results at once, instead of one page. Stops after 20 pages, in which case nextCursor is set so you can carry on from there. Optional."
+        )]
+        fetch_all: Option<bool>,
+    ) -> Result<Content, ServerError> {
+        retrieve_workflow_params.validate()?;
+
+        if fetch_all.unwrap_or_default() {
+            let cursor = retrieve_workflow_params.cursor.clone();
+            let query = RetrieveAllWorkflowParams {
+                cursor: None,
+                ..retrieve_workflow_params
+            }
+            .query();
+            let res: Paginated<serde_json::Value> = self
+                .fetch_all_pages(Endpoint::Workflows, query, cursor)
+                .await?;
+
+            return output::structured(&res);
+        }
+
+        let res: Paginated<serde_json::Value> = self
+            .fetch_page(Endpoint::Workflows, &retrieve_workflow_params.query())
+            .await?;
+
+        output::structured(&res)
+    }
+
+    #[tool(
+        description = "Search every workflow's node parameters, expressions, sticky notes and node notes for a string or regex (e.g. an old API URL or a credential name), returning which workflows and nodes it appears in.
+
+            Each hit gives the node, the path of the parameter within it (e.g. 'parameters.url'), whether it's a plain value, an expression, a sticky note or a node's notes, and an excerpt of the matching text."
+    )]
+    async fn search_workflows(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "What to search for, e.g. 'api.example.com'. Matched literally unless regex is true."
+        )]
+        pattern: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Whether the pattern is a regular expression (Rust regex syntax), e.g. 'https?://old-api\\.'. Defaults to false."
+        )]
+        regex: Option<bool>,
+        #[tool(param)]
+        #[schemars(description = "Whether to match case. Defaults to false.")]
+        case_sensitive: Option<bool>,
+        #[tool(param)]
+        #[schemars(
+            description = "Only search active (true) or inactive (false) workflows. Optional."
+        )]
+        active: Option<bool>,
+        #[tool(param)]
+        #[schemars(
+            description = "Only search workflows with these tags, as comma-separated tag names, e.g. 'prod,billing'. Optional."
+        )]
+        tags: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Only search workflows in this project. Optional.")]
+        project_id: Option<ProjectId>,
+    ) -> Result<Content, ServerError> {
+        validate::not_empty("pattern", &pattern)?;
+        validate::optional_id("project", project_id.as_deref())?;
+        let matcher = search::matcher(
+            &pattern,
+            regex.unwrap_or(false),
+            case_sensitive.unwrap_or(false),
+        )?;
+
+        let query = Query::new()
+            .set_optional("active", active)
+            .set_optional("tags", tags.as_deref())
+            .set_optional("projectId", project_id.as_deref())
+            .set("limit", MAX_PAGE_SIZE);
+        let workflows: Paginated<Workflow> = self
+            .fetch_all_pages(Endpoint::Workflows, query, None)
+            .await?;
+
+        let mut hit_count = 0;
+        let mut results = Vec::new();
+        for workflow in &workflows.data {
+            let mut hits = search::search_nodes(&workflow.nodes, &matcher);
+            if hits.is_empty() {
+                continue;
+            }
+
+            hits.truncate(search::MAX_HITS - hit_count);
+            hit_count += hits.len();
+            results.push(json!({
+                "id": workflow.id,
+                "name": workflow.name,
+                "active": workflow.extra.get("active"),
+                "hits": hits,
+            }));
+
+            if hit_count == search::MAX_HITS {
+                break;
+            }
+        }
+
+        let mut json_object = json!({
+            "searched": workflows.data.len(),
+            "matchedWorkflows": results.len(),
+            "hits": hit_count,
+            "results": results,
+        });
+        if hit_count == search::MAX_HITS {
+            json_object["warning"] = json!(format!(
+                "Only the first {} hits are listed - narrow the pattern or filters to see the rest",
+                search::MAX_HITS
+            ));
+        } else if workflows.next_cursor.is_some() {
+            json_object["warning"] = json!(format!(
+                "Only the first {MAX_FETCH_ALL_PAGES} pages of workflows were searched - narrow the filters to search the rest"
+            ));
+        }
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Work out which workflows call which, through Execute Workflow (Execute Sub-workflow) nodes and AI agent workflow tools, across the whole instance.
+
+            Given a workflow ID, returns the workflows it calls, the workflows that call it, and every workflow that calls it directly or indirectly - i.e. what could break if it's changed or deleted. Without one, returns every call in the instance. Either way, nodes whose target workflow can't be worked out without running them (e.g. because its ID is an expression) are listed as unresolved, since they could call anything."
+    )]
+    async fn workflow_dependencies(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "The workflow to show the callers and callees of. Optional - every call in the instance is returned by default."
+        )]
+        workflow_id: Option<WorkflowId>,
+    ) -> Result<Content, ServerError> {
+        validate::optional_id("workflow", workflow_id.as_deref())?;
+
+        let query = Query::new().set("limit", MAX_PAGE_SIZE);
+        let workflows: Paginated<Workflow> = self
+            .fetch_all_pages(Endpoint::Workflows, query, None)
+            .await?;
+
+        let graph = dependencies::Graph::build(&workflows.data);
+        let by_id: HashMap<&WorkflowId, &Workflow> = workflows
+            .data
+            .iter()
+            .filter_map(|workflow| Some((workflow.id.as_ref()?, workflow)))
+            .collect();
+        let summary = |workflow_id: &WorkflowId| match by_id.get(workflow_id) {
+            Some(workflow) => json!({
+                "id": workflow_id,
+                "name": workflow.name,
+                "active": workflow.extra.get("active"),
+            }),
+            None => json!({ "id": workflow_id, "missing": true }),
+        };
+
+        let mut json_object = match &workflow_id {
+            Some(workflow_id) => {
+                if !by_id.contains_key(workflow_id) && workflows.next_cursor.is_none() {
+                    return Err(ServerError::validation(format!(
+                        "There's no workflow with the ID '{workflow_id}'"
+                    )));
+                }
+
+                let call_summary = |workflow_id: &WorkflowId, call: &dependencies::Call| {
+                    let mut summary = summary(workflow_id);
+                    summary["node"] = json!(call.node);
+                    if call.disabled {
+                        summary["disabled"] = json!(true);
+                    }
+                    summary
+                };
+                let calls: Vec<_> = graph
+                    .calls_from(workflow_id)
+                    .map(|call| call_summary(&call.callee, call))
+                    .collect();
+                let called_by: Vec<_> = graph
+                    .calls_to(workflow_id)
+                    .map(|call| call_summary(&call.caller, call))
+                    .collect();
+                let all_callers: Vec<_> = graph
+                    .all_callers(workflow_id)
+                    .into_iter()
+                    .map(summary)
+                    .collect();
+
+                json!({
+                    "workflow": summary(workflow_id),
+                    "calls": calls,
+                    "calledBy": called_by,
+                    "allCallers": all_callers,
+                    "unresolved": graph.unresolved,
+                })
+            }
+            None => {
+                let involved: BTreeSet<&WorkflowId> = graph
+                    .calls
+                    .iter()
+                    .flat_map(|call| [&call.caller, &call.callee])
+                    .chain(graph.unresolved.iter().map(|call| &call.caller))
+                    .collect();
+                let involved: Vec<_> = involved.into_iter().map(summary).collect();
+
+                json!({
+                    "workflows": involved,
+                    "calls": graph.calls,
+                    "unresolved": graph.unresolved,
+                    "missing": graph.missing(&by_id),
+                })
+            }
+        };
+        if workflows.next_cursor.is_some() {
+            json_object["warning"] = json!(format!(
+                "Only the first {MAX_FETCH_ALL_PAGES} pages of workflows were analyzed, so some calls may be missing"
+            ));
+        }
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "List the credentials on the instance that no workflow's nodes use, so stale secrets can be cleaned up. Nothing is changed.
+
+            Credentials only used by disabled nodes are listed separately, with the nodes that use them. A credential can still be in use from outside this instance's workflows (e.g. by another instance sharing the database, or by workflows in projects the API key can't see), so check before deleting it."
+    )]
+    async fn find_unused_credentials(&self) -> Result<Content, ServerError> {
+        let query = Query::new().set("limit", MAX_PAGE_SIZE);
+        let credentials: Paginated<serde_json::Value> = self
+            .fetch_all_pages(Endpoint::Credentials, query.clone(), None)
+            .await?;
+        let workflows: Paginated<Workflow> = self
+            .fetch_all_pages(Endpoint::Workflows, query, None)
+            .await?;
+        // A credential used by a workflow that wasn't fetched would look unused
+        if credentials.next_cursor.is_some() || workflows.next_cursor.is_some() {
+            return Err(ServerError::validation(format!(
+                "The instance has more than {} credentials or {} workflows, too many to check in one go",
+                credentials.data.len(),
+                workflows.data.len()
+            )));
+        }
+
+        let mut unused = Vec::new();
+        let mut only_disabled = Vec::new();
+        for credential in &credentials.data {
+            let nodes: Vec<(&Workflow, &Node)> = workflows
+                .data
+                .iter()
+                .flat_map(|workflow| workflow.nodes.iter().map(move |node| (workflow, node)))
+                .filter(|(_, node)| workflow::uses_credential(node, credential))
+                .collect();
+
+            let summary = json!({
+                "id": credential["id"],
+                "name": credential["name"],
+                "type": credential["type"],
+                "updatedAt": credential["updatedAt"],
+            });
+            if nodes.is_empty() {
+                unused.push(summary);
+            } else if nodes.iter().all(|(_, node)| node.disabled.unwrap_or(false)) {
+                let mut summary = summary;
+                summary["disabledNodes"] = nodes
+                    .iter()
+                    .map(|(workflow, node)| {
+                        json!({
+                            "workflowId": workflow.id,
+                            "workflowName": workflow.name,
+                            "node": node.name,
+                        })
+                    })
+                    .collect();
+                only_disabled.push(summary);
+            }
+        }
+
+        let json_object = json!({
+            "credentials": credentials.data.len(),
+            "workflows": workflows.data.len(),
+            "unused": unused,
+            "onlyUsedByDisabledNodes": only_disabled,
+        });
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "List the active workflows that still have disabled nodes, which are usually left over from debugging and are a common reason for a step silently not happening (e.g. a Slack message never being sent). Nothing is changed.
+
+            Use set_node_disabled to enable a node again."
+    )]
+    async fn find_disabled_nodes(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Check inactive workflows as well as active ones. Defaults to false."
+        )]
+        include_inactive: Option<bool>,
+        #[tool(param)]
+        #[schemars(
+            description = "Only check workflows with these tags, as comma-separated tag names, e.g. 'prod,billing'. Optional."
+        )]
+        tags: Option<String>,
+    ) -> Result<Content, ServerError> {
+        let query = Query::new()
+            .set_optional(
+                "active",
+                (!include_inactive.unwrap_or(false)).then_some(true),
+            )
+            .set_optional("tags", tags.as_deref())
+            .set("limit", MAX_PAGE_SIZE);
+        let workflows: Paginated<Workflow> = self
+            .fetch_all_pages(Endpoint::Workflows, query, None)
+            .await?;
+
+        let results: Vec<_> = workflows
+            .data
+            .iter()
+            .filter_map(|workflow| {
+                let disabled: Vec<_> = workflow
+                    .nodes
+                    .iter()
+                    .filter(|node| node.disabled.unwrap_or(false))
+                    .map(|node| json!({"name": node.name, "type": node.kind}))
+                    .collect();
+
+                (!disabled.is_empty()).then(|| {
+                    json!({
+                        "id": workflow.id,
+                        "name": workflow.name,
+                        "active": workflow.extra.get("active"),
+                        "disabledNodes": disabled,
+                    })
+                })
+            })
+            .collect();
+
+        let mut json_object = json!({
+            "checked": workflows.data.len(),
+            "withDisabledNodes": results.len(),
+            "workflows": results,
+        });
+        if workflows.next_cursor.is_some() {
+            json_object["warning"] = json!(format!(
+                "Only the first {MAX_FETCH_ALL_PAGES} pages of workflows were checked - narrow the filters to check the rest"
+            ));
+        }
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Back up workflows to the server's backup directory, as one JSON file per workflow named by its ID (e.g. 'AbC123.json').
+
+            Exports every workflow, or only those matching the filters. A workflow's earlier backup is overwritten, so the directory holds the latest version of each workflow. Only available when a backup directory is configured (MCP_BACKUP_DIR, or backup_dir in the [features] section of the config file)."
+    )]
+    async fn export_workflows(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Only export active (true) or inactive (false) workflows. Optional."
+        )]
+        active: Option<bool>,
+        #[tool(param)]
+        #[schemars(
+            description = "Only export workflows with these tags, as comma-separated tag names, e.g. 'prod,billing'. Optional."
+        )]
+        tags: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Only export workflows with this name. Optional.")]
+        name: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Only export workflows in this project. Optional.")]
+        project_id: Option<ProjectId>,
+    ) -> Result<Content, ServerError> {
+        validate::optional_id("project", project_id.as_deref())?;
+        let Some(backup_dir) = self.features.backup_dir.clone() else {
+            return Err(ServerError::validation(
+                "No backup directory is configured, so workflows can't be exported - set MCP_BACKUP_DIR, or backup_dir in the [features] section of the config file (backups aren't available in multi-tenant mode)",
+            ));
+        };
+
+        let query = Query::new()
+            .set_optional("active", active)
+            .set_optional("tags", tags.as_deref())
+            .set_optional("name", name.as_deref())
+            .set_optional("projectId", project_id.as_deref())
+            .set("limit", MAX_PAGE_SIZE);
+        let workflows: Paginated<Workflow> = self
+            .fetch_all_pages(Endpoint::Workflows, query, None)
+            .await?;
+
+        tokio::fs::create_dir_all(&backup_dir)
+            .await
+            .map_err(|err| ServerError::file(&backup_dir, err))?;
+
+        let mut exported = Vec::new();
+        let mut failed = Vec::new();
+        for workflow in &workflows.data {
+            let Some(workflow_id) = &workflow.id else {
+                continue;
+            };
+
+            let written = match validate::id("workflow", workflow_id) {
+                Ok(()) => {
+                    backup::write_workflow(
+                        &backup_dir,
+                        workflow_id,
+                        &serde_json::to_value(workflow)?,
+                    )
+                    .await
+                }
+                Err(err) => Err(err),
+            };
+            match written {
+                Ok(path) => exported.push(json!({
+                    "id": workflow_id,
+                    "name": workflow.name,
+                    "file": path,
+                })),
+                Err(err) => failed.push(json!({
+                    "id": workflow_id,
+                    "name": workflow.name,
+                    "error": err.to_string(),
+                })),
+            }
+        }
+
+        let mut json_object = json!({
+            "directory": backup_dir,
+            "exported": exported,
+            "failed": failed,
+        });
+        if workflows.next_cursor.is_some() {
+            json_object["warning"] = json!(format!(
+                "Only the first {MAX_FETCH_ALL_PAGES} pages of workflows were exported - narrow the filters to export the rest"
+            ));
+        }
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Export executions to a file in the server's backup directory, for offline analysis or archiving: as JSON, or as a CSV table with a row per execution (ID, workflow, status, mode, start and stop times, duration, and the failing node and error).
+
+            Exports every execution in the date range, or only those matching the filters, up to 5000 (or 1000 with include_data). Only available when a backup directory is configured (MCP_BACKUP_DIR, or backup_dir in the [features] section of the config file), and not in multi-tenant mode; files are written to its 'executions' folder and never overwritten."
+    )]
+    #[allow(clippy::too_many_arguments)]
+    async fn export_executions(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "The format of the file: 'json' or 'csv'.")]
+        format: ExportFormat,
+        #[tool(param)]
+        #[schemars(
+            description = "The name of the file, e.g. 'october-failures.csv'. Defaults to one made from the current time, e.g. 'executions-20261015T120000Z.csv'."
+        )]
+        file_name: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Only export executions that started at or after this time. Either an RFC 3339 timestamp or a date (YYYY-MM-DD). Optional."
+        )]
+        started_after: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Only export executions that started before this time. Either an RFC 3339 timestamp or a date (YYYY-MM-DD). Optional."
+        )]
+        started_before: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "The status of the executions to export: 'error' | 'success' | 'waiting' | 'running'. Optional."
+        )]
+        status: Option<ExecutionStatus>,
+        #[tool(param)]
+        #[schemars(description = "Only export the executions of this workflow. Optional.")]
+        workflow_id: Option<WorkflowId>,
+        #[tool(param)]
+        #[schemars(
+            description = "Set to true to export each execution's full data (the input and output of every node) rather than a summary. Only for the json format. Defaults to false."
+        )]
+        include_data: Option<bool>,
+    ) -> Result<Content, ServerError> {
+        validate::optional_id("workflow", workflow_id.as_deref())?;
+        let Some(backup_dir) = self.features.backup_dir.clone() else {
+            return Err(ServerError::validation(
+                "No backup directory is configured, so executions can't be exported - set MCP_BACKUP_DIR, or backup_dir in the [features] section of the config file (backups aren't available in multi-tenant mode, where execution data would be shared between tenants)",
+            ));
+        };
+        let include_data = include_data.unwrap_or(false);
+        if include_data && format == ExportFormat::Csv {
+            return Err(ServerError::validation(
+                "include_data can only be used with the json format",
+            ));
+        }
+        let file_name = match file_name {
+            Some(file_name) => {
+                backup::validate_file_name(&file_name)?;
+                file_name
+            }
+            None => format!(
+                "executions-{}.{}",
+                Utc::now().format("%Y%m%dT%H%M%SZ"),
+                format.extension()
+            ),
+        };
+        let (started_after, started_before) =
+            parse_range(started_after.as_deref(), started_before.as_deref())?;
+
+        let dir = backup_dir.join("executions");
+        let path = dir.join(&file_name);
+        if tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            return Err(ServerError::validation(format!(
+                "{} already exists - choose another file_name",
+                path.display()
+            )));
+        }
+
+        let (executions, truncated) = self
+            .executions_in_range(ExecutionFilter {
+                started_after,
+                started_before,
+                status: status.as_ref(),
+                workflow_id: workflow_id.as_ref(),
+                include_data,
+                ..Default::default()
+            })
+            .await?;
+
+        let contents = match format {
+            ExportFormat::Csv => {
+                execution::to_csv(&executions, &self.workflow_names().await?).into_bytes()
+            }
+            ExportFormat::Json if include_data => serde_json::to_vec_pretty(&executions)?,
+            ExportFormat::Json => serde_json::to_vec_pretty(
+                &executions
+                    .iter()
+                    .map(Execution::summary)
+                    .collect::<Vec<_>>(),
+            )?,
+        };
+
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .map_err(|err| ServerError::file(&dir, err))?;
+        backup::write_file(&path, &contents).await?;
+
+        let mut json_object = json!({
+            "file": path,
+            "format": format,
+            "exported": executions.len(),
+            "sizeBytes": contents.len(),
+        });
+        if truncated {
+            json_object["warning"] = json!(format!(
+                "Only the most recent {} executions were exported - narrow the date range or filters to export the rest",
+                executions.len()
+            ));
+        }
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Restore workflows from the JSON files in the server's backup directory, e.g. those written by export_workflows, reporting what happened to each file.
+
+            Each workflow either updates an existing workflow or is created as a new one, depending on match_by. Updating replaces the existing workflow's name, nodes, connections, settings and static data with the backup's. Only available when a backup directory is configured (MCP_BACKUP_DIR, or backup_dir in the [features] section of the config file, and not in multi-tenant mode)."
+    )]
+    async fn import_workflows(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Only import these files from the backup directory, e.g. ['AbC123.json']. Optional - every .json file is imported by default."
+        )]
+        files: Option<Vec<String>>,
+        #[tool(param)]
+        #[schemars(
+            description = "How to find the workflow a backup should update: 'id' (the default) updates the workflow with the ID stored in the backup, and 'name' updates the only workflow with the same name. When there's no match, the workflow is created. Optional."
+        )]
+        match_by: Option<ImportMatch>,
+    ) -> Result<Content, ServerError> {
+        let Some(backup_dir) = self.features.backup_dir.clone() else {
+            return Err(ServerError::validation(
+                "No backup directory is configured, so workflows can't be imported - set MCP_BACKUP_DIR, or backup_dir in the [features] section of the config file (backups aren't available in multi-tenant mode, so one tenant can't import another's)",
+            ));
+        };
+
+        let backups = match files {
+            Some(files) => {
+                let mut backups = Vec::new();
+                for file in files {
+                    backup::validate_file_name(&file)?;
+                    let workflow = backup::read_workflow(&backup_dir.join(&file)).await;
+                    backups.push((file, workflow));
+                }
+                backups
+            }
+            None => backup::read_workflows(&backup_dir).await?,
+        };
+
+        let match_by = match_by.unwrap_or_default();
+        let mut results = Vec::new();
+        for (file, workflow) in backups {
+            let result = match workflow {
+                Ok(workflow) => self.import_workflow(workflow, match_by).await,
+                Err(err) => Err(err),
+            };
+
+            results.push(match result {
+                Ok(mut imported) => {
+                    imported["file"] = json!(file);
+                    imported
+                }
+                Err(err) => json!({
+                    "file": file,
+                    "error": err.to_string(),
+                }),
+            });
+        }
+
+        let json_object = json!({
+            "directory": backup_dir,
+            "imported": results.iter().filter(|result| result.get("error").is_none()).count(),
+            "failed": results.iter().filter(|result| result.get("error").is_some()).count(),
+            "results": results,
+        });
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(description = "Retrieve the details of a single workflow by its ID.")]
+    async fn retrieve_workflow_by_id(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "The workflow ID to fetch.")]
+        workflow_id: WorkflowId,
+    ) -> Result<Content, ServerError> {
+        validate::id("workflow", &workflow_id)?;
+
+        let url = self.url(Endpoint::Workflow(&workflow_id));
+
+        let res = self.client().get(url).send_logged(&self.logger).await?;
+
+        let res = response::json(res).await?;
+
+        output::structured(&res)
+    }
+
+    #[tool(description = "Delete a single workflow by its ID.")]
+    async fn delete_workflow_by_id(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "The workflow ID to use.")]
+        workflow_id: WorkflowId,
+    ) -> Result<Content, ServerError> {
+        validate::id("workflow", &workflow_id)?;
+
+        let res = self.delete_workflow(&workflow_id).await?;
+        let json_as_string = serde_json::to_string_pretty(&res).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Deletes every workflow matching a filter (tags, name prefix, project and/or active state).
+
+            Without confirm, nothing is deleted: the workflows that would be are listed instead, so they can be checked first. Calling it again with the same filter and confirm set to true deletes them one at a time, returning what happened to each."
+    )]
+    async fn delete_workflows_bulk(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Which workflows to delete. At least one of tags, namePrefix, projectId and active has to be set."
+        )]
+        filter: WorkflowFilter,
+        #[tool(param)]
+        #[schemars(
+            description = "Set to true to actually delete the workflows, after checking the list returned without it. Defaults to false."
+        )]
+        confirm: Option<bool>,
+    ) -> Result<Content, ServerError> {
+        filter.validate()?;
+
+        let workflows = self.find_workflows(&filter).await?;
+
+        if !confirm.unwrap_or(false) {
+            let matched: Vec<_> = workflows
+                .iter()
+                .map(|workflow| {
+                    json!({
+                        "id": workflow.id,
+                        "name": workflow.name,
+                        "active": workflow.extra.get("active"),
+                    })
+                })
+                .collect();
+
+            let json_object = json!({
+                "dryRun": true,
+                "matched": matched.len(),
+                "workflows": matched,
+                "message": format!(
+                    "Nothing was deleted. Call delete_workflows_bulk again with the same filter and confirm set to true to delete these {} workflows.",
+                    matched.len()
+                ),
+            });
+
+            let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+            return Ok(Content::text(json_as_string));
+        }
+
+        let total = workflows.len() as u32;
+
+        let mut results = Vec::new();
+        for (i, workflow) in workflows.iter().enumerate() {
+            let Some(workflow_id) = &workflow.id else {
+                continue;
+            };
+
+            // The IDs come from n8n, but are checked anyway, since they end up in the URL
+            let result = match validate::id("workflow", workflow_id) {
+                Ok(()) => self.delete_workflow(workflow_id).await,
+                Err(err) => Err(err),
+            };
+
+            results.push(match result {
+                Ok(_) => json!({
+                    "id": workflow_id,
+                    "name": workflow.name,
+                    "outcome": "deleted",
+                }),
+                Err(err) => json!({
+                    "id": workflow_id,
+                    "name": workflow.name,
+                    "outcome": "failed",
+                    "error": err.to_string(),
+                }),
+            });
+            progress::report(i as u32 + 1, Some(total)).await;
+        }
+
+        let deleted = results
+            .iter()
+            .filter(|result| result["outcome"] == "deleted")
+            .count();
+        let json_object = json!({
+            "dryRun": false,
+            "matched": results.len(),
+            "deleted": deleted,
+            "failed": results.len() - deleted,
+            "results": results,
+        });
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(description = "Activates a single workflow by ID.")]
+    async fn activate_workflow_by_id(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "The workflow ID to use.")]
+        workflow_id: WorkflowId,
+    ) -> Result<Content, ServerError> {
+        validate::id("workflow", &workflow_id)?;
+
+        let res = self.activate_workflow(&workflow_id).await?;
+        let json_as_string = serde_json::to_string_pretty(&res).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(description = "Deactivates a single workflow by ID.")]
+    async fn deactivate_workflow_by_id(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "The workflow ID to use.")]
+        workflow_id: WorkflowId,
+    ) -> Result<Content, ServerError> {
+        validate::id("workflow", &workflow_id)?;
+
+        let res = self.deactivate_workflow(&workflow_id).await?;
+        let json_as_string = serde_json::to_string_pretty(&res).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Archives a workflow: saves a snapshot of it, deactivates it and tags it 'archived', all in one step. A safer alternative to deleting a workflow that might be needed again.
+
+            The snapshot is only saved when a snapshot directory is configured (MCP_SNAPSHOT_DIR, or snapshot_dir in the [features] section of the config file), and can be restored with rollback_workflow. Use unarchive_workflow to undo the rest."
+    )]
+    async fn archive_workflow(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "The ID of the workflow to archive.")]
+        workflow_id: WorkflowId,
+    ) -> Result<Content, ServerError> {
+        validate::id("workflow", &workflow_id)?;
+
+        let workflow = self.fetch_workflow(&workflow_id).await?;
+        let snapshot = self
+            .snapshot(&workflow_id, snapshot::Reason::Archive)
+            .await?;
+
+        let active = workflow.extra.get("active") == Some(&json!(true));
+        if active {
+            self.deactivate_workflow(&workflow_id).await?;
+        }
+
+        let current = tag::of_workflow(&workflow);
+        let tagged = tag::find(&current, ARCHIVED_TAG).is_none();
+        if tagged {
+            let archived = match tag::find(&self.fetch_all_tags().await?, ARCHIVED_TAG) {
+                Some(archived) => archived.clone(),
+                None => self.create_tag_named(ARCHIVED_TAG).await?,
+            };
+            let tags = current.iter().chain([&archived]).collect();
+            self.put_workflow_tags(&workflow, &workflow_id, tags)
+                .await?;
+        }
+
+        let mut json_object = json!({
+            "id": workflow_id,
+            "name": workflow.name,
+            "deactivated": active,
+            "tagged": tagged,
+            "snapshot": snapshot.as_ref().and_then(|path| path.file_name()).map(|file| file.to_string_lossy()),
+        });
+        if snapshot.is_none() {
+            json_object["warning"] = json!(
+                "No snapshot directory is configured, so no snapshot of the workflow was saved - set MCP_SNAPSHOT_DIR, or snapshot_dir in the [features] section of the config file"
+            );
+        }
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Unarchives a workflow archived with archive_workflow: removes its 'archived' tag, and activates it again if asked to."
+    )]
+    async fn unarchive_workflow(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "The ID of the workflow to unarchive.")]
+        workflow_id: WorkflowId,
+        #[tool(param)]
+        #[schemars(
+            description = "Whether to activate the workflow again. Defaults to false, since it may not have been active before it was archived."
+        )]
+        activate: Option<bool>,
+    ) -> Result<Content, ServerError> {
+        validate::id("workflow", &workflow_id)?;
+
+        let workflow = self.fetch_workflow(&workflow_id).await?;
+
+        let current = tag::of_workflow(&workflow);
+        let archived = tag::find(&current, ARCHIVED_TAG).cloned();
+        if let Some(archived) = &archived {
+            let tags = current.iter().filter(|tag| tag.id != archived.id).collect();
+            self.put_workflow_tags(&workflow, &workflow_id, tags)
+                .await?;
+        }
+
+        let activate = activate.unwrap_or(false);
+        if activate {
+            self.activate_workflow(&workflow_id).await?;
+        }
+
+        let mut json_object = json!({
+            "id": workflow_id,
+            "name": workflow.name,
+            "untagged": archived.is_some(),
+            "activated": activate,
+        });
+        if archived.is_none() {
+            json_object["note"] = json!(format!(
+                "Workflow {workflow_id} wasn't tagged '{ARCHIVED_TAG}', so it may not have been archived"
+            ));
+        }
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Activates or deactivates every workflow matching a filter (tags, name prefix, project and/or active state), one at a time, returning what happened to each.
+
+            Workflows that are already in the requested state are left alone. One workflow failing to activate (e.g. because it has no trigger) doesn't stop the rest."
+    )]
+    async fn set_active_bulk(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Which workflows to change. At least one of tags, namePrefix, projectId and active has to be set."
+        )]
+        filter: WorkflowFilter,
+        #[tool(param)]
+        #[schemars(
+            description = "Whether to activate (true) or deactivate (false) the workflows."
+        )]
+        active: bool,
+    ) -> Result<Content, ServerError> {
+        filter.validate()?;
+
+        let workflows = self.find_workflows(&filter).await?;
+        let total = workflows.len() as u32;
+
+        let mut results = Vec::new();
+        for (i, workflow) in workflows.iter().enumerate() {
+            let Some(workflow_id) = &workflow.id else {
+                continue;
+            };
+
+            let result = if workflow.extra.get("active") == Some(&json!(active)) {
+                Ok("unchanged")
+            } else if let Err(err) = validate::id("workflow", workflow_id) {
+                Err(err)
+            } else if active {
+                self.activate_workflow(workflow_id)
+                    .await
+                    .map(|_| "activated")
+            } else {
+                self.deactivate_workflow(workflow_id)
+                    .await
+                    .map(|_| "deactivated")
+            };
+
+            results.push(match result {
+                Ok(outcome) => json!({
+                    "id": workflow_id,
+                    "name": workflow.name,
+                    "outcome": outcome,
+                }),
+                Err(ServerError::Detailed(details)) => json!({
+                    "id": workflow_id,
+                    "name": workflow.name,
+                    "outcome": "failed",
+                    "error": details["message"],
+                    "hint": details["hint"],
+                }),
+                Err(err) => json!({
+                    "id": workflow_id,
+                    "name": workflow.name,
+                    "outcome": "failed",
+                    "error": err.to_string(),
+                }),
+            });
+            progress::report(i as u32 + 1, Some(total)).await;
+        }
+
+        let count = |outcome: &str| {
+            results
+                .iter()
+                .filter(|result| result["outcome"] == outcome)
+                .count()
+        };
+        let json_object = json!({
+            "matched": results.len(),
+            "changed": count("activated") + count("deactivated"),
+            "unchanged": count("unchanged"),
+            "failed": count("failed"),
+            "results": results,
+        });
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Adds a tag to (or removes it from) every workflow matching a filter (tags, name prefix, project and/or active state), one at a time, returning what happened to each.
+
+            Each workflow's other tags are kept. Workflows that already have the tag (or, when removing, don't have it) are left alone, and a tag that doesn't exist yet is created when adding it."
+    )]
+    async fn tag_workflows_bulk(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Which workflows to tag. At least one of tags, namePrefix, projectId and active has to be set."
+        )]
+        filter: WorkflowFilter,
+        #[tool(param)]
+        #[schemars(description = "The name of the tag, e.g. 'billing'.")]
+        tag: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Remove the tag from the workflows instead of adding it. Defaults to false."
+        )]
+        remove: Option<bool>,
+    ) -> Result<Content, ServerError> {
+        filter.validate()?;
+        validate::not_empty("tag", &tag)?;
+        let remove = remove.unwrap_or(false);
+
+        let workflows = self.find_workflows(&filter).await?;
+        let total = workflows.len() as u32;
+
+        let existing_tags = self.fetch_all_tags().await?;
+        let existing_tag = match tag::find(&existing_tags, &tag) {
+            Some(existing_tag) => Some(existing_tag.clone()),
+            None if remove => {
+                return Err(ServerError::validation(format!(
+                    "There's no tag called '{tag}', so no workflow has it"
+                )));
+            }
+            // The tag is only created if there's a workflow to add it to, and before any are changed so that failing to
+            // create it doesn't leave the run half done
+            None if workflows.iter().any(|workflow| {
+                workflow
+                    .id
+                    .as_ref()
+                    .is_some_and(|id| validate::id("workflow", id).is_ok())
+            }) =>
+            {
+                Some(self.create_tag_named(&tag).await?)
+            }
+            None => None,
+        };
+
+        let mut results = Vec::new();
+        for (i, workflow) in workflows.iter().enumerate() {
+            let Some(workflow_id) = &workflow.id else {
+                continue;
+            };
+
+            let current = tag::of_workflow(workflow);
+            let has_tag = existing_tag
+                .as_ref()
+                .is_some_and(|tag| current.iter().any(|current| current.id == tag.id));
+
+            let result = if has_tag != remove {
+                Ok("unchanged")
+            } else if let Err(err) = validate::id("workflow", workflow_id) {
+                Err(err)
+            } else if let Some(tag) = &existing_tag {
+                let tags = current
+                    .iter()
+                    .filter(|current| current.id != tag.id)
+                    .chain((!remove).then_some(tag))
+                    .collect();
+
+                self.put_workflow_tags(workflow, workflow_id, tags)
+                    .await
+                    .map(|_| if remove { "untagged" } else { "tagged" })
+            } else {
+                Ok("unchanged")
+            };
+
+            results.push(match result {
+                Ok(outcome) => json!({
+                    "id": workflow_id,
+                    "name": workflow.name,
+                    "outcome": outcome,
+                }),
+                Err(err) => json!({
+                    "id": workflow_id,
+                    "name": workflow.name,
+                    "outcome": "failed",
+                    "error": err.to_string(),
+                }),
+            });
+            progress::report(i as u32 + 1, Some(total)).await;
+        }
+
+        let count = |outcome: &str| {
+            results
+                .iter()
+                .filter(|result| result["outcome"] == outcome)
+                .count()
+        };
+        let json_object = json!({
+            "tag": existing_tag.map_or(tag, |tag| tag.name),
+            "matched": results.len(),
+            "changed": count("tagged") + count("untagged"),
+            "unchanged": count("unchanged"),
+            "failed": count("failed"),
+            "results": results,
+        });
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Updates a workflow's name, nodes and connections. Its settings and static data are kept - use update_workflow_settings to change settings.
+
+            Along with the updated workflow, returns warnings about any connections or credentials that will stop parts of it from running (e.g. nodes that can't be reached from a trigger, or credentials that don't exist). Also describes the workflow's triggers, and whether and how it can be activated and run.
+            With dry_run, nothing is updated: the request that would be sent is returned instead, along with any ways its body doesn't match n8n's workflow format, and the same warnings."
+    )]
+    async fn update_workflow_by_id(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "The ID of the workflow to be updated.")]
+        workflow_id: WorkflowId,
+        #[tool(param)]
+        #[schemars(description = "The name of your workflow.")]
+        name: String,
+        #[tool(param)]
+        #[schemars(description = "The nodes you want to use in your workflow.")]
+        nodes: Vec<Node>,
+        #[tool(param)]
+        #[schemars(description = "The connections you want for your workflow.")]
+        connections: Connections,
+        #[tool(param)]
+        #[schemars(
+            description = "Set to true to only check the workflow and return the request that would be sent to n8n, without updating anything. Defaults to false."
+        )]
+        dry_run: Option<bool>,
+    ) -> Result<Content, ServerError> {
+        validate::id("workflow", &workflow_id)?;
+        validate::nodes(&nodes)?;
+
+        // Static data holds trigger state (e.g. the last poll time), and settings what was chosen with
+        // update_workflow_settings or set_error_workflow, so both must survive updates
+        let current = self.fetch_workflow(&workflow_id).await?;
+        let static_data = current.static_data;
+        let settings = current.settings;
+
+        let url = self.url(Endpoint::Workflow(&workflow_id));
+
+        let mut warnings = workflow::connection_warnings(&nodes, &connections);
+        warnings.extend(self.credential_warnings(&nodes).await);
+        let triggers = workflow::trigger_summary(&nodes);
+
+        let json_object = json!({
+            "name": name,
+            "nodes": nodes,
+            "connections": connections,
+            "settings": settings,
+            "staticData": static_data
+        });
+
+        let violations = self
+            .policy_violations(
+                Some(&workflow_id),
+                &serde_json::from_value(json_object.clone())?,
+            )
+            .await?;
+
+        if dry_run.unwrap_or(false) {
+            let json_as_string = serde_json::to_string_pretty(&json!({
+                "dryRun": dry_run_request("PUT", url, json_object),
+                "warnings": warnings,
+                "triggers": triggers,
+                "policyViolations": violations,
+            }))
+            .unwrap();
+
+            return Ok(Content::text(json_as_string));
+        }
+
+        warnings.extend(policy_warnings(&name, &violations)?);
+
+        self.snapshot(&workflow_id, snapshot::Reason::Update)
+            .await?;
+
+        let res = self
+            .client()
+            .put(url)
+            .json(&json_object)
+            .send_logged(&self.logger)
+            .await?;
+
+        let res = response::json(res).await?;
+        let json_as_string = serde_json::to_string_pretty(&json!({
+            "workflow": res,
+            "warnings": warnings,
+            "triggers": triggers,
+        }))
+        .unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Updates only the provided fields of a workflow, keeping everything else as-is.
+
+            Prefer this over updating the whole workflow when only the name, nodes, connections or settings need to change.
+            Along with the updated workflow, returns warnings about any connections or credentials that will stop parts of it from running. Also describes the workflow's triggers, and whether and how it can be activated and run."
+    )]
+    async fn update_workflow_fields(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "The ID of the workflow to be updated.")]
+        workflow_id: WorkflowId,
+        #[tool(param)]
+        #[schemars(description = "The new name of the workflow. Optional.")]
+        name: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "The new nodes of the workflow. Optional.")]
+        nodes: Option<Vec<Node>>,
+        #[tool(param)]
+        #[schemars(description = "The new connections of the workflow. Optional.")]
+        connections: Option<Connections>,
+        #[tool(param)]
+        #[schemars(
+            description = "Settings to change on the workflow. Only the given keys are changed. Optional."
+        )]
+        settings: Option<serde_json::Value>,
+    ) -> Result<Content, ServerError> {
+        validate::id("workflow", &workflow_id)?;
+        if name.is_none() && nodes.is_none() && connections.is_none() && settings.is_none() {
+            return Err(ServerError::validation(
+                "Nothing to update - give at least one of name, nodes, connections and settings",
+            ));
+        }
+        if let Some(nodes) = &nodes {
+            validate::nodes(nodes)?;
+        }
+        let settings = match settings {
+            Some(serde_json::Value::Object(settings)) => Some(settings),
+            Some(_) => {
+                return Err(ServerError::validation(
+                    "settings has to be an object of the settings to change, e.g. {'timezone': 'Europe/London'}",
+                ));
+            }
+            None => None,
+        };
+
+        let mut workflow = self.fetch_workflow(&workflow_id).await?;
+
+        if let Some(name) = name {
+            workflow.name = name;
+        }
+        if let Some(nodes) = nodes {
+            workflow.nodes = nodes;
+        }
+        if let Some(connections) = connections {
+            workflow.connections = connections;
+        }
+        if let Some(settings) = settings {
+            workflow.settings.extend(settings);
+        }
+
+        let mut warnings = workflow::connection_warnings(&workflow.nodes, &workflow.connections);
+        warnings.extend(self.credential_warnings(&workflow.nodes).await);
+        let violations = self
+            .policy_violations(Some(&workflow_id), &workflow)
+            .await?;
+        warnings.extend(policy_warnings(&workflow.name, &violations)?);
+        let triggers = workflow::trigger_summary(&workflow.nodes);
+        let res = self.put_workflow(&workflow_id, &workflow).await?;
+
+        let json_as_string = serde_json::to_string_pretty(&json!({
+            "workflow": res,
+            "warnings": warnings,
+            "triggers": triggers,
+        }))
+        .unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Renames a workflow, keeping everything else about it as-is.
+
+            Prefer this over updating the whole workflow just to change its name. Warns if another workflow already has the new name, since n8n allows that."
+    )]
+    async fn rename_workflow(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "The ID of the workflow to rename.")]
+        workflow_id: WorkflowId,
+        #[tool(param)]
+        #[schemars(description = "The new name of the workflow.")]
+        name: String,
+    ) -> Result<Content, ServerError> {
+        validate::id("workflow", &workflow_id)?;
+        validate::not_empty("name", &name)?;
+
+        let mut workflow = self.fetch_workflow(&workflow_id).await?;
+        let previous_name = std::mem::replace(&mut workflow.name, name.trim().to_string());
+
+        let taken_by: Vec<_> = self
+            .find_workflows_named(&name)
+            .await?
+            .into_iter()
+            .filter_map(|workflow| workflow.id)
+            .filter(|id| *id != workflow_id)
+            .collect();
+
+        let res = self.put_workflow(&workflow_id, &workflow).await?;
+
+        let mut json_object = json!({
+            "previousName": previous_name,
+            "workflow": res,
+        });
+        if !taken_by.is_empty() {
+            json_object["warning"] = json!(format!(
+                "Other workflows are also called '{}': {}",
+                workflow.name,
+                taken_by
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Updates the settings of a workflow (timeouts, execution saving, error workflow, timezone and execution order).
+
+            Only the provided settings are changed - everything else on the workflow is kept as-is."
+    )]
+    async fn update_workflow_settings(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "The ID of the workflow to be updated.")]
+        workflow_id: WorkflowId,
+        #[tool(param)]
+        #[schemars(
+            description = "The settings to change. Leave a field blank to keep its current value."
+        )]
+        settings: WorkflowSettingsUpdate,
+    ) -> Result<Content, ServerError> {
+        validate::id("workflow", &workflow_id)?;
+        settings.validate()?;
+
+        let mut workflow = self.fetch_workflow(&workflow_id).await?;
+
+        settings.apply_to(&mut workflow.settings);
+
+        let res = self.put_workflow(&workflow_id, &workflow).await?;
+
+        let json_as_string = serde_json::to_string_pretty(&res).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Sets the error workflow - the workflow n8n runs when another one fails - of one or more workflows, given by ID or picked out by a filter (tags, name prefix, project and/or active state). Returns what happened to each.
+
+            The error workflow can be given by ID or by name, and should start with an Error Trigger node. Use find_workflows_without_error_workflow to see which workflows don't have one yet."
+    )]
+    async fn set_error_workflow(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "The ID of the error workflow. Give either this or error_workflow_name."
+        )]
+        error_workflow_id: Option<WorkflowId>,
+        #[tool(param)]
+        #[schemars(
+            description = "The name of the error workflow, e.g. 'Error alerts'. Give either this or error_workflow_id."
+        )]
+        error_workflow_name: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "The IDs of the workflows to set it on. Give either this or filter."
+        )]
+        workflow_ids: Option<Vec<WorkflowId>>,
+        #[tool(param)]
+        #[schemars(
+            description = "Which workflows to set it on. At least one of tags, namePrefix, projectId and active has to be set. Give either this or workflow_ids."
+        )]
+        filter: Option<WorkflowFilter>,
+        #[tool(param)]
+        #[schemars(
+            description = "Leave workflows that already have a different error workflow alone. Defaults to false, which replaces it."
+        )]
+        only_unset: Option<bool>,
+    ) -> Result<Content, ServerError> {
+        let error_workflow = match (error_workflow_id, error_workflow_name) {
+            (Some(error_workflow_id), None) => {
+                validate::id("workflow", &error_workflow_id)?;
+                self.fetch_workflow(&error_workflow_id).await?
+            }
+            (None, Some(name)) => {
+                validate::not_empty("error_workflow_name", &name)?;
+                match self.find_workflows_named(&name).await?.as_slice() {
+                    [error_workflow] => error_workflow.clone(),
+                    [] => {
+                        return Err(ServerError::validation(format!(
+                            "There's no workflow called '{name}'"
+                        )));
+                    }
+                    found => {
+                        return Err(ServerError::validation(format!(
+                            "There are {} workflows called '{name}' - give error_workflow_id instead",
+                            found.len()
+                        )));
+                    }
+                }
+            }
+            _ => {
+                return Err(ServerError::validation(
+                    "Give either error_workflow_id or error_workflow_name",
+                ));
+            }
+        };
+        let Some(error_workflow_id) = error_workflow.id.clone() else {
+            return Err(ServerError::validation(format!(
+                "n8n didn't return an ID for the workflow '{}'",
+                error_workflow.name
+            )));
+        };
+
+        let workflows = match (workflow_ids, filter) {
+            (Some(workflow_ids), None) => {
+                if workflow_ids.is_empty() {
+                    return Err(ServerError::validation("workflow_ids can't be empty"));
+                }
+                let mut workflows = Vec::new();
+                for workflow_id in &workflow_ids {
+                    validate::id("workflow", workflow_id)?;
+                    workflows.push(self.fetch_workflow(workflow_id).await?);
+                }
+                workflows
+            }
+            (None, Some(filter)) => {
+                filter.validate()?;
+                self.find_workflows(&filter).await?
+            }
+            _ => {
+                return Err(ServerError::validation(
+                    "Give either workflow_ids or filter to pick the workflows",
+                ));
+            }
+        };
+        let total = workflows.len() as u32;
+
+        let mut results = Vec::new();
+        for (i, workflow) in workflows.into_iter().enumerate() {
+            let Some(workflow_id) = workflow.id.clone() else {
+                continue;
+            };
+            let name = workflow.name.clone();
+
+            let result = match workflow.error_workflow() {
+                _ if workflow_id == error_workflow_id => Ok("skipped"),
+                Some(current) if current == &*error_workflow_id => Ok("unchanged"),
+                Some(_) if only_unset.unwrap_or(false) => Ok("kept"),
+                _ => match validate::id("workflow", &workflow_id) {
+                    Err(err) => Err(err),
+                    Ok(()) => {
+                        let mut workflow = workflow;
+                        workflow
+                            .settings
+                            .insert("errorWorkflow".to_string(), json!(error_workflow_id));
+                        self.put_workflow(&workflow_id, &workflow)
+                            .await
+                            .map(|_| "set")
+                    }
+                },
+            };
+
+            results.push(match result {
+                Ok(outcome) => json!({
+                    "id": workflow_id,
+                    "name": name,
+                    "outcome": outcome,
+                }),
+                Err(err) => json!({
+                    "id": workflow_id,
+                    "name": name,
+                    "outcome": "failed",
+                    "error": err.to_string(),
+                }),
+            });
+            progress::report(i as u32 + 1, Some(total)).await;
+        }
+
+        let count = |outcome: &str| {
+            results
+                .iter()
+                .filter(|result| result["outcome"] == outcome)
+                .count()
+        };
+        let mut json_object = json!({
+            "errorWorkflow": {
+                "id": error_workflow_id,
+                "name": error_workflow.name,
+            },
+            "matched": results.len(),
+            "changed": count("set"),
+            "unchanged": count("unchanged") + count("kept") + count("skipped"),
+            "failed": count("failed"),
+            "results": results,
+        });
+        if !error_workflow.is_error_workflow() {
+            json_object["warning"] = json!(format!(
+                "'{}' has no Error Trigger node, so it won't be started when the workflows fail - add one to it",
+                error_workflow.name
+            ));
+        }
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "List the active workflows that have no error workflow set, so their failures go unnoticed unless someone looks for them. Nothing is changed.
+
+            Error workflows themselves (started by an Error Trigger) aren't listed. Use set_error_workflow to set one."
+    )]
+    async fn find_workflows_without_error_workflow(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Check inactive workflows as well as active ones. Defaults to false."
+        )]
+        include_inactive: Option<bool>,
+        #[tool(param)]
+        #[schemars(
+            description = "Only check workflows with these tags, as comma-separated tag names, e.g. 'prod,billing'. Optional."
+        )]
+        tags: Option<String>,
+    ) -> Result<Content, ServerError> {
+        let query = Query::new()
+            .set_optional(
+                "active",
+                (!include_inactive.unwrap_or(false)).then_some(true),
+            )
+            .set_optional("tags", tags.as_deref())
+            .set("limit", MAX_PAGE_SIZE);
+        let workflows: Paginated<Workflow> = self
+            .fetch_all_pages(Endpoint::Workflows, query, None)
+            .await?;
+
+        let results: Vec<_> = workflows
+            .data
+            .iter()
+            .filter(|workflow| workflow.error_workflow().is_none() && !workflow.is_error_workflow())
+            .map(|workflow| {
+                json!({
+                    "id": workflow.id,
+                    "name": workflow.name,
+                    "active": workflow.extra.get("active"),
+                })
+            })
+            .collect();
+
+        let mut json_object = json!({
+            "checked": workflows.data.len(),
+            "withoutErrorWorkflow": results.len(),
+            "workflows": results,
+        });
+        if workflows.next_cursor.is_some() {
+            json_object["warning"] = json!(format!(
+                "Only the first {MAX_FETCH_ALL_PAGES} pages of workflows were checked - narrow the filters to check the rest"
+            ));
+        }
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Checks workflows against the policies in the server's config, e.g. that names start with a team prefix or that every workflow has an owner tag, and lists every violation.
+
+            Checks one workflow if workflow_id is given, otherwise every workflow. Enforced policies also stop workflows that break them from being created or updated through this server; the rest only produce warnings."
+    )]
+    async fn check_policies(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "The ID of a workflow to check. Optional - checks every workflow if not given."
+        )]
+        workflow_id: Option<WorkflowId>,
+    ) -> Result<Content, ServerError> {
+        if self.policies.is_empty() {
+            return Err(ServerError::validation(
+                "No policies are configured. Add them as [[policies]] in the server's config file",
+            ));
+        }
+
+        let mut truncated = false;
+        let checked: Vec<(Workflow, Vec<Tag>)> = match &workflow_id {
+            Some(workflow_id) => {
+                validate::id("workflow", workflow_id)?;
+                let workflow = self.fetch_workflow(workflow_id).await?;
+                let tags = tag::of_workflow(&workflow);
+                vec![(workflow, tags)]
+            }
+            None => {
+                let query = Query::new().set("limit", MAX_PAGE_SIZE);
+                let workflows: Paginated<Workflow> = self
+                    .fetch_all_pages(Endpoint::Workflows, query, None)
+                    .await?;
+                truncated = workflows.next_cursor.is_some();
+                workflows
+                    .data
+                    .into_iter()
+                    .map(|workflow| {
+                        let tags = tag::of_workflow(&workflow);
+                        (workflow, tags)
+                    })
+                    .collect()
+            }
+        };
+
+        let results: Vec<_> = checked
+            .iter()
+            .filter_map(|(workflow, tags)| {
+                let violations = policy::check(&self.policies, workflow, Some(tags));
+                (!violations.is_empty()).then(|| {
+                    json!({
+                        "id": workflow.id,
+                        "name": workflow.name,
+                        "violations": violations,
+                    })
+                })
+            })
+            .collect();
+
+        let policies: Vec<_> = self.policies.iter().map(policy::Policy::summary).collect();
+        let mut json_object = json!({
+            "policies": policies,
+            "checked": checked.len(),
+            "compliant": checked.len() - results.len(),
+            "workflows": results,
+        });
+        if truncated {
+            json_object["warning"] = json!(format!(
+                "Only the first {MAX_FETCH_ALL_PAGES} pages of workflows were checked"
+            ));
+        }
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Finds workflows that are copies of each other: the same node types, connected the same way, whatever the workflows and their nodes are called. Copies pile up when workflows are cloned to try something out and then forgotten.
+
+            Each group says whether its workflows are identical (the node settings match too) or only share a structure, with when each was last updated, which helps decide which to keep. Sticky notes are ignored, and workflows with no nodes aren't compared."
+    )]
+    async fn find_duplicate_workflows(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Only compare workflows with these tags, as comma-separated tag names, e.g. 'prod,billing'. Optional."
+        )]
+        tags: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Only group workflows whose node settings match too, not just their structure. Defaults to false."
+        )]
+        identical_only: Option<bool>,
+    ) -> Result<Content, ServerError> {
+        let query = Query::new()
+            .set_optional("tags", tags.as_deref())
+            .set("limit", MAX_PAGE_SIZE);
+        let workflows: Paginated<Workflow> = self
+            .fetch_all_pages(Endpoint::Workflows, query, None)
+            .await?;
+
+        let groups = duplicate::groups(&workflows.data, identical_only.unwrap_or(false));
+        let copies: usize = groups.iter().map(|group| group.workflows.len() - 1).sum();
+
+        let mut json_object = json!({
+            "checked": workflows.data.len(),
+            "copies": copies,
+            "groups": groups,
+        });
+        if workflows.next_cursor.is_some() {
+            json_object["warning"] = json!(format!(
+                "Only the first {MAX_FETCH_ALL_PAGES} pages of workflows were compared - narrow the filters to compare the rest"
+            ));
+        }
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Swaps a workflow's trigger for a webhook, a schedule (with a cron expression) or a manual trigger, keeping the rest of the workflow as it is. The new trigger takes the old one's place on the canvas and its connections, and expressions that referred to the old trigger by name are changed to refer to the new one.
+
+            Useful e.g. to turn a manually tested workflow into a scheduled one, or to run a scheduled workflow on demand through a webhook. A workflow that's active can't be left with only a manual trigger - deactivate it first."
+    )]
+    async fn convert_trigger(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "The ID of the workflow whose trigger should be swapped.")]
+        workflow_id: WorkflowId,
+        #[tool(param)]
+        #[schemars(
+            description = "What to turn the trigger into: 'webhook', 'schedule' or 'manual'."
+        )]
+        to: TriggerConversion,
+        #[tool(param)]
+        #[schemars(
+            description = "The name of the trigger node to swap. Optional - only needed when the workflow has more than one trigger."
+        )]
+        trigger_node: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "When to run, as a cron expression, e.g. '0 9 * * 1-5' for 9am on weekdays. Only for 'schedule', which requires it."
+        )]
+        cron: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "The webhook's path, e.g. 'orders/new'. Only for 'webhook'. Optional - defaults to a random path."
+        )]
+        path: Option<String>,
+    ) -> Result<Content, ServerError> {
+        validate::id("workflow", &workflow_id)?;
+        let cron = cron.map(|cron| cron.trim().to_string());
+        match (to, &cron) {
+            (TriggerConversion::Schedule, None) => {
+                return Err(ServerError::validation(
+                    "cron is required when converting to a schedule, e.g. '0 * * * *' for every hour",
+                ));
+            }
+            (TriggerConversion::Schedule, Some(cron)) => {
+                let fields = cron.split_whitespace().count();
+                if !(5..=6).contains(&fields) {
+                    return Err(ServerError::validation(format!(
+                        "'{cron}' isn't a cron expression. It needs 5 fields (minute, hour, day of month, month, day of week), or 6 with seconds first"
+                    )));
+                }
+            }
+            (_, Some(_)) => {
+                return Err(ServerError::validation(
+                    "cron is only for converting to a schedule",
+                ));
+            }
+            (_, None) => {}
+        }
+        if path.is_some() && to != TriggerConversion::Webhook {
+            return Err(ServerError::validation(
+                "path is only for converting to a webhook",
+            ));
+        }
+
+        let mut workflow = self.fetch_workflow(&workflow_id).await?;
+
+        let triggers: Vec<&Node> = workflow
+            .nodes
+            .iter()
+            .filter(|node| node.is_trigger())
+            .collect();
+        let old = match (&trigger_node, triggers.as_slice()) {
+            (Some(name), _) => match workflow.node(name) {
+                Some(node) if node.is_trigger() => node.clone(),
+                Some(_) => {
+                    return Err(ServerError::validation(format!(
+                        "'{name}' isn't a trigger node. The workflow's triggers are: {}",
+                        triggers
+                            .iter()
+                            .map(|node| node.name.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )));
+                }
+                None => return Err(no_such_node(&workflow_id, &workflow, name)),
+            },
+            (None, [trigger]) => (*trigger).clone(),
+            (None, []) => {
+                return Err(ServerError::validation(format!(
+                    "Workflow {workflow_id} has no trigger node to convert. Add one with add_node_to_workflow instead"
+                )));
+            }
+            (None, triggers) => {
+                return Err(ServerError::validation(format!(
+                    "Workflow {workflow_id} has more than one trigger, so set trigger_node to the one to convert: {}",
+                    triggers
+                        .iter()
+                        .map(|node| node.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )));
+            }
+        };
+
+        let names: Vec<&str> = workflow
+            .nodes
+            .iter()
+            .map(|node| node.name.as_str())
+            .filter(|name| *name != old.name)
+            .collect();
+        let (mut node, todo) = scaffold::trigger_node(to.trigger_type(), &names);
+        let parameters = node.parameters.get_or_insert_default();
+        if let Some(cron) = &cron {
+            parameters.insert(
+                "rule".to_string(),
+                json!({ "interval": [{ "field": "cronExpression", "expression": cron }] }),
+            );
+        }
+        if let Some(path) = &path {
+            parameters.insert("path".to_string(), json!(path.trim_matches('/')));
+        }
+        let new_name = node.name.clone();
+        let new_type = node.kind.clone();
+
+        let changes = workflow.replace_node(&old.name, node);
+        workflow::fill_node_defaults(&mut workflow.nodes, &workflow.connections);
+
+        let triggers = workflow::trigger_summary(&workflow.nodes);
+        if workflow.extra.get("active") == Some(&json!(true)) && !triggers.can_activate {
+            return Err(ServerError::validation(format!(
+                "Workflow {workflow_id} is active, and wouldn't be able to stay active with only a manual trigger. Deactivate it first with deactivate_workflow_by_id"
+            )));
+        }
+
+        let mut warnings = Vec::new();
+        if old.trigger_kind() == Some(workflow::TriggerKind::Webhook) {
+            warnings.push(format!(
+                "'{}' was a webhook, so anything still calling its URL will get an error now",
+                old.name
+            ));
+        }
+        if to == TriggerConversion::Webhook
+            && let Some(todo) = todo
+            && path.is_none()
+        {
+            warnings.push(format!("'{new_name}': {todo}"));
+        }
+
+        self.put_workflow(&workflow_id, &workflow).await?;
+
+        let json_as_string = serde_json::to_string_pretty(&json!({
+            "workflowId": workflow_id,
+            "replaced": { "node": old.name, "type": old.kind },
+            "trigger": { "node": new_name, "type": new_type },
+            "changes": changes,
+            "warnings": warnings,
+            "triggers": triggers,
+        }))
+        .unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Enables or disables a single node in a workflow by its name.
+
+            Disabled nodes are skipped when the workflow runs, which is useful for temporarily turning off a step."
+    )]
+    async fn set_node_disabled(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "The ID of the workflow that the node belongs to.")]
+        workflow_id: WorkflowId,
+        #[tool(param)]
+        #[schemars(description = "The name of the node, as shown in the workflow.")]
+        node_name: String,
+        #[tool(param)]
+        #[schemars(description = "Whether the node should be disabled (true) or enabled (false).")]
+        disabled: bool,
+    ) -> Result<Content, ServerError> {
+        validate::id("workflow", &workflow_id)?;
+
+        let mut workflow = self.fetch_workflow(&workflow_id).await?;
+
+        if workflow.nodes.is_empty() {
+            return Err(ServerError::validation(format!(
+                "Workflow {workflow_id} has no nodes"
+            )));
+        }
+
+        let Some(node) = workflow.node_mut(&node_name) else {
+            return Err(no_such_node(&workflow_id, &workflow, &node_name));
+        };
+
+        node.disabled = Some(disabled);
+
+        let res = self.put_workflow(&workflow_id, &workflow).await?;
+
+        let json_as_string = serde_json::to_string_pretty(&res).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Pins test data to a node in a workflow, replacing any already pinned to it. When the workflow is run from the editor or a test webhook, the node outputs this data instead of running, so the nodes after it can be built and tried out against the same input every time.
+
+            Pinned data isn't used by production executions. Remove it with clear_pinned_data once the workflow is finished, before activating it."
+    )]
+    async fn set_pinned_data(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "The ID of the workflow that the node belongs to.")]
+        workflow_id: WorkflowId,
+        #[tool(param)]
+        #[schemars(description = "The name of the node, as shown in the workflow.")]
+        node_name: String,
+        #[tool(param)]
+        #[schemars(
+            description = "The items the node should output, as a list of JSON objects, e.g. [{'email': 'ada@example.com', 'plan': 'pro'}]. Items already in n8n's {'json': {...}} form are kept as they are."
+        )]
+        items: Vec<serde_json::Value>,
+    ) -> Result<Content, ServerError> {
+        validate::id("workflow", &workflow_id)?;
+        if items.is_empty() {
+            return Err(ServerError::validation(
+                "items must have at least one item. To remove a node's pinned data, use clear_pinned_data",
+            ));
+        }
+
+        let mut pinned = Vec::with_capacity(items.len());
+        for (i, item) in items.into_iter().enumerate() {
+            let serde_json::Value::Object(item) = item else {
+                return Err(ServerError::validation(format!(
+                    "Item {i} isn't a JSON object. Each item must be an object, e.g. {{'email': 'ada@example.com'}}"
+                )));
+            };
+            let item = match item.get("json") {
+                Some(serde_json::Value::Object(_)) => serde_json::Value::Object(item),
+                _ => json!({ "json": item }),
+            };
+            pinned.push(item);
+        }
+
+        let mut workflow = self.fetch_workflow(&workflow_id).await?;
+        if workflow.node(&node_name).is_none() {
+            return Err(no_such_node(&workflow_id, &workflow, &node_name));
+        }
 
-#[derive(Deserialize, Serialize, JsonSchema, Default)]
-#[serde(rename = "snake_case")]
-#[serde(untagged)]
-pub enum AllOrNone {
-    #[default]
-    All,
-    None,
-}
+        let count = pinned.len();
+        let mut pin_data = workflow.pin_data();
+        pin_data.insert(node_name.clone(), json!(pinned));
+        workflow.set_pin_data(pin_data);
 
-impl fmt::Display for AllOrNone {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::All => write!(f, "all"),
-            Self::None => write!(f, "none"),
+        self.put_workflow_with_pin_data(&workflow_id, &workflow)
+            .await?;
+
+        let mut json_object = json!({
+            "workflowId": workflow_id,
+            "node": node_name,
+            "items": count,
+            "pinnedNodes": workflow.pin_data().keys().collect::<Vec<_>>(),
+        });
+        if workflow.extra.get("active") == Some(&json!(true)) {
+            json_object["warning"] = json!(
+                "The workflow is active, but pinned data is only used when it's run from the editor or a test webhook, not by production executions"
+            );
         }
-    }
-}
 
-#[derive(Deserialize, Serialize, JsonSchema)]
-#[serde(rename = "camelCase")]
-pub struct CreateWorkflowParams {
-    name: String,
-    nodes: serde_json::Value,
-    connections: serde_json::Value,
-    settings: WorkflowSettings,
-    static_data: Option<serde_json::Value>,
-}
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
 
-#[derive(Deserialize, Serialize, JsonSchema)]
-#[serde(rename = "camelCase")]
-#[serde(untagged)]
-pub enum ExecutionStatus {
-    Error,
-    Success,
-    Waiting,
-}
+        Ok(Content::text(json_as_string))
+    }
 
-#[tool(tool_box)]
-impl Server {
-    #[tool(description = "Retrieve all executions.")]
-    async fn retrieve_all_executions(
+    #[tool(
+        description = "Removes the test data pinned to a node in a workflow, or to every node in it, so the nodes run for real again. Do this before activating a workflow that was built against pinned data."
+    )]
+    async fn clear_pinned_data(
         &self,
         #[tool(param)]
-        #[schemars(description = "Whether or not to include the execution's detailed data.")]
-        include_data: bool,
+        #[schemars(description = "The ID of the workflow to clear pinned data from.")]
+        workflow_id: WorkflowId,
         #[tool(param)]
         #[schemars(
-            description = "The status of an execution. Can either be: 'error' | 'success' | 'waiting'"
+            description = "The name of the node to clear pinned data from. Optional - clears it from every node if not given."
         )]
-        status: ExecutionStatus,
-        #[tool(param)]
-        #[schemars(description = "Workflow ID to filter executions by. Optional.")]
-        workflow_id: Option<String>,
+        node_name: Option<String>,
+    ) -> Result<Content, ServerError> {
+        validate::id("workflow", &workflow_id)?;
+
+        let mut workflow = self.fetch_workflow(&workflow_id).await?;
+        let mut pin_data = workflow.pin_data();
+
+        let cleared: Vec<String> = match &node_name {
+            Some(node_name) => {
+                if workflow.node(node_name).is_none() && !pin_data.contains_key(node_name) {
+                    return Err(no_such_node(&workflow_id, &workflow, node_name));
+                }
+                pin_data
+                    .remove(node_name)
+                    .map(|_| node_name.clone())
+                    .into_iter()
+                    .collect()
+            }
+            None => std::mem::take(&mut pin_data)
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect(),
+        };
+
+        if !cleared.is_empty() {
+            workflow.set_pin_data(pin_data);
+            self.put_workflow_with_pin_data(&workflow_id, &workflow)
+                .await?;
+        }
+
+        let json_as_string = serde_json::to_string_pretty(&json!({
+            "workflowId": workflow_id,
+            "cleared": cleared,
+            "pinnedNodes": workflow.pin_data().keys().collect::<Vec<_>>(),
+        }))
+        .unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Changes some of the parameters of a single node in a workflow, keeping the rest of the node and workflow as-is.
+
+            Prefer this over updating the whole workflow for small changes, e.g. a URL or a message. The given parameters are merged into the node's existing ones: nested objects are merged too, other values (including lists) are replaced, and a parameter set to null is removed."
+    )]
+    async fn update_node_parameters(
+        &self,
         #[tool(param)]
-        #[schemars(description = "Project ID to filter executions by. Optional.")]
-        project_id: Option<String>,
+        #[schemars(description = "The ID of the workflow that the node belongs to.")]
+        workflow_id: WorkflowId,
         #[tool(param)]
-        #[schemars(
-            description = "The maximum number of items to return. The absolute maximum is 250 - if you go above this, you will receive an error."
-        )]
-        limit: u8,
+        #[schemars(description = "The name of the node, as shown in the workflow.")]
+        node_name: String,
         #[tool(param)]
         #[schemars(
-            description = "Page number, used for pagination. You can either set this to navigate the page, or leave it blank to get the first page."
+            description = "The parameters to change, e.g. {'url': 'https://api.example.com/v2', 'options': {'timeout': 5000}}."
         )]
-        cursor: String,
-    ) -> Result<CallToolResult, McpError> {
-        let url = format!("{}/api/v1/executions", self.base_url);
-
-        let json_object = json!({
-            "includeData": include_data,
-            "status": status,
-            "workflowId": workflow_id,
-            "projectId": project_id,
-            "limit": limit,
-            "cursor": cursor
-        });
+        parameters: serde_json::Value,
+    ) -> Result<Content, ServerError> {
+        validate::id("workflow", &workflow_id)?;
+        let serde_json::Value::Object(parameters) = parameters else {
+            return Err(ServerError::validation(
+                "parameters must be an object of the parameters to change",
+            ));
+        };
 
-        let res = self.client.get(url).query(&json_object).send().await;
+        let mut workflow = self.fetch_workflow(&workflow_id).await?;
 
-        let res = match res {
-            Ok(res) => res,
-            Err(err) => {
-                return Ok(CallToolResult::error(vec![Content::text(format!(
-                    "Workflow error: {err}"
-                ))]));
-            }
+        let Some(node) = workflow.node_mut(&node_name) else {
+            return Err(no_such_node(&workflow_id, &workflow, &node_name));
         };
 
-        // This should essentially never panic as the response from n8n should always be 100% correctly formatted JSON
-        let res = res.json::<serde_json::Value>().await.unwrap();
-        let json_as_string = serde_json::to_string_pretty(&res).unwrap();
+        workflow::merge_parameters(node.parameters.get_or_insert_default(), parameters);
+        let node = serde_json::to_value(&*node)?;
+
+        let res = self.put_workflow(&workflow_id, &workflow).await?;
+
+        let json_as_string = serde_json::to_string_pretty(&json!({
+            "node": node,
+            "workflow": res,
+        }))
+        .unwrap();
 
-        Ok(CallToolResult::success(vec![Content::text(json_as_string)]))
+        Ok(Content::text(json_as_string))
     }
 
-    #[tool(description = "Retrieve an execution by ID.")]
-    async fn retrieve_execution_by_id(
+    #[tool(
+        description = "Adds a node to an existing workflow, optionally wired in after and/or before other nodes, keeping the rest of the workflow as-is.
+
+            Given both after and before, the node is inserted between them, taking the place of any connection from one to the other. Unless the node has a position, it's placed next to the nodes it's wired to, moving later nodes right if there isn't room. Missing parameters, typeVersion and position are filled in as fill_node_defaults does.
+            Along with the updated workflow, returns the connections made and warnings about any connections or credentials that will stop parts of it from running."
+    )]
+    async fn add_node_to_workflow(
         &self,
         #[tool(param)]
-        #[schemars(description = "The execution ID to use.")]
-        execution_id: String,
-    ) -> Result<CallToolResult, McpError> {
-        let url = format!("{}/api/v1/workflows/{execution_id}", self.base_url);
+        #[schemars(description = "The ID of the workflow to add the node to.")]
+        workflow_id: WorkflowId,
+        #[tool(param)]
+        #[schemars(
+            description = "The node to add. Its name has to be unique within the workflow."
+        )]
+        node: Node,
+        #[tool(param)]
+        #[schemars(
+            description = "The name of the node whose output should go into the new node. Optional."
+        )]
+        after: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Which output of the after node to connect from, e.g. 1 for an If node's false branch. Only used when after isn't already connected to before. Defaults to 0."
+        )]
+        output_index: Option<usize>,
+        #[tool(param)]
+        #[schemars(
+            description = "The name of the node the new node's output should go into. Optional."
+        )]
+        before: Option<String>,
+    ) -> Result<Content, ServerError> {
+        validate::id("workflow", &workflow_id)?;
+        validate::not_empty("node name", &node.name)?;
 
-        let res = self.client.get(url).send().await;
+        let mut workflow = self.fetch_workflow(&workflow_id).await?;
 
-        let res = match res {
-            Ok(res) => res,
-            Err(err) => {
-                return Ok(CallToolResult::error(vec![Content::text(format!(
-                    "Workflow error: {err}"
-                ))]));
+        if workflow.node(&node.name).is_some() {
+            return Err(ServerError::validation(format!(
+                "Workflow {workflow_id} already has a node named '{}' - node names must be unique",
+                node.name
+            )));
+        }
+        for neighbor in [&after, &before].into_iter().flatten() {
+            if workflow.node(neighbor).is_none() {
+                return Err(no_such_node(&workflow_id, &workflow, neighbor));
             }
-        };
+        }
 
-        // This should essentially never panic as the response from n8n should always be 100% correctly formatted JSON
-        let res = res.json::<serde_json::Value>().await.unwrap();
-        let json_as_string = serde_json::to_string_pretty(&res).unwrap();
+        let node_name = node.name.clone();
+        let connected = workflow.insert_node(
+            node,
+            after.as_deref(),
+            output_index.unwrap_or(0),
+            before.as_deref(),
+        );
+        let filled =
+            workflow::fill_node_defaults(workflow.nodes.as_mut_slice(), &workflow.connections);
+        validate::nodes(&workflow.nodes)?;
+
+        let mut warnings = workflow::connection_warnings(&workflow.nodes, &workflow.connections);
+        warnings.extend(self.credential_warnings(&workflow.nodes).await);
+        let node = serde_json::to_value(workflow.node(&node_name))?;
+        let res = self.put_workflow(&workflow_id, &workflow).await?;
 
-        Ok(CallToolResult::success(vec![Content::text(json_as_string)]))
+        let json_as_string = serde_json::to_string_pretty(&json!({
+            "node": node,
+            "connected": connected,
+            "filled": filled,
+            "warnings": warnings,
+            "workflow": res,
+        }))
+        .unwrap();
+
+        Ok(Content::text(json_as_string))
     }
 
-    #[tool(description = "Deletes an execution by ID.")]
-    async fn delete_execution_by_id(
+    #[tool(
+        description = "Removes a node from a workflow, along with its connections, keeping the rest of the workflow as-is.
+
+            The nodes that fed into the removed node are connected to the nodes it fed into, so the flow isn't left broken - unless more than one of its outputs (e.g. an If node's branches) or inputs (e.g. a Merge node's) was in use, since then it isn't clear which should go where.
+            Along with the updated workflow, returns the connections made and warnings about any connections or credentials that will stop parts of it from running."
+    )]
+    async fn remove_node_from_workflow(
         &self,
         #[tool(param)]
-        #[schemars(description = "The execution ID to use.")]
-        execution_id: String,
-    ) -> Result<CallToolResult, McpError> {
-        let url = format!("{}/api/v1/workflows/{execution_id}", self.base_url);
+        #[schemars(description = "The ID of the workflow that the node belongs to.")]
+        workflow_id: WorkflowId,
+        #[tool(param)]
+        #[schemars(description = "The name of the node to remove, as shown in the workflow.")]
+        node_name: String,
+    ) -> Result<Content, ServerError> {
+        validate::id("workflow", &workflow_id)?;
 
-        let res = self.client.delete(url).send().await;
+        let mut workflow = self.fetch_workflow(&workflow_id).await?;
 
-        let res = match res {
-            Ok(res) => res,
-            Err(err) => {
-                return Ok(CallToolResult::error(vec![Content::text(format!(
-                    "Workflow error: {err}"
-                ))]));
-            }
-        };
+        if workflow.node(&node_name).is_none() {
+            return Err(no_such_node(&workflow_id, &workflow, &node_name));
+        }
 
-        // This should essentially never panic as the response from n8n should always be 100% correctly formatted JSON
-        let res = res.json::<serde_json::Value>().await.unwrap();
-        let json_as_string = serde_json::to_string_pretty(&res).unwrap();
+        let reconnected = workflow.remove_node(&node_name);
+
+        let mut warnings = workflow::connection_warnings(&workflow.nodes, &workflow.connections);
+        warnings.extend(self.credential_warnings(&workflow.nodes).await);
+        let res = self.put_workflow(&workflow_id, &workflow).await?;
 
-        Ok(CallToolResult::success(vec![Content::text(json_as_string)]))
+        let json_as_string = serde_json::to_string_pretty(&json!({
+            "removed": node_name,
+            "reconnected": reconnected,
+            "warnings": warnings,
+            "workflow": res,
+        }))
+        .unwrap();
+
+        Ok(Content::text(json_as_string))
     }
 
-    #[tool(description = "Create a new workflow.")]
-    async fn create_workflow(
+    #[tool(
+        description = "Changes where one of a node's outputs goes, keeping the rest of the workflow as-is - e.g. to send a node's error output to a Slack node instead of an email node.
+
+            Connects output output_index of from_node to input input_index of to_node. With instead_of, the output's connection to that node is removed first; with instead_of and no to_node, the connection is only removed.
+            Along with the updated workflow, returns warnings about any connections or credentials that will stop parts of it from running."
+    )]
+    async fn rewire_connection(
         &self,
         #[tool(param)]
-        #[schemars(description = "The name of your workflow.")]
-        name: String,
+        #[schemars(description = "The ID of the workflow to change.")]
+        workflow_id: WorkflowId,
         #[tool(param)]
-        #[schemars(description = "The nodes you want to use in your workflow.")]
-        nodes: serde_json::Value,
+        #[schemars(description = "The name of the node the connection comes out of.")]
+        from_node: String,
         #[tool(param)]
-        #[schemars(description = "The connections you want for your workflow.")]
-        connections: serde_json::Value,
-    ) -> Result<CallToolResult, rmcp::Error> {
-        let url = format!("{}/api/v1/workflows", self.base_url);
-
-        let settings = WorkflowSettings::default();
+        #[schemars(
+            description = "Which output of from_node, e.g. 1 for an If node's false branch, or for the error output of a node set to continue on error. Defaults to 0."
+        )]
+        output_index: Option<usize>,
+        #[tool(param)]
+        #[schemars(
+            description = "The name of the node the output should go into. Optional if instead_of is given."
+        )]
+        to_node: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Which input of to_node, e.g. 1 for a Merge node's second input. Defaults to 0."
+        )]
+        input_index: Option<u32>,
+        #[tool(param)]
+        #[schemars(
+            description = "The name of the node the output goes into now, whose connection should be removed. Optional."
+        )]
+        instead_of: Option<String>,
+    ) -> Result<Content, ServerError> {
+        validate::id("workflow", &workflow_id)?;
+        if to_node.is_none() && instead_of.is_none() {
+            return Err(ServerError::validation(
+                "Give to_node to connect the output to, instead_of to disconnect it from, or both",
+            ));
+        }
+        let output_index = output_index.unwrap_or(0);
 
-        let json_object = json!({
-            "name": name,
-            "nodes": nodes,
-            "connections": connections,
-            "settings": settings,
-            "staticData": "null"
-        });
+        let mut workflow = self.fetch_workflow(&workflow_id).await?;
 
-        let res = self.client.post(url).json(&json_object).send().await;
+        for node_name in [Some(&from_node), to_node.as_ref(), instead_of.as_ref()]
+            .into_iter()
+            .flatten()
+        {
+            if workflow.node(node_name).is_none() {
+                return Err(no_such_node(&workflow_id, &workflow, node_name));
+            }
+        }
 
-        let res = match res {
-            Ok(res) => res,
-            Err(err) => {
-                return Ok(CallToolResult::error(vec![Content::text(format!(
-                    "Workflow error: {err}"
-                ))]));
+        let mut changes = Vec::new();
+        if let Some(instead_of) = &instead_of {
+            let removed = workflow::disconnect(
+                &mut workflow.connections,
+                &from_node,
+                Some(output_index),
+                instead_of,
+            );
+            if removed.is_empty() {
+                return Err(ServerError::validation(format!(
+                    "Output {output_index} of '{from_node}' isn't connected to '{instead_of}'"
+                )));
             }
-        };
+            changes.push(format!(
+                "Disconnected output {output_index} of '{from_node}' from '{instead_of}'"
+            ));
+        }
+        if let Some(to_node) = &to_node {
+            let input_index = input_index.unwrap_or(0);
+            if workflow::connect(
+                &mut workflow.connections,
+                &from_node,
+                output_index,
+                to_node,
+                input_index,
+            ) {
+                changes.push(format!(
+                    "Connected output {output_index} of '{from_node}' to input {input_index} of '{to_node}'"
+                ));
+            } else {
+                changes.push(format!(
+                    "Output {output_index} of '{from_node}' was already connected to input {input_index} of '{to_node}'"
+                ));
+            }
+        }
 
-        // This should essentially never panic as the response from n8n should always be 100% correctly formatted JSON
-        let res = res.json::<serde_json::Value>().await.unwrap();
-        let json_as_string = serde_json::to_string_pretty(&res).unwrap();
+        let mut warnings = workflow::connection_warnings(&workflow.nodes, &workflow.connections);
+        warnings.extend(self.credential_warnings(&workflow.nodes).await);
+        let res = self.put_workflow(&workflow_id, &workflow).await?;
+
+        let json_as_string = serde_json::to_string_pretty(&json!({
+            "changes": changes,
+            "warnings": warnings,
+            "workflow": res,
+        }))
+        .unwrap();
 
-        Ok(CallToolResult::success(vec![Content::text(json_as_string)]))
+        Ok(Content::text(json_as_string))
     }
 
     #[tool(
-        description = "Retrieve all workflows (with optional parameters for filtering).
+        description = "Lists the saved previous versions of a workflow, most recent first. A version is saved just before each time a workflow is updated, deleted or archived through this server.
 
-            Note that in order for a returned workflow to be runnable, the first node of a workflow entry MUST be of type 'n8n-nodes-base.webhook'.
-            "
+            Without a workflow ID, lists every workflow that has saved versions, including deleted ones. Only available when a snapshot directory is configured (MCP_SNAPSHOT_DIR, or snapshot_dir in the [features] section of the config file)."
     )]
-    async fn retrieve_workflows(
+    async fn list_workflow_versions(
         &self,
         #[tool(param)]
         #[schemars(
-            description = "The parameters to fetch workflows by. If you leave all fields as blank, it will attempt to fetch everything.
-
-                Note that the pages can be navigated by adjusting the cursor value."
+            description = "The workflow to list the versions of. Optional - every workflow with saved versions is listed by default."
         )]
-        retrieve_workflow_params: RetrieveAllWorkflowParams,
-    ) -> Result<CallToolResult, McpError> {
-        let url = format!("{}/api/v1/workflows", self.base_url);
-
-        let res = self
-            .client
-            .get(url)
-            .query(&retrieve_workflow_params)
-            .send()
-            .await;
+        workflow_id: Option<WorkflowId>,
+    ) -> Result<Content, ServerError> {
+        validate::optional_id("workflow", workflow_id.as_deref())?;
+        let Some(snapshot_dir) = self.features.snapshot_dir.clone() else {
+            return Err(ServerError::validation(
+                "No snapshot directory is configured, so no versions of workflows are saved - set MCP_SNAPSHOT_DIR, or snapshot_dir in the [features] section of the config file (snapshots aren't available in multi-tenant mode)",
+            ));
+        };
 
-        let res = match res {
-            Ok(res) => res,
-            Err(err) => {
-                return Ok(CallToolResult::error(vec![Content::text(format!(
-                    "Workflow error: {err}"
-                ))]));
+        let json_object = match workflow_id {
+            Some(workflow_id) => {
+                let versions = snapshot::list(&snapshot_dir, &workflow_id).await?;
+                json!({
+                    "workflowId": workflow_id,
+                    "versions": versions,
+                })
+            }
+            None => {
+                let mut workflows = Vec::new();
+                for workflow_id in snapshot::workflow_ids(&snapshot_dir).await? {
+                    let versions = snapshot::list(&snapshot_dir, &workflow_id).await?;
+                    let Some(latest) = versions.first() else {
+                        continue;
+                    };
+                    workflows.push(json!({
+                        "id": workflow_id,
+                        "name": latest.name,
+                        "versions": versions.len(),
+                        "latest": latest.taken_at,
+                        "deleted": latest.reason == snapshot::Reason::Delete.to_string(),
+                    }));
+                }
+                json!({ "workflows": workflows })
             }
         };
 
-        // This should essentially never panic as the response from n8n should always be 100% correctly formatted JSON
-        let res = res.json::<serde_json::Value>().await.unwrap();
-        let json_as_string = serde_json::to_string_pretty(&res).unwrap();
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
 
-        Ok(CallToolResult::success(vec![Content::text(json_as_string)]))
+        Ok(Content::text(json_as_string))
     }
 
-    #[tool(description = "Retrieve the details of a single workflow by its ID.")]
-    async fn retrieve_workflow_by_id(
+    #[tool(
+        description = "Restores a workflow to one of its saved previous versions (see list_workflow_versions), replacing its name, nodes, connections, settings and static data.
+
+            Picks the version by index (0, the default, is the most recent) or by when it was taken. A workflow that has since been deleted is created again from the version, with a new ID. The workflow's current version is saved first, so a rollback can itself be undone. Only available when a snapshot directory is configured (MCP_SNAPSHOT_DIR, or snapshot_dir in the [features] section of the config file)."
+    )]
+    async fn rollback_workflow(
         &self,
         #[tool(param)]
-        #[schemars(description = "The workflow ID to fetch.")]
-        workflow_id: String,
-    ) -> Result<CallToolResult, McpError> {
-        let url = format!("{}/api/v1/workflows/{workflow_id}", self.base_url);
-
-        let res = self.client.get(url).send().await;
+        #[schemars(description = "The ID of the workflow to roll back.")]
+        workflow_id: WorkflowId,
+        #[tool(param)]
+        #[schemars(
+            description = "Which version to restore, as listed by list_workflow_versions: 0 is the most recent, 1 the one before it, and so on. Defaults to 0."
+        )]
+        index: Option<usize>,
+        #[tool(param)]
+        #[schemars(
+            description = "Which version to restore, by its takenAt timestamp or file as listed by list_workflow_versions. Use instead of index. Optional."
+        )]
+        taken_at: Option<String>,
+    ) -> Result<Content, ServerError> {
+        validate::id("workflow", &workflow_id)?;
+        if index.is_some() && taken_at.is_some() {
+            return Err(ServerError::validation(
+                "Give either index or taken_at to pick the version, not both",
+            ));
+        }
+        let Some(snapshot_dir) = self.features.snapshot_dir.clone() else {
+            return Err(ServerError::validation(
+                "No snapshot directory is configured, so no versions of workflows are saved - set MCP_SNAPSHOT_DIR, or snapshot_dir in the [features] section of the config file (snapshots aren't available in multi-tenant mode)",
+            ));
+        };
 
-        let res = match res {
-            Ok(res) => res,
-            Err(err) => {
-                return Ok(CallToolResult::error(vec![Content::text(format!(
-                    "Workflow error: {err}"
-                ))]));
-            }
+        let versions = snapshot::list(&snapshot_dir, &workflow_id).await?;
+        let version = match &taken_at {
+            Some(taken_at) => versions
+                .iter()
+                .find(|version| version.taken_at == *taken_at || version.file == *taken_at),
+            None => versions.get(index.unwrap_or(0)),
+        };
+        let Some(version) = version else {
+            return Err(ServerError::validation(match versions.len() {
+                0 => format!("There are no saved versions of workflow {workflow_id}"),
+                count => format!(
+                    "Workflow {workflow_id} has no saved version with that index or timestamp - list_workflow_versions lists its {count} versions"
+                ),
+            }));
         };
 
-        // This should essentially never panic as the response from n8n should always be 100% correctly formatted JSON
-        let res = res.json::<serde_json::Value>().await.unwrap();
-        let json_as_string = serde_json::to_string_pretty(&res).unwrap();
+        let mut workflow = snapshot::read(&snapshot_dir, &workflow_id, version).await?;
+        workflow.id = Some(workflow_id.clone());
+        let mut json_object = self.import_workflow(workflow, ImportMatch::Id).await?;
+        json_object["restoredVersion"] = json!(version);
+        if json_object["action"] == "created" {
+            json_object["note"] = json!(format!(
+                "Workflow {workflow_id} no longer existed, so it was created again with a new ID. Its earlier versions are still listed under {workflow_id}"
+            ));
+        }
 
-        Ok(CallToolResult::success(vec![Content::text(json_as_string)]))
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
     }
 
-    #[tool(description = "Delete a single workflow by its ID.")]
-    async fn delete_workflow_by_id(
+    #[tool(description = "Gets the static data of a single workflow by ID.
+
+            Static data is where trigger nodes keep their state between executions (e.g. the last time a polling trigger ran).")]
+    async fn get_workflow_static_data(
         &self,
         #[tool(param)]
         #[schemars(description = "The workflow ID to use.")]
-        workflow_id: String,
-    ) -> Result<CallToolResult, McpError> {
-        let url = format!("{}/api/v1/workflows/{workflow_id}", self.base_url);
-
-        let res = self.client.delete(url).send().await;
+        workflow_id: WorkflowId,
+    ) -> Result<Content, ServerError> {
+        validate::id("workflow", &workflow_id)?;
 
-        let res = match res {
-            Ok(res) => res,
-            Err(err) => {
-                return Ok(CallToolResult::error(vec![Content::text(format!(
-                    "Workflow error: {err}"
-                ))]));
-            }
-        };
+        let workflow = self.fetch_workflow(&workflow_id).await?;
 
-        // This should essentially never panic as the response from n8n should always be 100% correctly formatted JSON
-        let res = res.json::<serde_json::Value>().await.unwrap();
-        let json_as_string = serde_json::to_string_pretty(&res).unwrap();
+        let json_as_string = serde_json::to_string_pretty(&workflow.static_data).unwrap();
 
-        Ok(CallToolResult::success(vec![Content::text(json_as_string)]))
+        Ok(Content::text(json_as_string))
     }
 
-    #[tool(description = "Activates a single workflow by ID.")]
-    async fn activate_workflow_by_id(
+    #[tool(description = "Replaces the static data of a single workflow by ID.
+
+            Only do this if the user has explicitly asked for it, as it resets the state of the workflow's trigger nodes.")]
+    async fn set_workflow_static_data(
         &self,
         #[tool(param)]
         #[schemars(description = "The workflow ID to use.")]
-        workflow_id: String,
-    ) -> Result<CallToolResult, McpError> {
-        let url = format!("{}/api/v1/workflows/{workflow_id}/activate", self.base_url);
+        workflow_id: WorkflowId,
+        #[tool(param)]
+        #[schemars(
+            description = "The static data to store on the workflow. Use null to clear it."
+        )]
+        static_data: serde_json::Value,
+    ) -> Result<Content, ServerError> {
+        validate::id("workflow", &workflow_id)?;
 
-        let res = self.client.post(url).send().await;
+        let mut workflow = self.fetch_workflow(&workflow_id).await?;
 
-        let res = match res {
-            Ok(res) => res,
-            Err(err) => {
-                return Ok(CallToolResult::error(vec![Content::text(format!(
-                    "Workflow error: {err}"
-                ))]));
-            }
-        };
+        workflow.static_data = static_data;
 
-        // This should essentially never panic as the response from n8n should always be 100% correctly formatted JSON
-        let res = res.json::<serde_json::Value>().await.unwrap();
-        let json_as_string = serde_json::to_string_pretty(&res).unwrap();
+        let res = self.put_workflow(&workflow_id, &workflow).await?;
+
+        let json_as_string = serde_json::to_string_pretty(&res["staticData"]).unwrap();
 
-        Ok(CallToolResult::success(vec![Content::text(json_as_string)]))
+        Ok(Content::text(json_as_string))
     }
 
-    #[tool(description = "Deactivates a single workflow by ID.")]
-    async fn deactivate_workflow_by_id(
+    #[tool(description = "Gets the tags of a single workflow by ID.")]
+    async fn get_workflow_tags_by_workflow_id(
         &self,
         #[tool(param)]
         #[schemars(description = "The workflow ID to use.")]
-        workflow_id: String,
-    ) -> Result<CallToolResult, McpError> {
-        let url = format!(
-            "{}/api/v1/workflows/{workflow_id}/deactivate",
-            self.base_url
-        );
+        workflow_id: WorkflowId,
+    ) -> Result<Content, ServerError> {
+        validate::id("workflow", &workflow_id)?;
 
-        let res = self.client.post(url).send().await;
+        let url = self.url(Endpoint::WorkflowTags(&workflow_id));
 
-        let res = match res {
-            Ok(res) => res,
-            Err(err) => {
-                return Ok(CallToolResult::error(vec![Content::text(format!(
-                    "Workflow error: {err}"
-                ))]));
-            }
-        };
+        let res = self.client().get(url).send_logged(&self.logger).await?;
 
-        // This should essentially never panic as the response from n8n should always be 100% correctly formatted JSON
-        let res = res.json::<serde_json::Value>().await.unwrap();
+        let res = response::json(res).await?;
         let json_as_string = serde_json::to_string_pretty(&res).unwrap();
 
-        Ok(CallToolResult::success(vec![Content::text(json_as_string)]))
+        Ok(Content::text(json_as_string))
     }
 
-    #[tool(description = "Updates a workflow.")]
-    async fn update_workflow_by_id(
+    #[tool(description = "Updates the tags of a single workflow to the provided tags.")]
+    async fn update_workflow_tags_by_workflow_id(
         &self,
         #[tool(param)]
-        #[schemars(description = "The ID of the workflow to be updated.")]
-        workflow_id: String,
+        #[schemars(description = "The workflow ID to use.")]
+        workflow_id: WorkflowId,
         #[tool(param)]
-        #[schemars(description = "The name of your workflow.")]
-        name: String,
+        #[schemars(description = "The IDs of the tags to assign to this workflow.")]
+        tags: Vec<Id>,
+    ) -> Result<Content, ServerError> {
+        validate::id("workflow", &workflow_id)?;
+
+        if self.policies.iter().any(|policy| policy.checks_tags()) {
+            let existing_tags = self.fetch_all_tags().await?;
+            // Tags that don't exist are left for n8n to reject
+            let tag_names: Vec<&str> = tags
+                .iter()
+                .filter_map(|tag| existing_tags.iter().find(|existing| existing.id == tag.id))
+                .map(|tag| tag.name.as_str())
+                .collect();
+            self.enforce_tag_policies(&workflow_id, None, &tag_names)
+                .await?;
+        }
+
+        let url = self.url(Endpoint::WorkflowTags(&workflow_id));
+
+        let res = self
+            .client()
+            .put(url)
+            .json(&json!(tags))
+            .send_logged(&self.logger)
+            .await?;
+
+        let res = response::json(res).await?;
+        let json_as_string = serde_json::to_string_pretty(&res).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Sets the tags of a single workflow by tag name, creating any tags that don't exist yet.
+
+            Note that this replaces the workflow's current tags - include any existing tags you want to keep."
+    )]
+    async fn tag_workflow_by_names(
+        &self,
         #[tool(param)]
-        #[schemars(description = "The nodes you want to use in your workflow.")]
-        nodes: serde_json::Value,
+        #[schemars(description = "The workflow ID to use.")]
+        workflow_id: WorkflowId,
         #[tool(param)]
-        #[schemars(description = "The connections you want for your workflow.")]
-        connections: serde_json::Value,
-    ) -> Result<CallToolResult, rmcp::Error> {
-        let url = format!("{}/api/v1/workflows/{workflow_id}", self.base_url);
+        #[schemars(description = "The names of the tags to assign to this workflow.")]
+        tag_names: Vec<String>,
+    ) -> Result<Content, ServerError> {
+        validate::id("workflow", &workflow_id)?;
+
+        // n8n trims tag names, so names that only differ by surrounding whitespace are the same tag
+        let mut names: Vec<&str> = Vec::new();
+        for tag_name in &tag_names {
+            if !names.contains(&tag_name.trim()) {
+                names.push(tag_name.trim());
+            }
+        }
+
+        // Checked before any tags are created, so a blocked change doesn't leave new tags behind
+        self.enforce_tag_policies(&workflow_id, None, &names)
+            .await?;
+
+        let mut existing_tags = self.fetch_all_tags().await?;
 
-        let settings = WorkflowSettings::default();
+        let mut tag_ids = Vec::new();
 
-        let json_object = json!({
-            "name": name,
-            "nodes": nodes,
-            "connections": connections,
-            "settings": settings,
-            "staticData": "null"
-        });
+        for tag_name in names {
+            let tag = match tag::find(&existing_tags, tag_name) {
+                Some(tag) => tag.clone(),
+                None => {
+                    let tag = self.create_tag_named(tag_name).await?;
+                    existing_tags.push(tag.clone());
+                    tag
+                }
+            };
 
-        let res = self.client.put(url).json(&json_object).send().await;
+            tag_ids.push(json!({"id": tag.id}));
+        }
 
-        let res = match res {
-            Ok(res) => res,
-            Err(err) => {
-                return Ok(CallToolResult::error(vec![Content::text(format!(
-                    "Workflow error: {err}"
-                ))]));
-            }
-        };
+        let url = self.url(Endpoint::WorkflowTags(&workflow_id));
+
+        let res = self
+            .client()
+            .put(url)
+            .json(&tag_ids)
+            .send_logged(&self.logger)
+            .await?;
 
-        // This should essentially never panic as the response from n8n should always be 100% correctly formatted JSON
-        let res = res.json::<serde_json::Value>().await.unwrap();
+        let res = response::json(res).await?;
         let json_as_string = serde_json::to_string_pretty(&res).unwrap();
 
-        Ok(CallToolResult::success(vec![Content::text(json_as_string)]))
+        Ok(Content::text(json_as_string))
     }
 
-    #[tool(description = "Gets the tags of a single workflow by ID.")]
-    async fn get_workflow_tags_by_workflow_id(
+    #[tool(description = "Run a workflow.
+
+            If you don't have a workflow ID to use, retrieve all workflows and search for an appropriate
+            workflow to run (according to the user's prompt.)
+            With wait_for_result, waits for the execution the webhook call started to finish and returns it, with what its last node output, so the result of the run can be checked.")]
+    async fn run_workflow(
         &self,
         #[tool(param)]
-        #[schemars(description = "The workflow ID to use.")]
-        workflow_id: String,
-    ) -> Result<CallToolResult, McpError> {
-        let url = format!("{}/api/v1/workflows/{workflow_id}/tags", self.base_url);
+        #[schemars(description = "The path of the webhook (that belongs to the workflow to run).")]
+        webhook_path: String,
+        #[tool(param)]
+        #[schemars(
+            description = "The data to pass to the webhook. If the user has not explicitly asked for data to be sent, leave this as None."
+        )]
+        data: Option<serde_json::Value>,
+        #[tool(param)]
+        #[schemars(
+            description = "Set to true to wait for the run to finish and return its execution and output, rather than just whether the webhook call was accepted. Defaults to false."
+        )]
+        wait_for_result: Option<bool>,
+        #[tool(param)]
+        #[schemars(
+            description = "The ID of the workflow the webhook belongs to, for wait_for_result. Optional - found from the webhook path if not given."
+        )]
+        workflow_id: Option<WorkflowId>,
+        #[tool(param)]
+        #[schemars(
+            description = "How long to wait for the run to finish with wait_for_result, in seconds, up to 300. Defaults to 60."
+        )]
+        timeout_seconds: Option<u64>,
+    ) -> Result<Content, ServerError> {
+        validate::not_empty("The webhook path", &webhook_path)?;
+        validate::optional_id("workflow", workflow_id.as_deref())?;
+        let timeout = run_timeout(timeout_seconds)?;
 
-        let res = self.client.get(url).send().await;
+        if wait_for_result.unwrap_or(false) {
+            let workflow_id = match workflow_id {
+                Some(workflow_id) => workflow_id,
+                None => self.find_webhook_workflow(&webhook_path).await?,
+            };
+            let json_object = self
+                .run_workflow_and_wait(&webhook_path, data, workflow_id, timeout)
+                .await?;
+            let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
 
-        let res = match res {
-            Ok(res) => res,
-            Err(err) => {
-                return Ok(CallToolResult::error(vec![Content::text(format!(
-                    "Workflow error: {err}"
-                ))]));
-            }
-        };
+            return Ok(Content::text(json_as_string));
+        }
 
-        // This should essentially never panic as the response from n8n should always be 100% correctly formatted JSON
-        let res = res.json::<serde_json::Value>().await.unwrap();
-        let json_as_string = serde_json::to_string_pretty(&res).unwrap();
+        self.call_webhook(Endpoint::Webhook(&webhook_path), data)
+            .await?;
 
-        Ok(CallToolResult::success(vec![Content::text(json_as_string)]))
+        Ok(Content::text("Workflow run successful"))
     }
 
-    #[tool(description = "Updates the tags of a single workflow to the provided tags.")]
-    async fn update_workflow_tags_by_workflow_id(
+    #[tool(
+        description = "Run a workflow through the test URL of its webhook, so workflows that aren't active yet can be tried out while they're being built.
+
+            n8n only listens on the test URL after \"Listen for test event\" is clicked on the Webhook node (or \"Test workflow\" in the editor), and then only for one call. Before calling this, ask the user to open the workflow in n8n and click it, and wait for them to say they have. The run shows up in the editor rather than in the execution list.
+            Returns whatever the webhook responded with."
+    )]
+    async fn run_workflow_test(
         &self,
         #[tool(param)]
-        #[schemars(description = "The workflow ID to use.")]
-        workflow_id: String,
+        #[schemars(description = "The path of the webhook (that belongs to the workflow to run).")]
+        webhook_path: String,
         #[tool(param)]
-        #[schemars(description = "The IDs of the tags to assign to this workflow.")]
-        tags: Vec<Id>,
-    ) -> Result<CallToolResult, McpError> {
-        let url = format!("{}/api/v1/workflows/{workflow_id}/tags", self.base_url);
-
-        let res = self.client.put(url).json(&json!(tags)).send().await;
+        #[schemars(
+            description = "The data to pass to the webhook. If the user has not explicitly asked for data to be sent, leave this as None."
+        )]
+        data: Option<serde_json::Value>,
+    ) -> Result<Content, ServerError> {
+        validate::not_empty("The webhook path", &webhook_path)?;
 
-        let res = match res {
+        let res = match self
+            .call_webhook(Endpoint::TestWebhook(&webhook_path), data)
+            .await
+        {
             Ok(res) => res,
-            Err(err) => {
-                return Ok(CallToolResult::error(vec![Content::text(format!(
-                    "Workflow error: {err}"
-                ))]));
+            Err(err) if err.status() == Some(reqwest::StatusCode::NOT_FOUND) => {
+                return Err(ServerError::Detailed(json!({
+                    "error": format!("n8n isn't listening for a test event on the webhook path '{webhook_path}'"),
+                    "hint": "Ask the user to open the workflow in the n8n editor and click \"Listen for test event\" on its Webhook node, then try again straight away. The test URL only accepts one call each time it's clicked, and stops listening after about two minutes. Check the path and HTTP method set on the node too",
+                })));
             }
+            Err(err) => return Err(err),
         };
 
-        // This should essentially never panic as the response from n8n should always be 100% correctly formatted JSON
-        let res = res.json::<serde_json::Value>().await.unwrap();
-        let json_as_string = serde_json::to_string_pretty(&res).unwrap();
+        let body = res.text().await?;
+        // Webhooks can respond with anything, so a body that isn't JSON is kept as text
+        let webhook_response: serde_json::Value =
+            serde_json::from_str(&body).unwrap_or(json!(body));
+        let json_as_string = serde_json::to_string_pretty(&json!({
+            "webhookPath": webhook_path,
+            "webhookResponse": webhook_response,
+        }))
+        .unwrap();
 
-        Ok(CallToolResult::success(vec![Content::text(json_as_string)]))
+        Ok(Content::text(json_as_string))
     }
 
-    #[tool(description = "Run a workflow.
+    #[tool(
+        description = "Re-run an execution with the same input, optionally changed, by calling the webhook that started it again - e.g. to retry a failed execution after fixing the record that broke it.
 
-            If you don't have a workflow ID to use, retrieve all workflows and search for an appropriate
-            workflow to run (according to the user's prompt.)")]
-    async fn run_workflow(
+            The body the webhook was called with is taken from the execution (its headers and query aren't sent again), and overrides are merged into it: nested objects are merged too, other values (including lists) are replaced, and a field set to null is removed. Only executions started by a webhook of a workflow that's still active can be re-run.
+            Waits for the new execution to finish and returns it, with what its last node output, unless wait_for_result is false."
+    )]
+    async fn rerun_execution(
         &self,
         #[tool(param)]
-        #[schemars(description = "The path of the webhook (that belongs to the workflow to run).")]
-        webhook_path: String,
+        #[schemars(description = "The ID of the execution to re-run, usually one that failed.")]
+        execution_id: ExecutionId,
         #[tool(param)]
         #[schemars(
-            description = "The data to pass to the webhook. If the user has not explicitly asked for data to be sent, leave this as None."
+            description = "Fields to change in the body the webhook was called with, e.g. {'customer': {'email': 'fixed@example.com'}}. Optional."
         )]
-        data: Option<serde_json::Value>,
-    ) -> Result<CallToolResult, rmcp::Error> {
-        let url = format!("{}/webhook/{webhook_path}", self.base_url);
+        overrides: Option<serde_json::Value>,
+        #[tool(param)]
+        #[schemars(
+            description = "Set to false to return as soon as the webhook call is accepted, rather than waiting for the new execution to finish. Defaults to true."
+        )]
+        wait_for_result: Option<bool>,
+        #[tool(param)]
+        #[schemars(
+            description = "How long to wait for the new execution to finish, in seconds, up to 300. Defaults to 60."
+        )]
+        timeout_seconds: Option<u64>,
+    ) -> Result<Content, ServerError> {
+        validate::id("execution", &execution_id)?;
+        let overrides = match overrides {
+            Some(serde_json::Value::Object(overrides)) => Some(overrides),
+            Some(_) => {
+                return Err(ServerError::validation(
+                    "overrides must be an object of the fields to change",
+                ));
+            }
+            None => None,
+        };
+        let timeout = run_timeout(timeout_seconds)?;
 
-        let res = if let Some(data) = data {
-            self.client.post(url).json(&data).send().await
+        let execution = self.fetch_execution(&execution_id).await?;
+        let Some(workflow_id) = execution.workflow_id.clone() else {
+            return Err(ServerError::validation(format!(
+                "Execution {execution_id} doesn't say which workflow it belongs to, so it can't be re-run"
+            )));
+        };
+        let Some((trigger, item)) = execution.trigger_item() else {
+            return Err(ServerError::validation(format!(
+                "The input of execution {execution_id} can't be found - its data may not have been saved, so it can't be re-run"
+            )));
+        };
+
+        let workflow = self.fetch_workflow(&workflow_id).await?;
+        let triggers = workflow::trigger_summary(&workflow.nodes);
+        let Some(webhook) = triggers
+            .webhooks
+            .iter()
+            .find(|webhook| webhook.node == trigger)
+        else {
+            return Err(ServerError::validation(format!(
+                "Execution {execution_id} was started by '{trigger}', which isn't an enabled webhook trigger of workflow {workflow_id}. Only executions started by a webhook can be re-run"
+            )));
+        };
+        if workflow.extra.get("active") != Some(&json!(true)) {
+            return Err(ServerError::validation(format!(
+                "Workflow {workflow_id} isn't active, so its webhook can't be called. Activate it first"
+            )));
+        }
+
+        // A webhook trigger outputs the request it was called with, with the body under `body`
+        let mut data = item.get("body").cloned().unwrap_or(serde_json::Value::Null);
+        if let Some(overrides) = overrides {
+            let serde_json::Value::Object(body) = &mut data else {
+                return Err(ServerError::validation(format!(
+                    "The body of the webhook call that started execution {execution_id} isn't an object, so it can't have fields overridden"
+                )));
+            };
+            workflow::merge_parameters(body, overrides);
+        }
+        // Webhooks are called with a POST when there's a body and a GET when there isn't
+        let data = match (webhook.method.as_str(), data) {
+            ("POST", serde_json::Value::Null) => Some(json!({})),
+            ("POST", data) => Some(data),
+            ("GET", serde_json::Value::Null) => None,
+            ("GET", serde_json::Value::Object(body)) if body.is_empty() => None,
+            ("GET", _) => {
+                return Err(ServerError::validation(format!(
+                    "'{trigger}' only accepts GET requests, which can't have a body"
+                )));
+            }
+            (method, _) => {
+                return Err(ServerError::validation(format!(
+                    "'{trigger}' accepts {method} requests, but webhooks can only be called with GET or POST"
+                )));
+            }
+        };
+
+        let mut json_object = if wait_for_result.unwrap_or(true) {
+            self.run_workflow_and_wait(&webhook.path, data.clone(), workflow_id, timeout)
+                .await?
         } else {
-            self.client.get(url).send().await
+            self.call_webhook(Endpoint::Webhook(&webhook.path), data.clone())
+                .await?;
+            json!({
+                "workflowId": workflow_id,
+                "message": "Workflow run successful",
+            })
         };
+        json_object["rerunOf"] = json!(execution.summary());
+        json_object["sentData"] = json!(data);
 
-        match res {
-            Ok(_res) => Ok(CallToolResult::success(vec![Content::text(
-                "Workflow run successful",
-            )])),
-            Err(err) => Ok(CallToolResult::error(vec![Content::text(format!(
-                "Workflow error: {err}"
-            ))])),
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
+    }
+
+    #[tool(
+        description = "Retrieve the license plan, feature entitlements and active workflow limits of the n8n instance.
+
+            The plan and limits come from endpoints the n8n editor uses, which don't accept the API key. The limit is only known when n8n accepts the server's basic auth credentials (N8N_USER and N8N_PASSWORD) or an editor session for them; otherwise activeWorkflowLimit is 'unknown', so ask the user for the instance's limit if it matters. The count of active workflows always comes from the API, up to 5000."
+    )]
+    async fn get_license_info(&self) -> Result<Content, ServerError> {
+        // The public API doesn't expose licensing, but the settings the n8n editor loads on startup do, and license
+        // usage is there for editor sessions
+        let settings = self.editor_data(Endpoint::Settings).await;
+        let license = self.editor_data(Endpoint::License).await;
+
+        let query = Query::new().set("active", true).set("limit", MAX_PAGE_SIZE);
+        let active_workflows: Paginated<serde_json::Value> = self
+            .fetch_all_pages(Endpoint::Workflows, query, None)
+            .await?;
+
+        let active_workflow_limit = &license["usage"]["activeWorkflowTriggers"]["limit"];
+
+        let mut json_object = json!({
+            "plan": license["license"]["planName"]
+                .as_str()
+                .or(settings["license"]["planName"].as_str()),
+            "environment": settings["license"]["environment"],
+            "activeWorkflows": active_workflows.data.len(),
+            // n8n uses -1 to mean "unlimited"
+            "activeWorkflowLimit": match active_workflow_limit.as_i64() {
+                Some(-1) => json!("unlimited"),
+                Some(limit) => json!(limit),
+                None => json!("unknown"),
+            },
+            "features": settings["enterprise"],
+        });
+        if active_workflow_limit.is_null() {
+            json_object["activeWorkflowLimitHint"] = json!(
+                "n8n only reports the limit to its editor, which needs basic auth credentials (N8N_USER and N8N_PASSWORD) or an editor session rather than the API key. Ask the user for the instance's limit"
+            );
         }
+        if active_workflows.next_cursor.is_some() {
+            json_object["warning"] = json!(format!(
+                "Only the first {MAX_FETCH_ALL_PAGES} pages of active workflows were counted, so there are more than activeWorkflows"
+            ));
+        }
+
+        let json_as_string = serde_json::to_string_pretty(&json_object).unwrap();
+
+        Ok(Content::text(json_as_string))
     }
 
-    #[tool(description = "Create a tag.")]
+    #[tool(description = "Create a tag.
+
+            Tag names are unique, so if there's already a tag with this name, that tag is returned instead.")]
     async fn create_tag(
         &self,
         #[tool(param)]
         #[schemars(description = "The name to use.")]
         name: String,
-    ) -> Result<CallToolResult, McpError> {
-        let url = format!("{}/tags", self.base_url);
-
-        let res = self
-            .client
-            .post(url)
-            .json(&json!({"name": name}))
-            .send()
-            .await;
+    ) -> Result<Content, ServerError> {
+        validate::not_empty("The tag name", &name)?;
 
-        let res = match res {
-            Ok(res) => res,
-            Err(err) => {
-                return Ok(CallToolResult::error(vec![Content::text(format!(
-                    "Workflow error: {err}"
-                ))]));
-            }
+        let existing_tags = self.fetch_all_tags().await?;
+        let tag = match tag::find(&existing_tags, &name) {
+            Some(tag) => tag.clone(),
+            None => self.create_tag_named(&name).await?,
         };
 
-        // This should essentially never panic as the response from n8n should always be 100% correctly formatted JSON
-        let res = res.json::<serde_json::Value>().await.unwrap();
-        let json_as_string = serde_json::to_string_pretty(&res).unwrap();
+        let json_as_string = serde_json::to_string_pretty(&tag).unwrap();
 
-        Ok(CallToolResult::success(vec![Content::text(json_as_string)]))
+        Ok(Content::text(json_as_string))
     }
 
     #[tool(description = "Retrieve all tags.")]
@@ -588,33 +5941,35 @@ impl Server {
         &self,
         #[tool(param)]
         #[schemars(
-            description = "The cursor to be used for navigating between pages. Note that this isn't provided by the user - to get the next cursor you have to run this function first."
+            description = "The nextCursor from the previous page, to get the page after it. Leave it out to get the first page."
         )]
         cursor: Option<String>,
-    ) -> Result<CallToolResult, McpError> {
-        let url = format!("{}/tags", self.base_url);
+        #[tool(param)]
+        #[schemars(
+            description = "Whether to follow the cursor through every page and return all the results at once, instead of one page. Stops after 20 pages, in which case nextCursor is set so you can carry on from there. Optional."
+        )]
+        fetch_all: Option<bool>,
+    ) -> Result<Content, ServerError> {
+        if let Some(cursor) = &cursor {
+            validate::cursor(cursor)?;
+        }
 
-        let res = self
-            .client
-            .post(url)
-            .query(&json!({"cursor": cursor}))
-            .send()
-            .await;
+        if fetch_all.unwrap_or_default() {
+            let res: Paginated<Tag> = self
+                .fetch_all_pages(
+                    Endpoint::Tags,
+                    Query::new().set("limit", MAX_PAGE_SIZE),
+                    cursor,
+                )
+                .await?;
 
-        let res = match res {
-            Ok(res) => res,
-            Err(err) => {
-                return Ok(CallToolResult::error(vec![Content::text(format!(
-                    "Workflow error: {err}"
-                ))]));
-            }
-        };
+            return output::structured(&res);
+        }
 
-        // This should essentially never panic as the response from n8n should always be 100% correctly formatted JSON
-        let res = res.json::<serde_json::Value>().await.unwrap();
-        let json_as_string = serde_json::to_string_pretty(&res).unwrap();
+        let query = Query::new().set_optional("cursor", cursor);
+        let res: Paginated<Tag> = self.fetch_page(Endpoint::Tags, &query).await?;
 
-        Ok(CallToolResult::success(vec![Content::text(json_as_string)]))
+        output::structured(&res)
     }
 
     #[tool(description = "Retrieve a tag by ID.")]
@@ -622,26 +5977,17 @@ impl Server {
         &self,
         #[tool(param)]
         #[schemars(description = "The tag ID to use.")]
-        tag_id: String,
-    ) -> Result<CallToolResult, McpError> {
-        let url = format!("{}/tags/{tag_id}", self.base_url);
+        tag_id: TagId,
+    ) -> Result<Content, ServerError> {
+        validate::id("tag", &tag_id)?;
 
-        let res = self.client.get(url).send().await;
+        let url = self.url(Endpoint::Tag(&tag_id));
 
-        let res = match res {
-            Ok(res) => res,
-            Err(err) => {
-                return Ok(CallToolResult::error(vec![Content::text(format!(
-                    "Workflow error: {err}"
-                ))]));
-            }
-        };
+        let res = self.client().get(url).send_logged(&self.logger).await?;
 
-        // This should essentially never panic as the response from n8n should always be 100% correctly formatted JSON
-        let res = res.json::<serde_json::Value>().await.unwrap();
-        let json_as_string = serde_json::to_string_pretty(&res).unwrap();
+        let res = response::json(res).await?;
 
-        Ok(CallToolResult::success(vec![Content::text(json_as_string)]))
+        output::structured(&res)
     }
 
     #[tool(description = "Delete a tag by its ID.")]
@@ -649,26 +5995,18 @@ impl Server {
         &self,
         #[tool(param)]
         #[schemars(description = "The ID of the tag to delete.")]
-        tag_id: String,
-    ) -> Result<CallToolResult, McpError> {
-        let url = format!("{}/tags/{tag_id}", self.base_url);
+        tag_id: TagId,
+    ) -> Result<Content, ServerError> {
+        validate::id("tag", &tag_id)?;
 
-        let res = self.client.delete(url).send().await;
+        let url = self.url(Endpoint::Tag(&tag_id));
 
-        let res = match res {
-            Ok(res) => res,
-            Err(err) => {
-                return Ok(CallToolResult::error(vec![Content::text(format!(
-                    "Workflow error: {err}"
-                ))]));
-            }
-        };
+        let res = self.client().delete(url).send_logged(&self.logger).await?;
 
-        // This should essentially never panic as the response from n8n should always be 100% correctly formatted JSON
-        let res = res.json::<serde_json::Value>().await.unwrap();
+        let res = response::json(res).await?;
         let json_as_string = serde_json::to_string_pretty(&res).unwrap();
 
-        Ok(CallToolResult::success(vec![Content::text(json_as_string)]))
+        Ok(Content::text(json_as_string))
     }
 
     #[tool(description = "Updates a tag by its ID.")]
@@ -676,49 +6014,55 @@ impl Server {
         &self,
         #[tool(param)]
         #[schemars(description = "The tag ID to use.")]
-        tag_id: String,
+        tag_id: TagId,
         #[tool(param)]
         #[schemars(description = "The name to use.")]
         name: String,
-    ) -> Result<CallToolResult, McpError> {
-        let url = format!("{}/tags/{tag_id}", self.base_url);
+    ) -> Result<Content, ServerError> {
+        validate::id("tag", &tag_id)?;
+        validate::not_empty("The tag name", &name)?;
+
+        let existing_tags = self.fetch_all_tags().await?;
+        if let Some(tag) = tag::find(&existing_tags, &name).filter(|tag| tag.id != tag_id) {
+            return Err(ServerError::validation(format!(
+                "There's already a tag called '{}' (with the ID {}), and tag names must be unique",
+                tag.name, tag.id
+            )));
+        }
+
+        let url = self.url(Endpoint::Tag(&tag_id));
 
         let res = self
-            .client
+            .client()
             .put(url)
             .json(&json!({"name": name}))
-            .send()
-            .await;
-
-        let res = match res {
-            Ok(res) => res,
-            Err(err) => {
-                return Ok(CallToolResult::error(vec![Content::text(format!(
-                    "Workflow error: {err}"
-                ))]));
-            }
-        };
+            .send_logged(&self.logger)
+            .await?;
 
-        // This should essentially never panic as the response from n8n should always be 100% correctly formatted JSON
-        let res = res.json::<serde_json::Value>().await.unwrap();
+        let res = response::json(res).await?;
         let json_as_string = serde_json::to_string_pretty(&res).unwrap();
 
-        Ok(CallToolResult::success(vec![Content::text(json_as_string)]))
+        Ok(Content::text(json_as_string))
     }
 }
 
-#[tool(tool_box)]
 impl ServerHandler for Server {
     fn get_info(&self) -> ServerInfo {
-        ServerInfo {
-            protocol_version: ProtocolVersion::V_2024_11_05,
-            capabilities: ServerCapabilities::builder()
-                .enable_prompts()
-                .enable_resources()
-                .enable_tools()
-                .build(),
-            server_info: Implementation::from_build_env(),
-            instructions: Some("This server provides a tool that can interact with a n8n server.
+        let mut capabilities = ServerCapabilities::builder()
+            .enable_logging()
+            .enable_prompts()
+            .enable_resources()
+            .enable_resources_subscribe()
+            .enable_resources_list_changed()
+            .enable_tools()
+            .build();
+        if !self.features.resource_subscriptions
+            && let Some(resources) = &mut capabilities.resources
+        {
+            resources.subscribe = None;
+        }
+
+        let mut instructions = "This server provides a tool that can interact with a n8n server.
 
                 n8n (or 'node-mation') is a service for creating automation that can either be used on n8n's cloud offfering or self-hosted.
                 Using this server, users can create, retrieve (in bulk and by id), update and delete workflows and retrieve the tags for a given workflow.
@@ -726,13 +6070,341 @@ impl ServerHandler for Server {
 
                 Users can also additionally retrieve (in bulk and by id), create, update and delete tags.
 
+                Workflows are also available as resources, with URIs of the form n8n://workflow/{id}.
+                Recent executions are available as resources too (n8n://execution/{id}), and can be subscribed to in order to be notified when they finish.
+                Any workflow, execution or tag can be read directly by filling in the n8n://workflow/{id}, n8n://execution/{id} and n8n://tag/{id} resource templates.
+
+                There are also prompts for common tasks, like building a webhook to Slack notification workflow, triaging failed executions and cleaning up unused workflows.
+
+                Every request made to n8n is logged to the client, with its method, path, status and duration. Use logging/setLevel to choose how much is logged.
+
+                Before activating a workflow, you can check the instance's license info to make sure the active workflow limit won't be exceeded.
+
                 If the user requests you to update or run a workflow (or assign a tag), you might need to either fetch all workflows first to see what workflows are possible.
-                ".to_string()),
+                ".to_string();
+        if self.access.is_read_only() {
+            instructions.push_str(
+                "\nOnly the tools that retrieve data are available to this client, so nothing in n8n can be created, changed, deleted or run.\n",
+            );
+        }
+
+        ServerInfo {
+            protocol_version: ProtocolVersion::V_2024_11_05,
+            capabilities,
+            server_info: Implementation::from_build_env(),
+            instructions: Some(instructions),
+        }
+    }
+
+    async fn list_tools(
+        &self,
+        _request: PaginatedRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        Ok(ListToolsResult {
+            next_cursor: None,
+            tools: Self::tools()
+                .into_iter()
+                .filter(|tool| self.access.allows(&tool.name))
+                .collect(),
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        if self.in_flight.is_closed() {
+            return Err(McpError::internal_error(
+                "The server is shutting down",
+                None,
+            ));
+        }
+
+        if let Some(denial) = self.access.denial(&request.name) {
+            return Err(McpError::invalid_request(denial, None));
+        }
+
+        let ct = context.ct.clone();
+        let tool_context = ToolCallContext::new(self, request, context.clone());
+
+        let call = async move {
+            tokio::select! {
+                // Tools report progress against the request they're handling
+                result = progress::scope(context, Self::tool_box().call(tool_context)) => result,
+                // Dropping the tool call also drops (and so aborts) any request it has in flight to n8n
+                _ = ct.cancelled() => {
+                    tracing::debug!("Tool call cancelled by the client");
+                    Err(McpError::internal_error("Request cancelled by the client", None))
+                }
+            }
+        };
+
+        self.in_flight.track_future(call).await
+    }
+
+    async fn complete(
+        &self,
+        request: CompleteRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<CompleteResult, McpError> {
+        let Some(kind) = CompletionKind::for_argument(&request.r#ref, &request.argument.name)
+        else {
+            return Ok(CompleteResult {
+                completion: CompletionInfo {
+                    values: Vec::new(),
+                    total: None,
+                    has_more: None,
+                },
+            });
+        };
+
+        let candidates = self.completion_candidates(kind).await?;
+
+        Ok(CompleteResult {
+            completion: completion::complete(&candidates, &request.argument.value),
+        })
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: PaginatedRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, McpError> {
+        Ok(ListPromptsResult {
+            next_cursor: None,
+            prompts: prompts::list(),
+        })
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, McpError> {
+        prompts::get(&request.name, request.arguments)
+    }
+
+    async fn list_resources(
+        &self,
+        request: PaginatedRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        let cursor = request.and_then(|request| request.cursor);
+
+        // Recent executions are listed alongside the first page of workflows
+        let mut resources = if cursor.is_none() {
+            self.recent_execution_resources().await?
+        } else {
+            Vec::new()
+        };
+
+        // MCP cursors are passed straight through to n8n, so each resources page is one page of workflows
+        let query = Query::new()
+            .set("limit", 100)
+            .set_optional("cursor", cursor);
+
+        let res: Paginated<serde_json::Value> =
+            self.fetch_page(Endpoint::Workflows, &query).await?;
+
+        let workflows = res.data.iter().filter_map(|workflow| {
+            let id = workflow["id"].as_str()?;
+            let name = workflow["name"].as_str().unwrap_or(id);
+
+            let tags: Vec<&str> = workflow["tags"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|tag| tag["name"].as_str())
+                .collect();
+
+            let mut description = if workflow["active"].as_bool() == Some(true) {
+                "Active n8n workflow".to_string()
+            } else {
+                "Inactive n8n workflow".to_string()
+            };
+            if !tags.is_empty() {
+                description.push_str(&format!(" tagged {}", tags.join(", ")));
+            }
+
+            let mut resource = RawResource::new(ResourceUri::Workflow(id.into()).to_string(), name);
+            resource.description = Some(description);
+            resource.mime_type = Some("application/json".to_string());
+
+            Some(resource.no_annotation())
+        });
+        resources.extend(workflows);
+
+        Ok(ListResourcesResult {
+            next_cursor: res.next_cursor,
+            resources,
+        })
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: PaginatedRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, McpError> {
+        Ok(ListResourceTemplatesResult {
+            next_cursor: None,
+            resource_templates: resources::templates(),
+        })
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        let Some(resource_uri) = ResourceUri::parse(&request.uri) else {
+            return Err(McpError::resource_not_found(
+                format!("Unknown resource: {}", request.uri),
+                None,
+            ));
+        };
+
+        let contents = match resource_uri {
+            ResourceUri::Workflow(workflow_id) => {
+                serde_json::to_value(self.fetch_workflow(&workflow_id).await?)
+                    .map_err(ServerError::from)?
+            }
+            ResourceUri::Execution(execution_id) => {
+                let url = self.url(Endpoint::Execution(&execution_id));
+
+                let res = self
+                    .client()
+                    .get(url)
+                    .query(&Query::new().set("includeData", true))
+                    .send_logged(&self.logger)
+                    .await
+                    .map_err(ServerError::from)?;
+
+                response::json(res).await?
+            }
+            ResourceUri::Tag(tag_id) => {
+                let url = self.url(Endpoint::Tag(&tag_id));
+
+                let res = self
+                    .client()
+                    .get(url)
+                    .send_logged(&self.logger)
+                    .await
+                    .map_err(ServerError::from)?;
+
+                response::json(res).await?
+            }
+        };
+
+        Ok(ReadResourceResult {
+            contents: vec![ResourceContents::TextResourceContents {
+                uri: request.uri,
+                mime_type: Some("application/json".to_string()),
+                text: serde_json::to_string_pretty(&contents).unwrap(),
+            }],
+        })
+    }
+
+    async fn set_level(
+        &self,
+        request: SetLevelRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        self.logger.set_level(request.level);
+        Ok(())
+    }
+
+    async fn subscribe(
+        &self,
+        request: SubscribeRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        if !self.features.resource_subscriptions {
+            return Err(McpError::invalid_request(
+                "Resource subscriptions are turned off on this server",
+                None,
+            ));
+        }
+
+        if ResourceUri::parse(&request.uri).is_none() {
+            return Err(McpError::resource_not_found(
+                format!("Unknown resource: {}", request.uri),
+                None,
+            ));
+        }
+
+        self.subscriptions.subscribe(request.uri);
+
+        if let Some(peer) = &self.peer
+            && self.subscriptions.start_watching()
+        {
+            let watcher = ExecutionWatcher {
+                n8n: self.n8n.clone(),
+                peer: peer.clone(),
+                subscriptions: Arc::downgrade(&self.subscriptions),
+                logger: self.logger.clone(),
+            };
+            tokio::spawn(watcher.run());
+        }
+
+        Ok(())
+    }
+
+    async fn unsubscribe(
+        &self,
+        request: UnsubscribeRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        self.subscriptions.unsubscribe(&request.uri);
+
+        Ok(())
+    }
+
+    fn get_peer(&self) -> Option<Peer<RoleServer>> {
+        self.peer.clone()
+    }
+
+    fn set_peer(&mut self, peer: Peer<RoleServer>) {
+        self.logger.set_peer(peer.clone());
+        self.peer = Some(peer);
+
+        if self.features.failure_watch.enabled {
+            let watcher = FailureWatcher {
+                n8n: self.n8n.clone(),
+                config: self.features.failure_watch.clone(),
+                session: Arc::downgrade(&self.subscriptions),
+                logger: self.logger.clone(),
+            };
+            tokio::spawn(watcher.run());
         }
     }
 }
 
 #[derive(Deserialize, Serialize, JsonSchema)]
 pub struct Id {
-    id: String,
+    id: TagId,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workflow_filters_are_sent_under_n8ns_names() {
+        let params: RetrieveAllWorkflowParams = serde_json::from_value(json!({
+            "projectId": "p1",
+            "excludePinnedData": true,
+            "limit": 10,
+        }))
+        .unwrap();
+
+        assert_eq!(
+            params.query(),
+            Query::new()
+                .set("projectId", "p1")
+                .set("excludePinnedData", true)
+                .set("limit", 10)
+        );
+    }
 }
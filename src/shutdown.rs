@@ -0,0 +1,46 @@
+use std::time::Duration;
+use tokio::signal::unix::{SignalKind, signal};
+use tokio_util::task::TaskTracker;
+
+/// How long in-flight tool calls get to finish before their sessions are closed anyway.
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+/// rmcp writes a tool call's response out after the call itself has returned, so give it a moment to do so.
+const FLUSH_GRACE: Duration = Duration::from_millis(250);
+
+/// Resolves once the process is asked to stop, with either SIGINT (Ctrl+C) or SIGTERM (e.g. from Docker or Kubernetes).
+pub async fn signal_received() -> std::io::Result<()> {
+    let mut interrupt = signal(SignalKind::interrupt())?;
+    let mut terminate = signal(SignalKind::terminate())?;
+
+    tokio::select! {
+        _ = interrupt.recv() => tracing::info!("Received SIGINT, shutting down"),
+        _ = terminate.recv() => tracing::info!("Received SIGTERM, shutting down"),
+    }
+
+    Ok(())
+}
+
+/// Stops new tool calls from starting, then waits for the ones already running to finish and their responses to be
+/// sent.
+pub async fn drain(in_flight: &TaskTracker) {
+    in_flight.close();
+
+    if !in_flight.is_empty() {
+        tracing::info!(
+            "Waiting for {} in-flight tool call(s) to finish",
+            in_flight.len()
+        );
+    }
+
+    if tokio::time::timeout(DRAIN_TIMEOUT, in_flight.wait())
+        .await
+        .is_err()
+    {
+        tracing::warn!(
+            "{} tool call(s) still running after {DRAIN_TIMEOUT:?}, closing sessions anyway",
+            in_flight.len()
+        );
+    }
+
+    tokio::time::sleep(FLUSH_GRACE).await;
+}
@@ -0,0 +1,173 @@
+use chrono::{NaiveDateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use crate::{backup, error::ServerError, id::WorkflowId, workflow::Workflow};
+
+/// How many snapshots are kept of each workflow. Older ones are removed as new ones are taken.
+const MAX_SNAPSHOTS: usize = 50;
+
+/// The format of the timestamp snapshot file names start with, which sorts them oldest first.
+const TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S%.3fZ";
+
+/// Why a snapshot was taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    /// The workflow was about to be updated.
+    Update,
+    /// The workflow was about to be deleted.
+    Delete,
+    /// The workflow was about to be archived.
+    Archive,
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Update => f.write_str("update"),
+            Self::Delete => f.write_str("delete"),
+            Self::Archive => f.write_str("archive"),
+        }
+    }
+}
+
+/// A saved copy of a workflow, as it was just before it was changed, deleted or archived.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+    /// 0 for the most recent snapshot, 1 for the one before it, and so on.
+    pub index: usize,
+    /// When the snapshot was taken, in RFC 3339 format.
+    pub taken_at: String,
+    /// What was about to happen to the workflow: `update`, `delete` or `archive`.
+    pub reason: String,
+    /// The workflow's name at the time.
+    pub name: Option<String>,
+    pub node_count: Option<usize>,
+    pub file: String,
+}
+
+/// Saves `workflow` (as fetched from n8n) as the newest snapshot of `workflow_id`, and removes the oldest if there are
+/// more than [`MAX_SNAPSHOTS`].
+///
+/// Each workflow's snapshots are kept in their own directory named by its ID, in files named by when they were taken
+/// and why, e.g. `AbC123/20261015T093000.000Z-update.json`.
+pub async fn save(
+    dir: &Path,
+    workflow_id: &WorkflowId,
+    workflow: &Value,
+    reason: Reason,
+) -> Result<PathBuf, ServerError> {
+    let workflow_dir = dir.join(&**workflow_id);
+    tokio::fs::create_dir_all(&workflow_dir)
+        .await
+        .map_err(|err| ServerError::file(&workflow_dir, err))?;
+
+    let timestamp = Utc::now().format(TIMESTAMP_FORMAT);
+    let path = workflow_dir.join(format!("{timestamp}-{reason}.json"));
+    backup::write_json(&path, workflow).await?;
+
+    let files = files(&workflow_dir).await?;
+    for file in files.iter().take(files.len().saturating_sub(MAX_SNAPSHOTS)) {
+        let old = workflow_dir.join(file);
+        tokio::fs::remove_file(&old)
+            .await
+            .map_err(|err| ServerError::file(&old, err))?;
+    }
+
+    Ok(path)
+}
+
+/// The snapshots of a workflow, most recent first. A workflow without any has none.
+pub async fn list(dir: &Path, workflow_id: &WorkflowId) -> Result<Vec<Snapshot>, ServerError> {
+    let workflow_dir = dir.join(&**workflow_id);
+    if !tokio::fs::try_exists(&workflow_dir).await.unwrap_or(false) {
+        return Ok(Vec::new());
+    }
+
+    let mut snapshots = Vec::new();
+    for (index, file) in files(&workflow_dir).await?.into_iter().rev().enumerate() {
+        // The summary is only for browsing, so a snapshot that can't be read is still listed
+        let workflow = backup::read_workflow(&workflow_dir.join(&file)).await.ok();
+        let (timestamp, reason) = file
+            .trim_end_matches(".json")
+            .split_once('-')
+            .unwrap_or_default();
+
+        snapshots.push(Snapshot {
+            index,
+            taken_at: NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT)
+                .map(|taken_at| taken_at.and_utc().to_rfc3339())
+                .unwrap_or_else(|_| timestamp.to_string()),
+            reason: reason.to_string(),
+            name: workflow.as_ref().map(|workflow| workflow.name.clone()),
+            node_count: workflow.as_ref().map(|workflow| workflow.nodes.len()),
+            file,
+        });
+    }
+
+    Ok(snapshots)
+}
+
+/// The IDs of every workflow with snapshots, including ones that have since been deleted.
+pub async fn workflow_ids(dir: &Path) -> Result<Vec<WorkflowId>, ServerError> {
+    if !tokio::fs::try_exists(dir).await.unwrap_or(false) {
+        return Ok(Vec::new());
+    }
+
+    let mut entries = tokio::fs::read_dir(dir)
+        .await
+        .map_err(|err| ServerError::file(dir, err))?;
+
+    let mut workflow_ids = Vec::new();
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|err| ServerError::file(dir, err))?
+    {
+        let is_dir = entry
+            .file_type()
+            .await
+            .is_ok_and(|file_type| file_type.is_dir());
+        if is_dir {
+            workflow_ids.push(entry.file_name().to_string_lossy().into_owned().into());
+        }
+    }
+    workflow_ids.sort();
+
+    Ok(workflow_ids)
+}
+
+pub async fn read(
+    dir: &Path,
+    workflow_id: &WorkflowId,
+    snapshot: &Snapshot,
+) -> Result<Workflow, ServerError> {
+    backup::read_workflow(&dir.join(&**workflow_id).join(&snapshot.file)).await
+}
+
+/// The snapshot files in a workflow's directory, oldest first.
+async fn files(workflow_dir: &Path) -> Result<Vec<String>, ServerError> {
+    let mut entries = tokio::fs::read_dir(workflow_dir)
+        .await
+        .map_err(|err| ServerError::file(workflow_dir, err))?;
+
+    let mut files = Vec::new();
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|err| ServerError::file(workflow_dir, err))?
+    {
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        if file_name.ends_with(".json") {
+            files.push(file_name);
+        }
+    }
+    files.sort();
+
+    Ok(files)
+}
@@ -0,0 +1,149 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::workflow::{Node, Trigger, Workflow};
+
+/// A summary of how big and complicated a workflow is.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Stats {
+    /// Not counting sticky notes, which are listed separately.
+    pub node_count: usize,
+    pub disabled_nodes: usize,
+    pub sticky_notes: usize,
+    /// How many nodes there are of each type.
+    pub node_types: BTreeMap<String, usize>,
+    pub triggers: Vec<Trigger>,
+    pub credentials: Vec<CredentialUse>,
+    pub connection_count: usize,
+    /// The most nodes in a row that data passes through, from a node with nothing going into it to the end of a
+    /// branch. Loops are only followed once.
+    pub depth: usize,
+}
+
+/// A credential a node uses.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialUse {
+    pub node: String,
+    #[serde(rename = "type")]
+    pub credential_type: String,
+    pub id: Value,
+    pub name: Value,
+}
+
+pub fn of(workflow: &Workflow) -> Stats {
+    let nodes: Vec<&Node> = workflow
+        .nodes
+        .iter()
+        .filter(|node| !node.is_sticky_note())
+        .collect();
+
+    let mut node_types = BTreeMap::new();
+    for node in &nodes {
+        *node_types.entry(node.kind.clone()).or_insert(0) += 1;
+    }
+
+    let triggers = nodes
+        .iter()
+        .filter_map(|node| {
+            Some(Trigger {
+                node: node.name.clone(),
+                node_type: node.kind.clone(),
+                kind: node.trigger_kind()?,
+            })
+        })
+        .collect();
+
+    let credentials = nodes
+        .iter()
+        .flat_map(|node| {
+            node.credentials
+                .iter()
+                .flatten()
+                .map(|(credential_type, credential)| CredentialUse {
+                    node: node.name.clone(),
+                    credential_type: credential_type.clone(),
+                    id: credential.get("id").cloned().unwrap_or(Value::Null),
+                    name: credential.get("name").cloned().unwrap_or(Value::Null),
+                })
+        })
+        .collect();
+
+    let connection_count = workflow
+        .connections
+        .values()
+        .flat_map(|outputs| outputs.values())
+        .flatten()
+        .flatten()
+        .map(Vec::len)
+        .sum();
+
+    Stats {
+        node_count: nodes.len(),
+        disabled_nodes: nodes
+            .iter()
+            .filter(|node| node.disabled == Some(true))
+            .count(),
+        sticky_notes: workflow.nodes.len() - nodes.len(),
+        node_types,
+        triggers,
+        credentials,
+        connection_count,
+        depth: depth(workflow),
+    }
+}
+
+/// The longest chain of `main` connections in the workflow, counted in nodes.
+fn depth(workflow: &Workflow) -> usize {
+    let mut next: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut has_input = HashSet::new();
+    for (source, outputs) in &workflow.connections {
+        for target in outputs
+            .get("main")
+            .into_iter()
+            .flatten()
+            .flatten()
+            .flatten()
+        {
+            next.entry(source.as_str())
+                .or_default()
+                .push(target.node.as_str());
+            has_input.insert(target.node.as_str());
+        }
+    }
+
+    let mut longest = HashMap::new();
+    workflow
+        .nodes
+        .iter()
+        .filter(|node| !node.is_sticky_note() && !has_input.contains(node.name.as_str()))
+        .map(|node| chain(&node.name, &next, &mut Vec::new(), &mut longest))
+        .max()
+        .unwrap_or(0)
+}
+
+/// The most nodes in a row starting at `node`, not going back into a node already on `path`.
+fn chain<'a>(
+    node: &'a str,
+    next: &HashMap<&'a str, Vec<&'a str>>,
+    path: &mut Vec<&'a str>,
+    longest: &mut HashMap<&'a str, usize>,
+) -> usize {
+    if let Some(length) = longest.get(node) {
+        return *length;
+    }
+
+    path.push(node);
+    let mut length = 1;
+    for target in next.get(node).into_iter().flatten() {
+        if !path.contains(target) {
+            length = length.max(1 + chain(target, next, path, longest));
+        }
+    }
+    path.pop();
+
+    longest.insert(node, length);
+    length
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{id::TagId, workflow::Workflow};
+
+/// A label workflows can be grouped by. Names are unique across the instance.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tag {
+    pub id: TagId,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+}
+
+/// Finds a tag by name, ignoring surrounding whitespace, which n8n trims off anyway.
+pub fn find<'a>(tags: &'a [Tag], name: &str) -> Option<&'a Tag> {
+    tags.iter().find(|tag| tag.name.trim() == name.trim())
+}
+
+/// The tags a workflow has, as returned with it by n8n.
+pub fn of_workflow(workflow: &Workflow) -> Vec<Tag> {
+    workflow
+        .extra
+        .get("tags")
+        .cloned()
+        .and_then(|tags| serde_json::from_value(tags).ok())
+        .unwrap_or_default()
+}
@@ -0,0 +1,109 @@
+use base64::prelude::*;
+use std::collections::HashSet;
+
+use crate::{error::ServerError, workflow::Node};
+
+/// The most items n8n's public API returns in a single page.
+pub const MAX_PAGE_SIZE: u8 = 250;
+
+/// IDs go straight into URL paths, so anything that could send the request to a different route is rejected.
+pub fn id(kind: &str, id: &str) -> Result<(), ServerError> {
+    if id.trim().is_empty() {
+        return Err(ServerError::validation(format!(
+            "The {kind} ID can't be empty"
+        )));
+    }
+
+    if !id
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(ServerError::validation(format!(
+            "'{id}' isn't a valid {kind} ID - IDs only contain letters, numbers, '-' and '_'"
+        )));
+    }
+
+    Ok(())
+}
+
+pub fn optional_id(kind: &str, id: Option<&str>) -> Result<(), ServerError> {
+    id.map_or(Ok(()), |id| self::id(kind, id))
+}
+
+pub fn limit(limit: u8) -> Result<(), ServerError> {
+    if limit == 0 || limit > MAX_PAGE_SIZE {
+        return Err(ServerError::validation(format!(
+            "limit must be between 1 and {MAX_PAGE_SIZE}, not {limit}"
+        )));
+    }
+
+    Ok(())
+}
+
+/// n8n's cursors are base64-encoded JSON, and only ever come from the `nextCursor` of a previous page.
+pub fn cursor(cursor: &str) -> Result<(), ServerError> {
+    let trimmed = cursor.trim_end_matches('=');
+    let decoded = BASE64_STANDARD_NO_PAD
+        .decode(trimmed)
+        .or_else(|_| BASE64_URL_SAFE_NO_PAD.decode(trimmed))
+        .ok()
+        .and_then(|decoded| serde_json::from_slice::<serde_json::Value>(&decoded).ok());
+
+    if decoded.is_none() {
+        return Err(ServerError::validation(format!(
+            "'{cursor}' isn't a cursor n8n gave out - use the nextCursor from the previous page, or leave it out to get the first page"
+        )));
+    }
+
+    Ok(())
+}
+
+pub fn not_empty(name: &str, value: &str) -> Result<(), ServerError> {
+    if value.trim().is_empty() {
+        return Err(ServerError::validation(format!("{name} can't be empty")));
+    }
+
+    Ok(())
+}
+
+/// Checks that every node has what n8n needs, and that no two share a name, reporting every problem at once.
+///
+/// n8n's own response to a malformed node rarely says which node is wrong, or what's wrong with it.
+pub fn nodes(nodes: &[Node]) -> Result<(), ServerError> {
+    let mut problems = Vec::new();
+    let mut names = HashSet::new();
+
+    for (i, node) in nodes.iter().enumerate() {
+        let node_label = if node.name.trim().is_empty() {
+            format!("node {}", i + 1)
+        } else {
+            format!("node {} ('{}')", i + 1, node.name)
+        };
+
+        let missing = [
+            ("name", node.name.trim().is_empty()),
+            ("type", node.kind.trim().is_empty()),
+            ("typeVersion", node.type_version.is_none()),
+            ("position", node.position.is_none()),
+            ("parameters", node.parameters.is_none()),
+        ];
+        for (field, _) in missing.iter().filter(|(_, missing)| *missing) {
+            problems.push(format!("{node_label} is missing {field}"));
+        }
+
+        if !node.name.trim().is_empty() && !names.insert(node.name.as_str()) {
+            problems.push(format!(
+                "{node_label} has the same name as an earlier node, but names must be unique"
+            ));
+        }
+    }
+
+    if !problems.is_empty() {
+        return Err(ServerError::validation(format!(
+            "The workflow's nodes aren't valid:\n- {}",
+            problems.join("\n- ")
+        )));
+    }
+
+    Ok(())
+}
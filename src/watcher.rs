@@ -0,0 +1,316 @@
+use rmcp::{
+    model::{LoggingLevel, ResourceUpdatedNotificationParam},
+    service::{Peer, RoleServer},
+};
+use serde_json::json;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        Arc, Mutex, Weak,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use crate::{
+    client::N8nClient,
+    config::FailureWatch,
+    endpoints::Endpoint,
+    execution::Execution,
+    id::{ExecutionId, WorkflowId},
+    logging::{RequestLogger, SendLogged},
+    page::Paginated,
+    query::Query,
+    resources::ResourceUri,
+    validate::MAX_PAGE_SIZE,
+    workflow::Workflow,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The resources a client has subscribed to during its session.
+#[derive(Default)]
+pub struct Subscriptions {
+    uris: Mutex<HashSet<String>>,
+    watching: AtomicBool,
+}
+
+impl Subscriptions {
+    pub fn subscribe(&self, uri: String) {
+        self.uris.lock().unwrap().insert(uri);
+    }
+
+    pub fn unsubscribe(&self, uri: &str) {
+        self.uris.lock().unwrap().remove(uri);
+    }
+
+    fn uris(&self) -> Vec<String> {
+        self.uris.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Returns true the first time it's called, so only one watcher is started per session.
+    pub fn start_watching(&self) -> bool {
+        !self.watching.swap(true, Ordering::SeqCst)
+    }
+}
+
+/// Polls n8n for new executions and for changes to subscribed executions, notifying the client of both.
+///
+/// The watcher only holds a weak reference to the session's subscriptions, so it stops once the session ends.
+pub struct ExecutionWatcher {
+    pub n8n: N8nClient,
+    pub peer: Peer<RoleServer>,
+    pub subscriptions: Weak<Subscriptions>,
+    pub logger: Arc<RequestLogger>,
+}
+
+impl ExecutionWatcher {
+    pub async fn run(self) {
+        let mut latest_execution_id = None;
+        let mut statuses: HashMap<String, String> = HashMap::new();
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            let Some(subscriptions) = self.subscriptions.upgrade() else {
+                tracing::debug!("session ended, stopping execution watcher");
+                return;
+            };
+
+            if let Some(newest) = self.fetch_newest_execution_id().await {
+                if latest_execution_id.is_some_and(|latest| newest > latest)
+                    && self.peer.notify_resource_list_changed().await.is_err()
+                {
+                    return;
+                }
+                latest_execution_id = Some(newest);
+            }
+
+            for uri in subscriptions.uris() {
+                let Some(ResourceUri::Execution(execution_id)) = ResourceUri::parse(&uri) else {
+                    continue;
+                };
+                let Some(status) = self.fetch_execution_status(&execution_id).await else {
+                    continue;
+                };
+
+                let changed = statuses
+                    .insert(uri.clone(), status.clone())
+                    .is_some_and(|previous| previous != status);
+
+                if changed
+                    && self
+                        .peer
+                        .notify_resource_updated(ResourceUpdatedNotificationParam { uri })
+                        .await
+                        .is_err()
+                {
+                    return;
+                }
+            }
+        }
+    }
+
+    async fn fetch_newest_execution_id(&self) -> Option<u64> {
+        let url = Endpoint::Executions.url(&self.n8n.base_url());
+
+        let res = self
+            .n8n
+            .http()
+            .get(url)
+            .query(&Query::new().set("limit", 1))
+            .send_logged(&self.logger)
+            .await
+            .ok()?
+            .json::<serde_json::Value>()
+            .await
+            .ok()?;
+
+        let id = &res["data"][0]["id"];
+        id.as_u64().or_else(|| id.as_str()?.parse().ok())
+    }
+
+    async fn fetch_execution_status(&self, execution_id: &ExecutionId) -> Option<String> {
+        let url = Endpoint::Execution(execution_id).url(&self.n8n.base_url());
+
+        let execution = self
+            .n8n
+            .http()
+            .get(url)
+            .send_logged(&self.logger)
+            .await
+            .ok()?
+            .json::<serde_json::Value>()
+            .await
+            .ok()?;
+
+        // Older n8n versions only report whether an execution has finished
+        match execution["status"].as_str() {
+            Some(status) => Some(status.to_string()),
+            None => Some(execution["finished"].as_bool()?.to_string()),
+        }
+    }
+}
+
+/// Polls n8n for executions that have failed since the last check, and sends the client an error log message about
+/// each one in a watched workflow. Failures from before the watcher started aren't reported.
+///
+/// Like [`ExecutionWatcher`], it only holds a weak reference to the session's subscriptions, so it stops once the
+/// session ends.
+pub struct FailureWatcher {
+    pub n8n: N8nClient,
+    pub config: FailureWatch,
+    pub session: Weak<Subscriptions>,
+    pub logger: Arc<RequestLogger>,
+}
+
+impl FailureWatcher {
+    pub async fn run(self) {
+        let mut last_seen = None;
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(self.config.interval_secs.max(1)));
+
+        loop {
+            interval.tick().await;
+
+            if self.session.strong_count() == 0 {
+                tracing::debug!("session ended, stopping failure watcher");
+                return;
+            }
+
+            let Some(failures) = self.fetch_failures().await else {
+                continue;
+            };
+            let newest = failures.iter().filter_map(number).max().unwrap_or(0);
+
+            // The first check only finds out where to start from
+            let Some(previous) = last_seen else {
+                last_seen = Some(newest);
+                continue;
+            };
+            last_seen = Some(previous.max(newest));
+
+            let new_failures: Vec<&Execution> = failures
+                .iter()
+                .rev()
+                .filter(|execution| number(execution).is_some_and(|id| id > previous))
+                .collect();
+            if new_failures.is_empty() {
+                continue;
+            }
+
+            let watched = self.watched_workflows().await;
+            for failure in new_failures {
+                let is_watched = match (&watched, &failure.workflow_id) {
+                    (None, _) => true,
+                    (Some(watched), Some(workflow_id)) => watched.contains(workflow_id),
+                    (Some(_), None) => false,
+                };
+                if is_watched {
+                    self.report(failure).await;
+                }
+            }
+        }
+    }
+
+    /// The most recent failed executions, newest first.
+    async fn fetch_failures(&self) -> Option<Vec<Execution>> {
+        let url = Endpoint::Executions.url(&self.n8n.base_url());
+
+        let res = self
+            .n8n
+            .http()
+            .get(url)
+            .query(&Query::new().set("status", "error").set("limit", 50))
+            .send_logged(&self.logger)
+            .await
+            .ok()?
+            .json::<Paginated<Execution>>()
+            .await
+            .ok()?;
+
+        Some(res.data)
+    }
+
+    /// The IDs of the workflows to report failures of, or `None` for every workflow.
+    async fn watched_workflows(&self) -> Option<HashSet<WorkflowId>> {
+        if self.config.workflows.is_empty() && self.config.tags.is_empty() {
+            return None;
+        }
+
+        let mut watched: HashSet<WorkflowId> = self.config.workflows.iter().cloned().collect();
+        for tag in &self.config.tags {
+            let url = Endpoint::Workflows.url(&self.n8n.base_url());
+            let query = Query::new().set("tags", tag).set("limit", MAX_PAGE_SIZE);
+
+            let Some(res) = async {
+                self.n8n
+                    .http()
+                    .get(url)
+                    .query(&query)
+                    .send_logged(&self.logger)
+                    .await
+                    .ok()?
+                    .json::<Paginated<Workflow>>()
+                    .await
+                    .ok()
+            }
+            .await
+            else {
+                tracing::debug!("could not list the workflows tagged {tag}");
+                continue;
+            };
+
+            watched.extend(res.data.into_iter().filter_map(|workflow| workflow.id));
+        }
+
+        Some(watched)
+    }
+
+    async fn report(&self, failure: &Execution) {
+        // The error is only in the execution's data
+        let url = Endpoint::Execution(&failure.id).url(&self.n8n.base_url());
+        let execution = async {
+            self.n8n
+                .http()
+                .get(url)
+                .query(&Query::new().set("includeData", true))
+                .send_logged(&self.logger)
+                .await
+                .ok()?
+                .json::<Execution>()
+                .await
+                .ok()
+        }
+        .await
+        .unwrap_or_else(|| failure.clone());
+
+        let workflow_name = execution
+            .extra
+            .get("workflowData")
+            .and_then(|workflow| workflow["name"].as_str());
+        let message = match (workflow_name, &execution.workflow_id) {
+            (Some(name), _) => format!("Workflow '{name}' failed"),
+            (None, Some(workflow_id)) => format!("Workflow {workflow_id} failed"),
+            (None, None) => "A workflow failed".to_string(),
+        };
+
+        self.logger
+            .message(
+                "n8n-failures",
+                LoggingLevel::Error,
+                json!({
+                    "message": message,
+                    "workflowName": workflow_name,
+                    "execution": execution.summary(),
+                }),
+            )
+            .await;
+    }
+}
+
+/// An execution's ID as a number, which goes up with each execution.
+fn number(execution: &Execution) -> Option<u64> {
+    execution.id.parse().ok()
+}
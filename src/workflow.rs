@@ -0,0 +1,1048 @@
+use rmcp::schemars::{self, JsonSchema};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    sync::LazyLock,
+};
+
+use crate::id::WorkflowId;
+
+/// The JSON Schema of n8n's workflow format, compiled on first use.
+static SCHEMA: LazyLock<jsonschema::Validator> = LazyLock::new(|| {
+    let schema = serde_json::from_str(include_str!("../schemas/workflow.schema.json"))
+        .expect("the bundled workflow schema is valid JSON");
+    jsonschema::validator_for(&schema).expect("the bundled workflow schema is a valid JSON Schema")
+});
+
+/// An n8n workflow, as returned by the API.
+///
+/// Only the parts the server works with are typed. Everything else (`active`, `tags`, timestamps and whatever newer
+/// versions of n8n add) is kept in `extra`, so nothing is lost on the way through.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Workflow {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<WorkflowId>,
+    pub name: String,
+    #[serde(default)]
+    pub nodes: Vec<Node>,
+    #[serde(default)]
+    pub connections: Connections,
+    #[serde(default)]
+    pub settings: Map<String, Value>,
+    #[serde(default)]
+    pub static_data: Value,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Workflow {
+    /// The body of a `PUT` to update the workflow. n8n rejects read-only fields (id, active, tags, timestamps...) on
+    /// update, so only the writable parts are included.
+    pub fn update_body(&self) -> Value {
+        json!({
+            "name": self.name,
+            "nodes": self.nodes,
+            "connections": self.connections,
+            "settings": self.settings,
+            "staticData": self.static_data,
+        })
+    }
+
+    /// Like [`Self::update_body`], but with the workflow's pinned data, for the updates that change it. Other updates
+    /// leave it out, so they work with n8n versions whose API doesn't take it.
+    pub fn update_body_with_pin_data(&self) -> Value {
+        let mut body = self.update_body();
+        body["pinData"] = json!(self.pin_data());
+        body
+    }
+
+    /// The test data pinned to the workflow's nodes, keyed by node name.
+    pub fn pin_data(&self) -> Map<String, Value> {
+        match self.extra.get("pinData") {
+            Some(Value::Object(pin_data)) => pin_data.clone(),
+            _ => Map::new(),
+        }
+    }
+
+    pub fn set_pin_data(&mut self, pin_data: Map<String, Value>) {
+        self.extra
+            .insert("pinData".to_string(), Value::Object(pin_data));
+    }
+
+    pub fn node(&self, name: &str) -> Option<&Node> {
+        self.nodes.iter().find(|node| node.name == name)
+    }
+
+    pub fn node_mut(&mut self, name: &str) -> Option<&mut Node> {
+        self.nodes.iter_mut().find(|node| node.name == name)
+    }
+
+    pub fn node_names(&self) -> Vec<&str> {
+        self.nodes.iter().map(|node| node.name.as_str()).collect()
+    }
+
+    /// The ID of the workflow that runs when this one fails, if one is set.
+    pub fn error_workflow(&self) -> Option<&str> {
+        self.settings
+            .get("errorWorkflow")
+            .and_then(Value::as_str)
+            .filter(|error_workflow| !error_workflow.is_empty())
+    }
+
+    /// How long the workflow can run for before n8n stops it, if it has a timeout set.
+    pub fn execution_timeout(&self) -> Option<u64> {
+        self.settings
+            .get("executionTimeout")
+            .and_then(Value::as_i64)
+            .filter(|timeout| *timeout > 0)
+            .map(|timeout| timeout as u64)
+    }
+
+    /// Whether this is an error workflow itself, i.e. it's started by an Error Trigger when another workflow fails.
+    pub fn is_error_workflow(&self) -> bool {
+        self.nodes
+            .iter()
+            .any(|node| node.kind == ERROR_TRIGGER_TYPE)
+    }
+
+    /// Adds `node`, wired from output `output` of `after` and/or into `before`, which have to be nodes of the
+    /// workflow. When `after` is already connected to `before`, the node takes the place of that connection. Returns
+    /// what was changed besides adding the node.
+    ///
+    /// A node without a position is put to the right of `after` (or left of `before`), and the nodes from `before`
+    /// on are moved right if there isn't room for it between them.
+    pub fn insert_node(
+        &mut self,
+        mut node: Node,
+        after: Option<&str>,
+        output: usize,
+        before: Option<&str>,
+    ) -> Vec<String> {
+        let mut changes = Vec::new();
+
+        let mut links = match (after, before) {
+            (Some(after), Some(before)) => disconnect(&mut self.connections, after, None, before),
+            _ => Vec::new(),
+        };
+        if links.is_empty() {
+            links.push((output, 0));
+        } else {
+            changes.push(format!(
+                "Replaced the connection from '{}' to '{}'",
+                after.unwrap_or_default(),
+                before.unwrap_or_default()
+            ));
+        }
+        for &(output, input) in &links {
+            if let Some(after) = after
+                && connect(&mut self.connections, after, output, &node.name, 0)
+            {
+                changes.push(format!(
+                    "Connected output {output} of '{after}' to '{}'",
+                    node.name
+                ));
+            }
+            if let Some(before) = before
+                && connect(&mut self.connections, &node.name, 0, before, input)
+            {
+                changes.push(format!(
+                    "Connected '{}' to input {input} of '{before}'",
+                    node.name
+                ));
+            }
+        }
+
+        if node.position.is_none() {
+            let position = |name: Option<&str>| self.node(name?)?.position;
+            let [x, y] = match (position(after), position(before)) {
+                (Some([after_x, y]), before_position) => {
+                    let x = after_x + LAYOUT_SPACING[0];
+                    let room = before_position.map(|[before_x, _]| before_x - x);
+                    if let Some(room) = room.filter(|room| *room < LAYOUT_SPACING[0]) {
+                        let moved = downstream(&self.connections, before.unwrap_or_default());
+                        for node in &mut self.nodes {
+                            if let (true, Some(position)) =
+                                (moved.contains(&node.name), &mut node.position)
+                            {
+                                position[0] += LAYOUT_SPACING[0] - room;
+                            }
+                        }
+                        changes.push(format!("Moved {} node(s) right to make room", moved.len()));
+                    }
+                    [x, y]
+                }
+                (None, Some([before_x, y])) => [before_x - LAYOUT_SPACING[0], y],
+                (None, None) => {
+                    let rightmost = self
+                        .nodes
+                        .iter()
+                        .filter_map(|node| node.position)
+                        .map(|[x, _]| x)
+                        .reduce(f64::max);
+                    match rightmost {
+                        Some(x) => [x + LAYOUT_SPACING[0], LAYOUT_ORIGIN[1]],
+                        None => LAYOUT_ORIGIN,
+                    }
+                }
+            };
+
+            let mut y = y;
+            while self
+                .nodes
+                .iter()
+                .filter_map(|node| node.position)
+                .any(|position| position == [x, y])
+            {
+                y += LAYOUT_SPACING[1];
+            }
+            node.position = Some([x, y]);
+        }
+
+        self.nodes.push(node);
+
+        changes
+    }
+
+    /// Removes the node called `name` and every connection to and from it. The nodes that fed into it are then
+    /// connected to the nodes it fed into, so the rest of the workflow still runs in the same order - unless that's
+    /// ambiguous, because more than one of its outputs (e.g. an If node's branches) or inputs (e.g. a Merge node's)
+    /// was in use. Returns what was changed besides removing the node.
+    pub fn remove_node(&mut self, name: &str) -> Vec<String> {
+        let mut changes = Vec::new();
+
+        self.nodes.retain(|node| node.name != name);
+
+        let outgoing: Vec<(usize, Connection)> = self
+            .connections
+            .remove(name)
+            .and_then(|mut outputs| outputs.remove("main"))
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .flat_map(|(output, targets)| {
+                targets
+                    .into_iter()
+                    .flatten()
+                    .map(move |target| (output, target))
+            })
+            .collect();
+
+        // Each `main` connection into the node, as the node it comes from, that node's output, and the input it goes into
+        let mut incoming: Vec<(String, usize, u32)> = Vec::new();
+        for (source, outputs) in &mut self.connections {
+            for (connection_type, outputs) in outputs {
+                for (output, targets) in outputs.iter_mut().enumerate() {
+                    for targets in targets.iter_mut() {
+                        targets.retain(|connection| {
+                            if connection.node != name {
+                                return true;
+                            }
+                            if connection_type == "main" {
+                                incoming.push((source.clone(), output, connection.index));
+                            }
+                            false
+                        });
+                    }
+                }
+            }
+        }
+
+        if incoming.is_empty() || outgoing.is_empty() {
+            return changes;
+        }
+
+        let outputs: HashSet<usize> = outgoing.iter().map(|(output, _)| *output).collect();
+        let inputs: HashSet<u32> = incoming.iter().map(|(_, _, input)| *input).collect();
+        if outputs.len() > 1 || inputs.len() > 1 {
+            let side = if outputs.len() > 1 { "output" } else { "input" };
+            changes.push(format!(
+                "The nodes before '{name}' weren't connected to the nodes after it, since it had more than one {side} in use and it isn't clear which should go where"
+            ));
+            return changes;
+        }
+
+        for (source, output, _) in &incoming {
+            for (_, target) in &outgoing {
+                if connect(
+                    &mut self.connections,
+                    source,
+                    *output,
+                    &target.node,
+                    target.index,
+                ) {
+                    changes.push(format!(
+                        "Connected output {output} of '{source}' to input {} of '{}'",
+                        target.index, target.node
+                    ));
+                }
+            }
+        }
+
+        changes
+    }
+
+    /// Puts `node` in place of the node called `name`: at its position, with its connections, and with expressions in
+    /// other nodes that refer to it by name (e.g. `$('Webhook').item.json`) pointing at the new node instead. Returns
+    /// what was changed besides swapping the node.
+    pub fn replace_node(&mut self, name: &str, mut node: Node) -> Vec<String> {
+        let mut changes = Vec::new();
+
+        let Some(index) = self.nodes.iter().position(|existing| existing.name == name) else {
+            return changes;
+        };
+        if node.position.is_none() {
+            node.position = self.nodes[index].position;
+        }
+        let new_name = node.name.clone();
+        self.nodes[index] = node;
+
+        if new_name == name {
+            return changes;
+        }
+
+        if let Some(outputs) = self.connections.remove(name) {
+            self.connections.insert(new_name.clone(), outputs);
+        }
+        for outputs in self.connections.values_mut() {
+            for connection in outputs.values_mut().flatten().flatten().flatten() {
+                if connection.node == name {
+                    connection.node = new_name.clone();
+                }
+            }
+        }
+
+        for node in &mut self.nodes {
+            if let Some(parameters) = &mut node.parameters
+                && rename_references(parameters, name, &new_name)
+            {
+                changes.push(format!(
+                    "Changed the expressions in '{}' that referred to '{name}' to refer to '{new_name}'",
+                    node.name
+                ));
+            }
+        }
+
+        changes
+    }
+}
+
+/// The ways an expression refers to another node by name, as what comes before and after the name.
+const NODE_REFERENCES: &[(&str, &str)] = &[
+    ("$('", "')"),
+    ("$(\"", "\")"),
+    ("$node['", "']"),
+    ("$node[\"", "\"]"),
+];
+
+/// Changes expressions referring to the node called `old` by name to refer to `new`. Returns whether any did.
+fn rename_references(parameters: &mut Map<String, Value>, old: &str, new: &str) -> bool {
+    fn rename(value: &mut Value, old: &str, new: &str) -> bool {
+        match value {
+            Value::String(text) if text.starts_with('=') => {
+                let mut renamed = text.clone();
+                for (before, after) in NODE_REFERENCES {
+                    renamed = renamed.replace(
+                        &format!("{before}{old}{after}"),
+                        &format!("{before}{new}{after}"),
+                    );
+                }
+                let changed = renamed != *text;
+                *text = renamed;
+                changed
+            }
+            Value::Array(values) => values
+                .iter_mut()
+                .fold(false, |changed, value| rename(value, old, new) | changed),
+            Value::Object(values) => values
+                .values_mut()
+                .fold(false, |changed, value| rename(value, old, new) | changed),
+            _ => false,
+        }
+    }
+
+    parameters
+        .values_mut()
+        .fold(false, |changed, value| rename(value, old, new) | changed)
+}
+
+/// A single step of a workflow.
+///
+/// n8n needs every node to have a name, type, type version, position and parameters, but they're all allowed to be
+/// missing here, so that nodes written by an agent can be checked with [`validate::nodes`](crate::validate::nodes)
+/// and each problem pointed out, rather than failing to parse.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Node {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Unique within the workflow, and what connections refer to the node by.
+    #[serde(default)]
+    pub name: String,
+    /// e.g. `n8n-nodes-base.webhook`.
+    #[serde(default, rename = "type")]
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_version: Option<f64>,
+    /// Where the node is drawn in the editor, as `[x, y]`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<[f64; 2]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<Map<String, Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credentials: Option<Map<String, Value>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Node types that start a workflow without having "Trigger" in their name.
+const TRIGGER_NODE_TYPES: &[&str] = &[
+    "n8n-nodes-base.webhook",
+    "n8n-nodes-base.cron",
+    "n8n-nodes-base.interval",
+    "n8n-nodes-base.start",
+];
+
+const STICKY_NOTE_TYPE: &str = "n8n-nodes-base.stickyNote";
+
+const ERROR_TRIGGER_TYPE: &str = "n8n-nodes-base.errorTrigger";
+
+impl Node {
+    /// Whether the node starts the workflow, e.g. a webhook, schedule or manual trigger.
+    pub fn is_trigger(&self) -> bool {
+        let short_type = self.kind.rsplit('.').next().unwrap_or_default();
+        short_type.to_lowercase().ends_with("trigger")
+            || TRIGGER_NODE_TYPES.contains(&self.kind.as_str())
+    }
+
+    pub fn trigger_kind(&self) -> Option<TriggerKind> {
+        if !self.is_trigger() {
+            return None;
+        }
+
+        let kind = match self.kind.as_str() {
+            "n8n-nodes-base.webhook" => TriggerKind::Webhook,
+            "n8n-nodes-base.scheduleTrigger"
+            | "n8n-nodes-base.cron"
+            | "n8n-nodes-base.interval" => TriggerKind::Schedule,
+            "n8n-nodes-base.manualTrigger" | "n8n-nodes-base.start" => TriggerKind::Manual,
+            _ => TriggerKind::Other,
+        };
+
+        Some(kind)
+    }
+
+    /// Sticky notes are only there to annotate the editor, and are never connected to anything.
+    pub fn is_sticky_note(&self) -> bool {
+        self.kind == STICKY_NOTE_TYPE
+    }
+}
+
+/// Merges `patch` into a node's parameters, the way a JSON merge patch (RFC 7386) does: objects are merged key by key,
+/// anything else replaces what was there, and `null` removes the parameter.
+pub fn merge_parameters(parameters: &mut Map<String, Value>, patch: Map<String, Value>) {
+    for (name, value) in patch {
+        match value {
+            Value::Null => {
+                parameters.remove(&name);
+            }
+            Value::Object(patch) => {
+                if let Some(Value::Object(existing)) = parameters.get_mut(&name) {
+                    merge_parameters(existing, patch);
+                } else {
+                    let mut merged = Map::new();
+                    merge_parameters(&mut merged, patch);
+                    parameters.insert(name, Value::Object(merged));
+                }
+            }
+            value => {
+                parameters.insert(name, value);
+            }
+        }
+    }
+}
+
+/// Problems with how a workflow's nodes are connected, which n8n accepts but which mean parts of the workflow will
+/// never run.
+///
+/// Connections of a type other than `main` go from an AI sub-node (e.g. a chat model) into the node that uses it, so
+/// a sub-node counts as reachable when the node it's attached to is.
+pub fn connection_warnings(nodes: &[Node], connections: &Connections) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let nodes: Vec<&Node> = nodes.iter().filter(|node| !node.is_sticky_note()).collect();
+    let names: HashSet<&str> = nodes.iter().map(|node| node.name.as_str()).collect();
+    let mut edges: HashMap<&str, Vec<&str>> = HashMap::new();
+
+    for (source, outputs) in connections {
+        if !names.contains(source.as_str()) {
+            warnings.push(format!(
+                "There are connections out of '{source}', but there's no node with that name"
+            ));
+            continue;
+        }
+
+        for (connection_type, outputs) in outputs {
+            for connection in outputs.iter().flatten().flatten() {
+                if !names.contains(connection.node.as_str()) {
+                    warnings.push(format!(
+                        "'{source}' is connected to '{}', but there's no node with that name",
+                        connection.node
+                    ));
+                } else if connection_type == "main" {
+                    edges.entry(source).or_default().push(&connection.node);
+                } else {
+                    edges.entry(&connection.node).or_default().push(source);
+                }
+            }
+        }
+    }
+
+    let triggers: Vec<&str> = nodes
+        .iter()
+        .filter(|node| node.is_trigger())
+        .map(|node| node.name.as_str())
+        .collect();
+
+    if triggers.is_empty() {
+        if !nodes.is_empty() {
+            warnings.push(
+                "There's no trigger node (e.g. a webhook, schedule or manual trigger), so nothing will start the workflow"
+                    .to_string(),
+            );
+        }
+        return warnings;
+    }
+
+    for trigger in &triggers {
+        if nodes.len() > 1 && !edges.contains_key(trigger) {
+            warnings.push(format!(
+                "The trigger '{trigger}' isn't connected to anything"
+            ));
+        }
+    }
+
+    let mut reached: HashSet<&str> = triggers.iter().copied().collect();
+    let mut queue: VecDeque<&str> = triggers.into_iter().collect();
+    while let Some(name) = queue.pop_front() {
+        for next in edges.get(name).into_iter().flatten() {
+            if reached.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+
+    for node in nodes {
+        if !reached.contains(node.name.as_str()) {
+            warnings.push(format!(
+                "'{}' can't be reached from a trigger node, so it will never run",
+                node.name
+            ));
+        }
+    }
+
+    warnings
+}
+
+/// Problems with the credentials nodes use, going by the credentials on the instance (as listed by its API).
+///
+/// Nodes refer to credentials by type, and by ID and name. n8n goes by the ID, and a node whose credential doesn't
+/// exist is only found out about when it runs.
+pub fn credential_warnings(nodes: &[Node], credentials: &[Value]) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    for node in nodes {
+        for (credential_type, reference) in node.credentials.iter().flatten() {
+            let id = reference["id"].as_str();
+            let name = reference["name"].as_str().unwrap_or_default();
+            let of_type = |credential: &&Value| credential["type"] == credential_type.as_str();
+
+            let by_id =
+                id.and_then(|id| credentials.iter().find(|credential| credential["id"] == id));
+            match by_id {
+                Some(credential) if !of_type(&credential) => warnings.push(format!(
+                    "'{}' uses credential {} as a {credential_type} credential, but it's a {} credential",
+                    node.name,
+                    id.unwrap_or_default(),
+                    credential["type"].as_str().unwrap_or("different kind of"),
+                )),
+                Some(credential) if credential["name"] != name => warnings.push(format!(
+                    "'{}' refers to credential {} as '{name}', but it's called '{}' on the instance",
+                    node.name,
+                    id.unwrap_or_default(),
+                    credential["name"].as_str().unwrap_or_default(),
+                )),
+                Some(_) => {}
+                None => {
+                    let by_name = credentials
+                        .iter()
+                        .filter(of_type)
+                        .find(|credential| credential["name"] == name);
+                    let warning = match by_name {
+                        Some(credential) => format!(
+                            "'{}' uses a {credential_type} credential that doesn't exist, but there's one called '{name}' with the ID {} - use that ID instead",
+                            node.name,
+                            credential["id"].as_str().unwrap_or_default(),
+                        ),
+                        None => format!(
+                            "'{}' uses a {credential_type} credential called '{name}' that doesn't exist on the instance",
+                            node.name,
+                        ),
+                    };
+                    warnings.push(warning);
+                }
+            }
+        }
+    }
+
+    warnings
+}
+
+/// Whether the node uses a credential (as listed by the instance's API). References are matched by ID, or by type and
+/// name when they don't have one.
+pub fn uses_credential(node: &Node, credential: &Value) -> bool {
+    node.credentials
+        .iter()
+        .flatten()
+        .any(|(credential_type, reference)| match reference.get("id") {
+            Some(id) => *id == credential["id"],
+            None => {
+                credential["type"] == credential_type.as_str()
+                    && reference["name"] == credential["name"]
+            }
+        })
+}
+
+/// A node's reference to a credential, by type and name.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialReference {
+    pub node: String,
+    #[serde(rename = "type")]
+    pub credential_type: String,
+    pub name: String,
+}
+
+/// Points the nodes' credentials at the credentials with the same type and name on another instance (as listed by its
+/// API), for copying a workflow there. Returns the references with no such credential, which are left with only
+/// their name, rather than an ID that could belong to an unrelated credential on the other instance.
+pub fn remap_credentials(nodes: &mut [Node], credentials: &[Value]) -> Vec<CredentialReference> {
+    let mut unmapped = Vec::new();
+
+    for node in nodes {
+        for (credential_type, reference) in node.credentials.iter_mut().flatten() {
+            let name = reference["name"].as_str().unwrap_or_default().to_string();
+            let credential = credentials.iter().find(|credential| {
+                credential["type"] == credential_type.as_str() && credential["name"] == name
+            });
+
+            match credential {
+                Some(credential) => reference["id"] = credential["id"].clone(),
+                None => {
+                    if let Some(reference) = reference.as_object_mut() {
+                        reference.remove("id");
+                    }
+                    unmapped.push(CredentialReference {
+                        node: node.name.clone(),
+                        credential_type: credential_type.clone(),
+                        name,
+                    });
+                }
+            }
+        }
+    }
+
+    unmapped
+}
+
+/// How a workflow can be started, so the agent knows whether to activate it and how to run it.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerSummary {
+    pub triggers: Vec<Trigger>,
+    /// n8n refuses to activate a workflow unless it has a trigger other than a manual one.
+    pub can_activate: bool,
+    /// The webhooks `run_workflow` can call, once the workflow is active.
+    pub webhooks: Vec<Webhook>,
+    pub guidance: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Trigger {
+    pub node: String,
+    #[serde(rename = "type")]
+    pub node_type: String,
+    pub kind: TriggerKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TriggerKind {
+    Webhook,
+    Schedule,
+    Manual,
+    /// Anything else that starts a workflow, e.g. app events, forms, chat or polling triggers.
+    Other,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Webhook {
+    pub node: String,
+    pub path: String,
+    pub method: String,
+}
+
+/// Works out how a workflow's enabled trigger nodes let it be started.
+pub fn trigger_summary(nodes: &[Node]) -> TriggerSummary {
+    let enabled_triggers = nodes
+        .iter()
+        .filter(|node| node.disabled != Some(true))
+        .filter_map(|node| Some((node, node.trigger_kind()?)));
+
+    let mut triggers = Vec::new();
+    let mut webhooks = Vec::new();
+    for (node, kind) in enabled_triggers {
+        if kind == TriggerKind::Webhook {
+            let parameter = |name: &str| node.parameters.as_ref()?.get(name)?.as_str();
+            webhooks.push(Webhook {
+                node: node.name.clone(),
+                // n8n falls back to the node's webhook ID when no path has been set
+                path: parameter("path")
+                    .or(node.extra.get("webhookId").and_then(Value::as_str))
+                    .unwrap_or_default()
+                    .to_string(),
+                method: parameter("httpMethod").unwrap_or("GET").to_string(),
+            });
+        }
+
+        triggers.push(Trigger {
+            node: node.name.clone(),
+            node_type: node.kind.clone(),
+            kind,
+        });
+    }
+
+    let can_activate = triggers
+        .iter()
+        .any(|trigger| trigger.kind != TriggerKind::Manual);
+
+    let guidance = if triggers.is_empty() {
+        "The workflow has no trigger node, so it can't be activated or run with run_workflow. Add a webhook trigger \
+         (n8n-nodes-base.webhook) to run it with run_workflow, or e.g. a schedule trigger to run it on a schedule."
+            .to_string()
+    } else if !can_activate {
+        "The workflow only has a manual trigger, so it can only be run from the n8n editor, and can't be activated. \
+         Add a webhook trigger (n8n-nodes-base.webhook) to run it with run_workflow."
+            .to_string()
+    } else if let Some(webhook) = webhooks.first() {
+        let data = if webhook.method == "GET" {
+            "without data"
+        } else {
+            "with data"
+        };
+        format!(
+            "Activate the workflow, then run it with run_workflow using the webhook path '{}' ({data}, as the webhook \
+             accepts {} requests).",
+            webhook.path, webhook.method
+        )
+    } else {
+        "Activate the workflow to have it run on its triggers. It can't be run with run_workflow, as it has no \
+         webhook trigger."
+            .to_string()
+    };
+
+    TriggerSummary {
+        triggers,
+        can_activate,
+        webhooks,
+        guidance,
+    }
+}
+
+/// The latest version of common node types, for nodes written without a `typeVersion`. n8n's public API can't list
+/// node types, so any other type is given version 1.
+const LATEST_TYPE_VERSIONS: &[(&str, f64)] = &[
+    ("n8n-nodes-base.webhook", 2.0),
+    ("n8n-nodes-base.scheduleTrigger", 1.2),
+    ("n8n-nodes-base.manualTrigger", 1.0),
+    ("n8n-nodes-base.executeWorkflowTrigger", 1.1),
+    ("n8n-nodes-base.errorTrigger", 1.0),
+    ("n8n-nodes-base.formTrigger", 2.2),
+    ("n8n-nodes-base.respondToWebhook", 1.1),
+    ("n8n-nodes-base.httpRequest", 4.2),
+    ("n8n-nodes-base.set", 3.4),
+    ("n8n-nodes-base.code", 2.0),
+    ("n8n-nodes-base.if", 2.2),
+    ("n8n-nodes-base.switch", 3.2),
+    ("n8n-nodes-base.filter", 2.2),
+    ("n8n-nodes-base.merge", 3.1),
+    ("n8n-nodes-base.splitInBatches", 3.0),
+    ("n8n-nodes-base.splitOut", 1.0),
+    ("n8n-nodes-base.aggregate", 1.0),
+    ("n8n-nodes-base.wait", 1.1),
+    ("n8n-nodes-base.noOp", 1.0),
+    ("n8n-nodes-base.executeWorkflow", 1.2),
+    ("n8n-nodes-base.stickyNote", 1.0),
+    ("n8n-nodes-base.slack", 2.3),
+    ("n8n-nodes-base.gmail", 2.1),
+    ("n8n-nodes-base.googleSheets", 4.5),
+    ("@n8n/n8n-nodes-langchain.agent", 1.7),
+    ("@n8n/n8n-nodes-langchain.chatTrigger", 1.1),
+    ("@n8n/n8n-nodes-langchain.lmChatOpenAi", 1.2),
+];
+
+/// Where nodes without a position are laid out from, and how far apart they're spaced, as `[x, y]`.
+const LAYOUT_ORIGIN: [f64; 2] = [250.0, 300.0];
+const LAYOUT_SPACING: [f64; 2] = [220.0, 200.0];
+
+/// Fills in what n8n needs but a sparse node definition leaves out: an empty `parameters` object, the latest known
+/// `typeVersion` of the node's type, and a position. Anything already set is kept. Returns what was filled in, one
+/// entry per node.
+///
+/// Nodes without a position are laid out left to right, a column per step from the start of the workflow, with
+/// sub-nodes (e.g. an agent's chat model) below the node they're attached to.
+pub fn fill_node_defaults(nodes: &mut [Node], connections: &Connections) -> Vec<String> {
+    let columns = layout_columns(nodes, connections);
+    let mut taken: HashSet<[i64; 2]> = nodes
+        .iter()
+        .filter_map(|node| node.position)
+        .map(|[x, y]| [x as i64, y as i64])
+        .collect();
+    let mut filled = Vec::new();
+
+    for (i, node) in nodes.iter_mut().enumerate() {
+        let mut defaults = Vec::new();
+
+        if node.parameters.is_none() {
+            node.parameters = Some(Map::new());
+            defaults.push("empty parameters".to_string());
+        }
+
+        if node.type_version.is_none() && !node.kind.trim().is_empty() {
+            let latest = LATEST_TYPE_VERSIONS
+                .iter()
+                .find(|(kind, _)| *kind == node.kind)
+                .map(|(_, version)| *version);
+            defaults.push(match latest {
+                Some(version) => format!("typeVersion {version}"),
+                None => format!(
+                    "typeVersion 1, as {} isn't a type with a known latest version",
+                    node.kind
+                ),
+            });
+            node.type_version = Some(latest.unwrap_or(1.0));
+        }
+
+        if node.position.is_none() {
+            let column = columns.get(&node.name).copied().unwrap_or_default();
+            let x = LAYOUT_ORIGIN[0] + column as f64 * LAYOUT_SPACING[0];
+            let mut y = LAYOUT_ORIGIN[1];
+            while !taken.insert([x as i64, y as i64]) {
+                y += LAYOUT_SPACING[1];
+            }
+            node.position = Some([x, y]);
+            defaults.push(format!("position [{x}, {y}]"));
+        }
+
+        if !defaults.is_empty() {
+            let node_label = if node.name.trim().is_empty() {
+                format!("Node {}", i + 1)
+            } else {
+                format!("'{}'", node.name)
+            };
+            filled.push(format!("{node_label}: {}", defaults.join(", ")));
+        }
+    }
+
+    filled
+}
+
+/// The column each node is laid out in: how many `main` connections it is from a node with none coming into it.
+/// Sub-nodes share the column of the node they're attached to.
+fn layout_columns(nodes: &[Node], connections: &Connections) -> HashMap<String, usize> {
+    let mut edges: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut has_inputs = HashSet::new();
+    let mut attached_to = HashMap::new();
+
+    for (source, outputs) in connections {
+        for (connection_type, outputs) in outputs {
+            for connection in outputs.iter().flatten().flatten() {
+                if connection_type == "main" {
+                    edges
+                        .entry(source.as_str())
+                        .or_default()
+                        .push(connection.node.as_str());
+                    has_inputs.insert(connection.node.as_str());
+                } else {
+                    attached_to.insert(source.as_str(), connection.node.as_str());
+                }
+            }
+        }
+    }
+
+    let mut columns: HashMap<&str, usize> = nodes
+        .iter()
+        .map(|node| node.name.as_str())
+        .filter(|name| !has_inputs.contains(name) && !attached_to.contains_key(name))
+        .map(|name| (name, 0))
+        .collect();
+    let mut queue: VecDeque<&str> = columns.keys().copied().collect();
+    while let Some(name) = queue.pop_front() {
+        let column = columns[name] + 1;
+        for next in edges.get(name).into_iter().flatten() {
+            if !columns.contains_key(next) {
+                columns.insert(next, column);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    for (sub_node, node) in attached_to {
+        if let Some(&column) = columns.get(node) {
+            columns.entry(sub_node).or_insert(column);
+        }
+    }
+
+    columns
+        .into_iter()
+        .map(|(name, column)| (name.to_string(), column))
+        .collect()
+}
+
+/// Connects output `output` of `from` to input `input` of `to`, returning whether they weren't already.
+pub fn connect(
+    connections: &mut Connections,
+    from: &str,
+    output: usize,
+    to: &str,
+    input: u32,
+) -> bool {
+    let outputs = connections
+        .entry(from.to_string())
+        .or_default()
+        .entry("main".to_string())
+        .or_default();
+    if outputs.len() <= output {
+        outputs.resize(output + 1, Some(Vec::new()));
+    }
+
+    let connection = Connection {
+        node: to.to_string(),
+        kind: "main".to_string(),
+        index: input,
+    };
+    let targets = outputs[output].get_or_insert_default();
+    if targets.contains(&connection) {
+        return false;
+    }
+    targets.push(connection);
+
+    true
+}
+
+/// Removes every `main` connection from `from` (or only from its output `output`) to `to`, returning the output of
+/// `from` and the input of `to` each one joined.
+pub fn disconnect(
+    connections: &mut Connections,
+    from: &str,
+    output: Option<usize>,
+    to: &str,
+) -> Vec<(usize, u32)> {
+    let mut removed = Vec::new();
+    let Some(outputs) = connections
+        .get_mut(from)
+        .and_then(|outputs| outputs.get_mut("main"))
+    else {
+        return removed;
+    };
+
+    for (i, targets) in outputs.iter_mut().enumerate() {
+        if output.is_some_and(|output| output != i) {
+            continue;
+        }
+        for targets in targets.iter_mut() {
+            targets.retain(|connection| {
+                let matches = connection.node == to;
+                if matches {
+                    removed.push((i, connection.index));
+                }
+                !matches
+            });
+        }
+    }
+
+    removed
+}
+
+/// `start` and every node after it: those its `main` outputs lead to, and the sub-nodes attached to any of them.
+fn downstream(connections: &Connections, start: &str) -> HashSet<String> {
+    let mut found = HashSet::from([start.to_string()]);
+    let mut queue = VecDeque::from([start.to_string()]);
+
+    while let Some(name) = queue.pop_front() {
+        for (source, outputs) in connections {
+            for (connection_type, outputs) in outputs {
+                for connection in outputs.iter().flatten().flatten() {
+                    let next = if connection_type == "main" && *source == name {
+                        &connection.node
+                    } else if connection_type != "main" && connection.node == name {
+                        source
+                    } else {
+                        continue;
+                    };
+                    if found.insert(next.clone()) {
+                        queue.push_back(next.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    found
+}
+
+/// Somewhere a workflow document doesn't match n8n's workflow format.
+#[derive(Debug, Serialize)]
+pub struct SchemaViolation {
+    /// A JSON pointer to the offending value, e.g. `/nodes/1/typeVersion`.
+    pub path: String,
+    pub message: String,
+}
+
+/// Checks a workflow document against the bundled JSON Schema of n8n's workflow format, returning every violation.
+pub fn schema_violations(document: &Value) -> Vec<SchemaViolation> {
+    SCHEMA
+        .iter_errors(document)
+        .map(|err| {
+            let path = err.instance_path().to_string();
+            SchemaViolation {
+                path: if path.is_empty() {
+                    "/".to_string()
+                } else {
+                    path
+                },
+                message: err.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// A workflow's connections, keyed by the name of the node they come out of.
+pub type Connections = BTreeMap<String, NodeOutputs>;
+
+/// A node's outgoing connections, keyed by connection type. That's `main` for ordinary data, or e.g. `ai_tool` for
+/// the sub-nodes of an AI agent. Each entry of the list is one of the node's outputs, in order.
+pub type NodeOutputs = BTreeMap<String, Vec<Option<Vec<Connection>>>>;
+
+/// Where one of a node's outputs goes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Connection {
+    /// The name of the node the connection goes into.
+    pub node: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Which of that node's inputs it goes into.
+    pub index: u32,
+}